@@ -0,0 +1,124 @@
+use color_eyre::Result;
+use color_eyre::eyre::Context;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use url::Url;
+
+use crate::cache::cache_key;
+use crate::fetch::Snapshot;
+
+/// Where a page in the archive came from, stored next to it as JSON.
+#[derive(Debug, Serialize)]
+struct Sidecar<'a> {
+    url: &'a str,
+    /// URL the page was served from, after following redirects
+    final_url: &'a str,
+    /// Fetch time in ISO 8601
+    fetched_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    archive_url: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    archived_at: Option<&'a str>,
+}
+
+/// Directory keeping the raw HTML of fetched pages, so their extraction can
+/// be run again later.
+///
+/// Each page is stored as `<name>.html` with a `<name>.json` sidecar, where
+/// the name is made from the URL to stay readable but unique.
+pub struct Archive {
+    dir: PathBuf,
+}
+
+impl Archive {
+    pub fn new(dir: &Path) -> Result<Self> {
+        fs::create_dir_all(dir)
+            .wrap_err_with(|| format!("could not create archive directory {:#?}", dir))?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+        })
+    }
+
+    /// Save the `html` fetched from `url`, returning the path of the HTML file.
+    pub fn save(
+        &self,
+        url: &str,
+        final_url: &str,
+        html: &str,
+        snapshot: Option<&Snapshot>,
+    ) -> Result<PathBuf> {
+        let name = file_name(url);
+        let path = self.dir.join(format!("{}.html", name));
+        fs::write(&path, html).wrap_err_with(|| format!("could not write {:#?}", path))?;
+
+        let sidecar = Sidecar {
+            url,
+            final_url,
+            fetched_at: unix_to_iso(
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |elapsed| elapsed.as_secs()),
+            ),
+            archive_url: snapshot.map(|s| s.url.as_str()),
+            archived_at: snapshot.map(|s| s.timestamp.as_str()),
+        };
+        let meta = self.dir.join(format!("{}.json", name));
+        fs::write(&meta, serde_json::to_vec_pretty(&sidecar)?)
+            .wrap_err_with(|| format!("could not write {:#?}", meta))?;
+        Ok(path)
+    }
+}
+
+/// File name for `url`: its host and path as a slug, plus a short hash.
+fn file_name(url: &str) -> String {
+    let (readable, key) = match Url::parse(url) {
+        Ok(parsed) => (
+            format!("{}{}", parsed.host_str().unwrap_or_default(), parsed.path()),
+            cache_key(&parsed),
+        ),
+        Err(_) => (url.to_string(), String::new()),
+    };
+    let mut slug = String::new();
+    for c in readable.chars() {
+        if c.is_ascii_alphanumeric() || c == '.' {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug: String = slug.trim_matches(['-', '.']).chars().take(80).collect();
+    // The hash tells apart URLs that only differ in their query
+    format!(
+        "{}-{}",
+        slug.trim_end_matches('-'),
+        &key[..key.len().min(8)]
+    )
+}
+
+/// Format a Unix timestamp in seconds as UTC ISO 8601.
+pub fn unix_to_iso(secs: u64) -> String {
+    let (days, rem) = (secs / 86400, secs % 86400);
+
+    // Civil date from days since the epoch, after Howard Hinnant's algorithm
+    let z = days + 719468;
+    let era = z / 146097;
+    let doe = z % 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
@@ -0,0 +1,112 @@
+use color_eyre::Result;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::mpsc;
+use std::thread;
+
+use crate::fetch::{self, Fetcher};
+use crate::{Input, get_html};
+
+/// Number of inputs loaded concurrently; per-host limits apply on top.
+const FETCH_THREADS: usize = 8;
+
+/// Normalize URL inputs with [`fetch::normalize_url`] and drop repeated
+/// inputs, keeping the first of each. Returns the remaining inputs and how
+/// many were dropped.
+pub fn dedupe(inputs: Vec<String>) -> (Vec<String>, usize) {
+    let total = inputs.len();
+    let mut seen = HashSet::new();
+    let unique: Vec<String> = inputs
+        .into_iter()
+        .map(normalize_input)
+        .filter(|input| seen.insert(input.clone()))
+        .collect();
+    let dropped = total - unique.len();
+    (unique, dropped)
+}
+
+/// Normalize an input that is a URL with [`fetch::normalize_url`].
+pub fn normalize_input(input: String) -> String {
+    // Existing files are read as they are, even if they look like URLs
+    if Path::new(&input).is_file() {
+        return input;
+    }
+    match fetch::try_parse_url(&input) {
+        Some(mut url) => {
+            fetch::normalize_url(&mut url);
+            url.to_string()
+        }
+        None => input,
+    }
+}
+
+/// Load `inputs` concurrently and hand them to `handle` one by one, in input order.
+///
+/// Loading happens on worker threads while `handle` runs on the calling
+/// thread, so it may use a (`!Send`) `Readability` instance.
+pub fn for_each_input(
+    inputs: Vec<String>,
+    fetcher: &Fetcher,
+    mut handle: impl FnMut(&str, Result<Input>),
+) {
+    // WASI has no threads, so inputs are loaded one at a time
+    if cfg!(target_family = "wasm") {
+        for input in inputs {
+            let result = get_html(Some(input.clone()), fetcher);
+            handle(&input, result);
+        }
+        return;
+    }
+
+    let names = inputs.clone();
+    let mut jobs = inputs.into_iter().enumerate();
+    let (job_tx, job_rx) = mpsc::channel();
+    let job_rx = Mutex::new(job_rx);
+    let (tx, rx) = mpsc::sync_channel(FETCH_THREADS);
+
+    // Only FETCH_THREADS inputs are handed out ahead of the one being
+    // handled, so a slow input cannot make the rest pile up in memory
+    let mut job_tx = Some(job_tx);
+    let mut hand_out = |job_tx: &mut Option<mpsc::Sender<_>>| match jobs.next() {
+        Some(job) => {
+            if let Some(job_tx) = job_tx {
+                let _ = job_tx.send(job);
+            }
+        }
+        // Closing the channel lets idle workers finish
+        None => *job_tx = None,
+    };
+    for _ in 0..FETCH_THREADS {
+        hand_out(&mut job_tx);
+    }
+
+    thread::scope(|scope| {
+        for _ in 0..FETCH_THREADS.min(names.len()) {
+            let tx = tx.clone();
+            let job_rx = &job_rx;
+            scope.spawn(move || {
+                loop {
+                    let job = job_rx.lock().unwrap().recv();
+                    let Ok((index, input)) = job else { break };
+                    if tx.send((index, get_html(Some(input), fetcher))).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(tx);
+
+        // Results arrive in completion order; release them in input order
+        let mut pending = BTreeMap::new();
+        let mut next = 0;
+        for (index, result) in rx {
+            pending.insert(index, result);
+            while let Some(result) = pending.remove(&next) {
+                handle(&names[next], result);
+                next += 1;
+                hand_out(&mut job_tx);
+            }
+        }
+    });
+}
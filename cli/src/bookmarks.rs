@@ -0,0 +1,202 @@
+use color_eyre::Result;
+use color_eyre::eyre::Context;
+use std::fs;
+use std::path::Path;
+
+use crate::archive::unix_to_iso;
+use crate::import::SavedItem;
+
+/// Which bookmarks to take from an export.
+#[derive(Default)]
+pub struct BookmarkFilter {
+    /// Only bookmarks in this folder or its subfolders
+    pub folder: Option<String>,
+    /// Only bookmarks with this tag
+    pub tag: Option<String>,
+}
+
+/// Read the bookmarks in a Netscape-format export, the `bookmarks.html` that
+/// Firefox, Chrome, Safari and most other browsers write.
+///
+/// Only `http` and `https` bookmarks are kept, in the order they appear.
+pub fn read(path: &Path, filter: &BookmarkFilter) -> Result<Vec<SavedItem>> {
+    let html = fs::read_to_string(path)
+        .wrap_err_with(|| format!("could not read bookmarks {:#?}", path))?;
+    Ok(parse(&html, filter))
+}
+
+/// Parse a Netscape-format export. Pocket's HTML export is a flat list of
+/// the same kind of links, so it is read here too.
+pub fn parse(html: &str, filter: &BookmarkFilter) -> Vec<SavedItem> {
+    let mut items = Vec::new();
+    // Folders enclosing the current position, and the name of the folder
+    // whose list starts at the next <DL>
+    let mut folders: Vec<String> = Vec::new();
+    let mut heading: Option<String> = None;
+
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start..];
+        let end = rest.find('>').map_or(rest.len(), |end| end + 1);
+        let tag = &rest[..end];
+        let body = &rest[end..];
+        let name = tag_name(tag);
+
+        if name.eq_ignore_ascii_case("h3") {
+            heading = Some(decode(text_until(body, "</")));
+        } else if name.eq_ignore_ascii_case("dl") {
+            folders.push(heading.take().unwrap_or_default());
+        } else if name.eq_ignore_ascii_case("/dl") {
+            folders.pop();
+        } else if name.eq_ignore_ascii_case("a")
+            && let Some(href) = attribute(tag, "href")
+        {
+            let href = decode(href);
+            let tags: Vec<String> = attribute(tag, "tags")
+                .map(decode)
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|tag| !tag.is_empty())
+                .map(str::to_string)
+                .collect();
+            let in_folder = filter.folder.as_ref().is_none_or(|wanted| {
+                folders
+                    .iter()
+                    .any(|folder| folder.trim().eq_ignore_ascii_case(wanted))
+            });
+            let tagged = filter
+                .tag
+                .as_ref()
+                .is_none_or(|wanted| tags.iter().any(|tag| tag.eq_ignore_ascii_case(wanted)));
+            if in_folder && tagged && (href.starts_with("http://") || href.starts_with("https://"))
+            {
+                // Browsers call it ADD_DATE, Pocket time_added; both are Unix seconds
+                let saved_at = attribute(tag, "add_date")
+                    .or_else(|| attribute(tag, "time_added"))
+                    .and_then(|secs| secs.trim().parse().ok())
+                    .map(unix_to_iso);
+                items.push(SavedItem {
+                    url: href,
+                    tags,
+                    saved_at,
+                });
+            }
+        }
+        rest = &rest[end..];
+    }
+    items
+}
+
+fn tag_name(tag: &str) -> &str {
+    let inner = tag.trim_start_matches('<');
+    let end = inner
+        .find(|c: char| c.is_whitespace() || c == '>')
+        .unwrap_or(inner.len());
+    &inner[..end]
+}
+
+fn text_until<'a>(text: &'a str, end: &str) -> &'a str {
+    &text[..text.find(end).unwrap_or(text.len())]
+}
+
+/// Value of the attribute `name` in the start tag `tag`, still HTML-escaped.
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let lower = tag.to_ascii_lowercase();
+    let mut from = 0;
+    while let Some(i) = lower[from..].find(name) {
+        let at = from + i;
+        from = at + name.len();
+        // Must be a whole attribute name, followed by =
+        if !lower[..at].ends_with(char::is_whitespace) {
+            continue;
+        }
+        let Some(value) = tag[from..].trim_start().strip_prefix('=') else {
+            continue;
+        };
+        let value = value.trim_start();
+        return Some(match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => text_until(&value[1..], &quote.to_string()),
+            _ => text_until(value, " ").trim_end_matches('>'),
+        });
+    }
+    None
+}
+
+fn decode(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<TITLE>Bookmarks</TITLE>
+<DL><p>
+    <DT><H3 ADD_DATE="1700000000">Work</H3>
+    <DL><p>
+        <DT><H3>Reading &amp; notes</H3>
+        <DL><p>
+            <DT><A HREF="https://a.example/?a=1&amp;b=2" ADD_DATE="1700000000" TAGS="rust, web">A</A>
+        </DL><p>
+        <DT><A HREF="https://b.example/" TAGS="later">B</A>
+    </DL><p>
+    <DT><A HREF="https://c.example/">C</A>
+    <DT><A HREF="javascript:void(0)">Bookmarklet</A>
+</DL><p>
+"#;
+
+    fn urls(filter: BookmarkFilter) -> Vec<String> {
+        parse(EXPORT, &filter)
+            .into_iter()
+            .map(|item| item.url)
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_attributes() {
+        let items = parse(EXPORT, &BookmarkFilter::default());
+        assert_eq!(
+            items
+                .iter()
+                .map(|item| item.url.as_str())
+                .collect::<Vec<_>>(),
+            [
+                "https://a.example/?a=1&b=2",
+                "https://b.example/",
+                "https://c.example/"
+            ]
+        );
+        assert_eq!(items[0].tags, ["rust", "web"]);
+        assert_eq!(items[0].saved_at.as_deref(), Some("2023-11-14T22:13:20Z"));
+        assert_eq!(items[2].saved_at, None);
+    }
+
+    #[test]
+    fn test_filter_by_nested_folder_and_tag() {
+        let folder = |name: &str| BookmarkFilter {
+            folder: Some(name.to_string()),
+            tag: None,
+        };
+        assert_eq!(
+            urls(folder("work")),
+            ["https://a.example/?a=1&b=2", "https://b.example/"]
+        );
+        assert_eq!(
+            urls(folder("Reading & notes")),
+            ["https://a.example/?a=1&b=2"]
+        );
+        assert!(urls(folder("Elsewhere")).is_empty());
+
+        let tagged = BookmarkFilter {
+            folder: None,
+            tag: Some("Later".to_string()),
+        };
+        assert_eq!(urls(tagged), ["https://b.example/"]);
+    }
+}
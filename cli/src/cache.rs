@@ -0,0 +1,73 @@
+use color_eyre::Result;
+use color_eyre::eyre::{Context, bail};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use url::Url;
+
+/// Validators stored next to a cached page body.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub url: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+/// On-disk cache of fetched pages, keyed by URL.
+///
+/// Each page is stored as `<key>.html` with its validators in `<key>.json`,
+/// so it can be revalidated with a conditional request on the next run.
+pub struct HttpCache {
+    dir: PathBuf,
+}
+
+impl HttpCache {
+    /// Open the cache under `$XDG_CACHE_HOME/readable`, falling back to `~/.cache/readable`.
+    pub fn open_default() -> Result<Self> {
+        let base = match std::env::var_os("XDG_CACHE_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => match std::env::var_os("HOME") {
+                Some(home) => PathBuf::from(home).join(".cache"),
+                None => bail!("could not determine cache directory: HOME is not set"),
+            },
+        };
+        let dir = base.join("readable");
+        fs::create_dir_all(&dir)
+            .wrap_err_with(|| format!("could not create cache directory {:#?}", dir))?;
+        Ok(Self { dir })
+    }
+
+    pub fn get(&self, url: &Url) -> Option<(CacheEntry, String)> {
+        let key = cache_key(url);
+        let meta = fs::read_to_string(self.dir.join(format!("{}.json", key))).ok()?;
+        let entry: CacheEntry = serde_json::from_str(&meta).ok()?;
+        // Guard against hash collisions
+        if entry.url != url.as_str() {
+            return None;
+        }
+        let body = fs::read_to_string(self.dir.join(format!("{}.html", key))).ok()?;
+        Some((entry, body))
+    }
+
+    pub fn put(&self, entry: &CacheEntry, body: &str) -> Result<()> {
+        let key = cache_key(&Url::parse(&entry.url)?);
+        fs::write(self.dir.join(format!("{}.html", key)), body)
+            .wrap_err("could not write cached page")?;
+        fs::write(
+            self.dir.join(format!("{}.json", key)),
+            serde_json::to_vec(entry)?,
+        )
+        .wrap_err("could not write cache entry")?;
+        Ok(())
+    }
+}
+
+/// Stable file name for a URL (64-bit FNV-1a, hex encoded).
+pub fn cache_key(url: &Url) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in url.as_str().bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
@@ -0,0 +1,53 @@
+use color_eyre::Result;
+use color_eyre::eyre::Context;
+use readability_js::{Readability, ReadabilityCheckOptions};
+
+use crate::fetch::Fetcher;
+use crate::get_html;
+
+#[derive(clap::Args)]
+pub struct CheckArgs {
+    #[arg(
+        help = "Input html file or URL (reads from stdin if not provided)",
+        value_hint = clap::ValueHint::AnyPath
+    )]
+    input: Option<String>,
+
+    #[arg(long, help = "Print the readerable score")]
+    score: bool,
+
+    #[arg(
+        long,
+        help = "Score a page needs to exceed to be readerable [default: 20]"
+    )]
+    min_score: Option<f64>,
+
+    #[arg(
+        long,
+        help = "Minimum paragraph length counted towards the score [default: 140]"
+    )]
+    min_content_length: Option<usize>,
+}
+
+/// Run the readerable pre-check, returning whether the page passed.
+pub fn run(args: CheckArgs, fetcher: &Fetcher) -> Result<bool> {
+    let input = get_html(args.input, fetcher)?;
+
+    let mut options = ReadabilityCheckOptions::new();
+    if let Some(min_score) = args.min_score {
+        options = options.min_score(min_score);
+    }
+    if let Some(min_content_length) = args.min_content_length {
+        options = options.min_content_length(min_content_length);
+    }
+
+    let parser = Readability::new().wrap_err("could not create Readability")?;
+    let check = parser
+        .check_readerable(&input.html, Some(options))
+        .wrap_err("readerable check")?;
+
+    if args.score {
+        println!("{:.2}", check.score);
+    }
+    Ok(check.readerable)
+}
@@ -0,0 +1,78 @@
+use color_eyre::Result;
+use color_eyre::eyre::bail;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Commands that print the clipboard, tried in order. HTML flavors come
+/// first, so copying a rendered page works as well as copying its source.
+const PASTE: &[&[&str]] = &[
+    &["wl-paste", "--no-newline", "--type", "text/html"],
+    &["wl-paste", "--no-newline"],
+    &[
+        "xclip",
+        "-selection",
+        "clipboard",
+        "-target",
+        "text/html",
+        "-out",
+    ],
+    &["xclip", "-selection", "clipboard", "-out"],
+    &["xsel", "--clipboard", "--output"],
+    &["pbpaste"],
+    &["powershell", "-NoProfile", "-Command", "Get-Clipboard -Raw"],
+];
+
+/// Commands that set the clipboard to their stdin, tried in order.
+const COPY: &[&[&str]] = &[
+    &["wl-copy"],
+    &["xclip", "-selection", "clipboard", "-in"],
+    &["xsel", "--clipboard", "--input"],
+    &["pbcopy"],
+    &[
+        "powershell",
+        "-NoProfile",
+        "-Command",
+        "[Console]::In.ReadToEnd() | Set-Clipboard",
+    ],
+];
+
+/// Read the clipboard using the first clipboard tool that works here.
+pub fn read() -> Result<String> {
+    for command in PASTE {
+        let Ok(output) = Command::new(command[0])
+            .args(&command[1..])
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+        else {
+            continue;
+        };
+        if output.status.success() && !output.stdout.is_empty() {
+            return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+        }
+    }
+    bail!("could not read the clipboard; install wl-clipboard, xclip or xsel")
+}
+
+/// Set the clipboard to `text` using the first clipboard tool that works here.
+pub fn write(text: &str) -> Result<()> {
+    for command in COPY {
+        let Ok(mut child) = Command::new(command[0])
+            .args(&command[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            continue;
+        };
+        let written = child
+            .stdin
+            .take()
+            .is_some_and(|mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+        if child.wait().is_ok_and(|status| status.success()) && written {
+            return Ok(());
+        }
+    }
+    bail!("could not write the clipboard; install wl-clipboard, xclip or xsel")
+}
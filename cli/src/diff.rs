@@ -0,0 +1,58 @@
+use color_eyre::Result;
+use color_eyre::eyre::Context;
+use readability_js::{Article, Readability};
+use similar::{ChangeTag, TextDiff};
+use std::io::Write;
+
+/// Write a unified diff from the visible text of the page to the text of the
+/// article, one block per line, and summarize how much was kept on stderr.
+pub fn write(
+    out: &mut impl Write,
+    parser: &Readability,
+    source: &str,
+    html: &str,
+    article: &Article,
+) -> Result<()> {
+    let page = lines(parser.text_blocks(html).wrap_err("reading the page text")?);
+    let kept = lines(
+        parser
+            .text_blocks(&article.content)
+            .wrap_err("reading the article text")?,
+    );
+    let diff = TextDiff::from_lines(&page, &kept);
+
+    let (mut blocks, mut total_blocks, mut chars, mut total_chars) = (0, 0, 0, 0);
+    for change in diff.iter_all_changes() {
+        if change.tag() == ChangeTag::Insert {
+            continue;
+        }
+        let len = change.value().chars().count();
+        total_blocks += 1;
+        total_chars += len;
+        if change.tag() == ChangeTag::Equal {
+            blocks += 1;
+            chars += len;
+        }
+    }
+    eprintln!(
+        "{}: kept {} of {} blocks, {}% of the text",
+        source,
+        blocks,
+        total_blocks,
+        (chars * 100).checked_div(total_chars).unwrap_or(100)
+    );
+
+    write!(
+        out,
+        "{}",
+        diff.unified_diff().context_radius(2).header(
+            &format!("{} (page)", source),
+            &format!("{} (article)", source)
+        )
+    )?;
+    Ok(())
+}
+
+fn lines(blocks: Vec<String>) -> String {
+    blocks.into_iter().map(|block| block + "\n").collect()
+}
@@ -0,0 +1,330 @@
+use color_eyre::Result;
+#[cfg(feature = "epub")]
+use color_eyre::eyre::Context;
+#[cfg(not(feature = "epub"))]
+use color_eyre::eyre::bail;
+use readability_js::Article;
+#[cfg(feature = "epub")]
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::fetch::Fetcher;
+
+/// Chapter headings and reading styles shared by every chapter.
+#[cfg(feature = "epub")]
+const STYLE: &str = "
+body { margin: 0 5%; line-height: 1.5; }
+h1 { margin-bottom: .25em; line-height: 1.2; }
+.meta { margin-top: 0; color: #666; font-size: .9em; }
+img { max-width: 100%; height: auto; }
+pre { white-space: pre-wrap; font-size: .85em; }
+blockquote { margin-left: 1em; padding-left: 1em; border-left: 2px solid #ccc; }
+";
+
+/// Image types EPUB readers are required to support, by extension.
+#[cfg(feature = "epub")]
+const IMAGE_TYPES: [(&str, &str); 5] = [
+    ("jpg", "image/jpeg"),
+    ("png", "image/png"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("svg", "image/svg+xml"),
+];
+
+/// One article of the book.
+#[cfg_attr(not(feature = "epub"), allow(dead_code))]
+struct Chapter {
+    title: String,
+    /// XHTML body content
+    body: String,
+}
+
+/// An image downloaded for the book.
+#[cfg_attr(not(feature = "epub"), allow(dead_code))]
+struct Image {
+    /// Path inside the book, relative to the package document
+    href: String,
+    media_type: &'static str,
+    data: Vec<u8>,
+}
+
+/// An EPUB 3 book collecting several articles, one chapter each, with a
+/// table of contents and the articles' images.
+#[cfg_attr(not(feature = "epub"), allow(dead_code))]
+pub struct Book {
+    title: String,
+    language: Option<String>,
+    chapters: Vec<Chapter>,
+    images: Vec<Image>,
+    /// Paths of images already in the book, by source URL; `None` if it
+    /// could not be downloaded
+    #[cfg(feature = "epub")]
+    image_paths: HashMap<String, Option<String>>,
+}
+
+#[cfg(not(feature = "epub"))]
+impl Book {
+    /// Always fails: this build cannot write EPUB.
+    pub fn new(_title: &str) -> Result<Self> {
+        bail!("readable was built without EPUB support")
+    }
+
+    pub fn add(&mut self, _article: &Article, _url: Option<&str>, _fetcher: &Fetcher) {}
+
+    pub fn write(&self, _path: &Path) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "epub")]
+impl Book {
+    pub fn new(title: &str) -> Result<Self> {
+        Ok(Self {
+            title: title.to_string(),
+            language: None,
+            chapters: Vec::new(),
+            images: Vec::new(),
+            image_paths: HashMap::new(),
+        })
+    }
+
+    /// Add `article`, fetched from `url` if known, as the next chapter.
+    ///
+    /// Its images are downloaded with `fetcher`; those that fail are replaced
+    /// by their alt text.
+    pub fn add(&mut self, article: &Article, url: Option<&str>, fetcher: &Fetcher) {
+        let base = url.and_then(|url| url::Url::parse(url).ok());
+        let content = crate::xhtml::from_html(&article.content, base.as_ref(), |src| {
+            self.image(src, fetcher)
+        });
+
+        let title = article.title.trim();
+        let title = if title.is_empty() { "Untitled" } else { title };
+        let mut body = format!("<h1>{}</h1>\n", crate::page::escape(title));
+        let mut meta: Vec<String> = [article.byline.as_deref(), article.site_name.as_deref()]
+            .into_iter()
+            .flatten()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(crate::page::escape)
+            .collect();
+        if let Some(url) = url {
+            meta.push(format!("<a href=\"{0}\">{0}</a>", crate::page::escape(url)));
+        }
+        if !meta.is_empty() {
+            body.push_str(&format!("<p class=\"meta\">{}</p>\n", meta.join(" · ")));
+        }
+        body.push_str(&content);
+
+        if self.language.is_none() {
+            self.language = article.language.clone();
+        }
+        self.chapters.push(Chapter {
+            title: title.to_string(),
+            body,
+        });
+    }
+
+    /// Download the image at `src` into the book, returning its path.
+    fn image(&mut self, src: &url::Url, fetcher: &Fetcher) -> Option<String> {
+        if let Some(path) = self.image_paths.get(src.as_str()) {
+            return path.clone();
+        }
+        let path = match fetcher.fetch_bytes(src) {
+            Ok((data, content_type)) => match image_type(src, content_type.as_deref()) {
+                Some((extension, media_type)) => {
+                    let href = format!("images/{}.{}", self.images.len() + 1, extension);
+                    self.images.push(Image {
+                        href: href.clone(),
+                        media_type,
+                        data,
+                    });
+                    Some(href)
+                }
+                None => {
+                    eprintln!(
+                        "warning: skipping image {}: unsupported type {}",
+                        src,
+                        content_type.as_deref().unwrap_or("(none)")
+                    );
+                    None
+                }
+            },
+            Err(e) => {
+                eprintln!("warning: could not download image {}: {:#}", src, e);
+                None
+            }
+        };
+        self.image_paths.insert(src.to_string(), path.clone());
+        path
+    }
+
+    /// Write the book to `path`.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let file = std::fs::File::create(path)
+            .wrap_err_with(|| format!("could not create {:#?}", path))?;
+        self.write_to(std::io::BufWriter::new(file))
+            .wrap_err_with(|| format!("could not write {:#?}", path))
+    }
+
+    fn write_to(&self, out: impl std::io::Write) -> Result<()> {
+        use crate::page::escape;
+
+        let mut zip = crate::zip::ZipWriter::new(out);
+        // The mimetype must come first and uncompressed, so readers can sniff it
+        zip.add("mimetype", b"application/epub+zip", true)?;
+        zip.add(
+            "META-INF/container.xml",
+            br#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>
+"#,
+            false,
+        )?;
+
+        let language = escape(self.language.as_deref().unwrap_or("en"));
+        let title = escape(&self.title);
+        let modified = crate::archive::unix_to_iso(
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+        );
+        let identifier = format!("urn:readable:{}", self.identifier());
+
+        let mut manifest = String::new();
+        let mut spine = String::new();
+        let mut nav = String::new();
+        let mut ncx = String::new();
+        for (i, chapter) in self.chapters.iter().enumerate() {
+            let n = i + 1;
+            let href = format!("chapter-{}.xhtml", n);
+            let chapter_title = escape(&chapter.title);
+            zip.add(
+                &format!("OEBPS/{}", href),
+                xhtml_document(&chapter_title, &language, &chapter.body).as_bytes(),
+                false,
+            )?;
+            manifest.push_str(&format!(
+                "    <item id=\"chapter-{n}\" href=\"{href}\" media-type=\"application/xhtml+xml\"/>\n"
+            ));
+            spine.push_str(&format!("    <itemref idref=\"chapter-{n}\"/>\n"));
+            nav.push_str(&format!(
+                "      <li><a href=\"{href}\">{chapter_title}</a></li>\n"
+            ));
+            ncx.push_str(&format!(
+                "    <navPoint id=\"chapter-{n}\" playOrder=\"{n}\">\n      <navLabel><text>{chapter_title}</text></navLabel>\n      <content src=\"{href}\"/>\n    </navPoint>\n"
+            ));
+        }
+        for (i, image) in self.images.iter().enumerate() {
+            // Already compressed, so stored as they are
+            zip.add(&format!("OEBPS/{}", image.href), &image.data, true)?;
+            manifest.push_str(&format!(
+                "    <item id=\"image-{}\" href=\"{}\" media-type=\"{}\"/>\n",
+                i + 1,
+                image.href,
+                image.media_type
+            ));
+        }
+
+        zip.add("OEBPS/style.css", STYLE.as_bytes(), false)?;
+        let nav_body = format!(
+            "<nav epub:type=\"toc\" id=\"toc\">\n  <h1>{title}</h1>\n  <ol>\n{nav}  </ol>\n</nav>"
+        );
+        zip.add(
+            "OEBPS/nav.xhtml",
+            xhtml_document(&title, &language, &nav_body).as_bytes(),
+            false,
+        )?;
+        // For readers that predate EPUB 3 navigation documents
+        let toc = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<ncx xmlns="http://www.daisy.org/z3986/2005/ncx/" version="2005-1">
+  <head>
+    <meta name="dtb:uid" content="{identifier}"/>
+  </head>
+  <docTitle><text>{title}</text></docTitle>
+  <navMap>
+{ncx}  </navMap>
+</ncx>
+"#
+        );
+        zip.add("OEBPS/toc.ncx", toc.as_bytes(), false)?;
+
+        let package = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="book-id" xml:lang="{language}">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:identifier id="book-id">{identifier}</dc:identifier>
+    <dc:title>{title}</dc:title>
+    <dc:language>{language}</dc:language>
+    <meta property="dcterms:modified">{modified}</meta>
+  </metadata>
+  <manifest>
+    <item id="nav" href="nav.xhtml" media-type="application/xhtml+xml" properties="nav"/>
+    <item id="ncx" href="toc.ncx" media-type="application/x-dtbncx+xml"/>
+    <item id="style" href="style.css" media-type="text/css"/>
+{manifest}  </manifest>
+  <spine toc="ncx">
+{spine}  </spine>
+</package>
+"#
+        );
+        zip.add("OEBPS/content.opf", package.as_bytes(), false)?;
+        zip.finish()?;
+        Ok(())
+    }
+
+    /// Identifier derived from the chapters, stable for the same articles.
+    fn identifier(&self) -> String {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for chapter in &self.chapters {
+            for byte in chapter.title.bytes().chain(chapter.body.bytes()) {
+                hash ^= u64::from(byte);
+                hash = hash.wrapping_mul(0x100000001b3);
+            }
+        }
+        format!("{:016x}", hash)
+    }
+}
+
+#[cfg(feature = "epub")]
+fn xhtml_document(title: &str, language: &str, body: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE html>
+<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops" xml:lang="{language}" lang="{language}">
+<head>
+  <title>{title}</title>
+  <link rel="stylesheet" type="text/css" href="style.css"/>
+</head>
+<body>
+{body}
+</body>
+</html>
+"#
+    )
+}
+
+/// Extension and media type of an image, from its content type or else its URL.
+#[cfg(feature = "epub")]
+fn image_type(src: &url::Url, content_type: Option<&str>) -> Option<(&'static str, &'static str)> {
+    let from_header = content_type.and_then(|content_type| {
+        let media_type = content_type.split(';').next()?.trim();
+        IMAGE_TYPES
+            .iter()
+            .find(|(_, t)| media_type.eq_ignore_ascii_case(t))
+    });
+    let from_url = || {
+        let extension = src.path().rsplit('.').next()?.to_ascii_lowercase();
+        let extension = if extension == "jpeg" {
+            "jpg".to_string()
+        } else {
+            extension
+        };
+        IMAGE_TYPES.iter().find(|(e, _)| *e == extension)
+    };
+    from_header.or_else(from_url).copied()
+}
@@ -0,0 +1,111 @@
+use color_eyre::Report;
+use readability_js::ReadabilityError;
+use std::fmt;
+
+/// Exit codes for the failure classes scripts may want to tell apart.
+pub const FETCH_ERROR: i32 = 2;
+pub const CHECK_FAILED: i32 = 3;
+pub const PARSE_ERROR: i32 = 4;
+pub const INVALID_URL: i32 = 5;
+
+/// Context marking an error as a failure to fetch a URL.
+#[derive(Debug)]
+pub struct FetchFailed(pub String);
+
+impl fmt::Display for FetchFailed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "could not fetch {}", self.0)
+    }
+}
+
+/// An input that looks like a URL but isn't a valid one.
+#[derive(Debug)]
+pub struct InvalidUrl(pub String);
+
+impl fmt::Display for InvalidUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid url: {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidUrl {}
+
+/// An article shorter than `--min-words`.
+#[derive(Debug)]
+pub struct TooShort {
+    pub words: usize,
+    pub min: usize,
+}
+
+impl fmt::Display for TooShort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "article has {} words, fewer than the minimum of {}",
+            self.words, self.min
+        )
+    }
+}
+
+impl std::error::Error for TooShort {}
+
+/// An article in a language not accepted by `--lang`.
+#[derive(Debug)]
+pub struct WrongLanguage(pub String);
+
+impl fmt::Display for WrongLanguage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "article language {} is not accepted", self.0)
+    }
+}
+
+impl std::error::Error for WrongLanguage {}
+
+/// Some inputs of a batch run failed; `code` is shared by all of the failures.
+#[derive(Debug)]
+pub struct BatchFailed {
+    pub failed: usize,
+    pub total: usize,
+    pub code: i32,
+}
+
+impl fmt::Display for BatchFailed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} of {} inputs failed", self.failed, self.total)
+    }
+}
+
+impl std::error::Error for BatchFailed {}
+
+/// The exit code for `error`: one of the constants above, or 1.
+pub fn code(error: &Report) -> i32 {
+    if let Some(batch) = error.downcast_ref::<BatchFailed>() {
+        return batch.code;
+    }
+    if error.downcast_ref::<FetchFailed>().is_some() {
+        return FETCH_ERROR;
+    }
+    if error.downcast_ref::<InvalidUrl>().is_some() {
+        return INVALID_URL;
+    }
+    if error.downcast_ref::<TooShort>().is_some() || error.downcast_ref::<WrongLanguage>().is_some()
+    {
+        return CHECK_FAILED;
+    }
+    for cause in error.chain() {
+        if let Some(e) = cause.downcast_ref::<ReadabilityError>() {
+            return match e {
+                ReadabilityError::ReadabilityCheckFailed | ReadabilityError::ExtractionError(_) => {
+                    CHECK_FAILED
+                }
+                ReadabilityError::HtmlParseError(_) => PARSE_ERROR,
+                ReadabilityError::InvalidOptions(_) => INVALID_URL,
+                _ => 1,
+            };
+        }
+        if cause.downcast_ref::<url::ParseError>().is_some() {
+            return INVALID_URL;
+        }
+    }
+    1
+}
@@ -0,0 +1,53 @@
+use readability_js::{CandidateScore, Explanation};
+
+/// Candidates listed by `--explain`
+const SHOWN: usize = 10;
+
+/// Render the highest scoring candidates of an extraction as a table.
+pub fn render(source: &str, explanation: &Explanation) -> String {
+    let candidates = &explanation.candidates;
+    let passes = candidates.iter().map(|c| c.pass).max().unwrap_or(1);
+    let mut out = format!(
+        "{}: {} candidates scored in {} pass{}, top {} by score\n",
+        source,
+        candidates.len(),
+        passes,
+        if passes == 1 { "" } else { "es" },
+        SHOWN.min(candidates.len())
+    );
+    out.push_str("   score  content  links  class  pass  element\n");
+    for candidate in candidates.iter().take(SHOWN) {
+        out.push_str(&format!(
+            "{:>8.1} {:>8.1} {:>6.2} {:>+6} {:>5}  {}  {:?}\n",
+            candidate.score,
+            candidate.content_score,
+            candidate.link_density,
+            candidate.class_weight,
+            candidate.pass,
+            selector(candidate),
+            preview(&candidate.text_preview, 40)
+        ));
+    }
+    out
+}
+
+/// The element as a CSS selector, like `div#content.post.body`.
+fn selector(candidate: &CandidateScore) -> String {
+    let mut selector = candidate.tag.clone();
+    if let Some(id) = &candidate.id {
+        selector.push('#');
+        selector.push_str(id);
+    }
+    for class in candidate.class.iter().flat_map(|c| c.split_whitespace()) {
+        selector.push('.');
+        selector.push_str(class);
+    }
+    preview(&selector, 40)
+}
+
+fn preview(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}…", text[..end].trim_end_matches('…')),
+        None => text.to_string(),
+    }
+}
@@ -0,0 +1,705 @@
+#[cfg(feature = "network")]
+use base64::prelude::*;
+use color_eyre::Result;
+#[cfg(feature = "network")]
+use color_eyre::eyre::Context;
+use color_eyre::eyre::bail;
+#[cfg(feature = "network")]
+use serde::Deserialize;
+#[cfg(feature = "network")]
+use std::collections::HashSet;
+use std::path::Path;
+#[cfg(feature = "network")]
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+#[cfg(feature = "network")]
+use ureq::ResponseExt;
+use url::Url;
+
+#[cfg(feature = "network")]
+use crate::cache::{CacheEntry, HttpCache};
+use crate::ratelimit::HostLimiter;
+
+#[cfg(feature = "network")]
+const ACCEPT: &str = "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.";
+#[cfg(feature = "network")]
+const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/121.0.0.0 Safari/537.36";
+
+/// Status codes after which an archived snapshot is worth trying.
+#[cfg(feature = "network")]
+const UNAVAILABLE_STATUSES: [u16; 4] = [403, 404, 410, 451];
+
+/// A fetched page.
+pub struct Page {
+    pub html: String,
+    /// URL the page was served from, after following redirects
+    pub final_url: String,
+    /// Set when the page was served from the Wayback Machine
+    pub snapshot: Option<Snapshot>,
+}
+
+/// An Internet Archive snapshot used in place of the live page.
+pub struct Snapshot {
+    /// Human-facing snapshot URL on web.archive.org
+    pub url: String,
+    /// Capture time in ISO 8601
+    pub timestamp: String,
+}
+
+#[cfg(feature = "network")]
+#[derive(Deserialize)]
+struct Availability {
+    archived_snapshots: ArchivedSnapshots,
+}
+
+#[cfg(feature = "network")]
+#[derive(Deserialize)]
+struct ArchivedSnapshots {
+    closest: Option<ClosestSnapshot>,
+}
+
+#[cfg(feature = "network")]
+#[derive(Deserialize)]
+struct ClosestSnapshot {
+    available: bool,
+    timestamp: String,
+}
+
+/// Fetches pages over HTTP, optionally through the on-disk cache.
+///
+/// Builds without the `network` feature (such as WASI) keep the type so that
+/// file and stdin inputs work unchanged, but every fetch fails.
+#[derive(Default)]
+pub struct Fetcher {
+    /// Keep fetched pages in the on-disk cache
+    #[cfg(feature = "network")]
+    use_cache: bool,
+    /// Opened on the first fetch, so inputs that need no network never touch it
+    #[cfg(feature = "network")]
+    cache: OnceLock<Option<HttpCache>>,
+    refresh: bool,
+    wayback: bool,
+    limiter: Option<HostLimiter>,
+    /// Client with custom settings; ureq's default agent otherwise
+    #[cfg(feature = "network")]
+    agent: Option<ureq::Agent>,
+    /// Custom TLS settings; the backend's defaults otherwise
+    #[cfg(any(feature = "rustls", feature = "native-tls"))]
+    tls: Option<ureq::tls::TlsConfig>,
+    /// `Authorization` header value sent with page and image requests
+    #[cfg(feature = "network")]
+    authorization: Option<String>,
+    /// Hosts of the URLs given to [`Fetcher::fetch`], the only ones
+    /// `authorization` is sent to
+    #[cfg(feature = "network")]
+    auth_hosts: Mutex<HashSet<String>>,
+    /// Limit on a whole request, from connecting to reading the body
+    #[cfg(feature = "network")]
+    timeout: Option<Duration>,
+    /// Refuse every request
+    #[cfg(feature = "network")]
+    offline: bool,
+}
+
+/// Credentials for sites behind a login.
+#[derive(Clone)]
+#[cfg_attr(not(feature = "network"), allow(dead_code))]
+pub enum Auth {
+    /// HTTP Basic authentication
+    Basic { user: String, password: String },
+    /// A bearer token, as used by most APIs and SSO-protected tools
+    Bearer(String),
+}
+
+impl Auth {
+    /// Parse `user:password` for Basic authentication.
+    pub fn parse_basic(credentials: &str) -> std::result::Result<Self, String> {
+        match credentials.split_once(':') {
+            Some((user, password)) if !user.is_empty() => Ok(Auth::Basic {
+                user: user.to_string(),
+                password: password.to_string(),
+            }),
+            _ => Err("expected USER:PASSWORD".to_string()),
+        }
+    }
+
+    #[cfg(feature = "network")]
+    fn header(&self) -> String {
+        match self {
+            Auth::Basic { user, password } => {
+                let credentials = format!("{}:{}", user, password);
+                format!("Basic {}", BASE64_STANDARD.encode(credentials))
+            }
+            Auth::Bearer(token) => format!("Bearer {}", token),
+        }
+    }
+}
+
+impl Fetcher {
+    /// Create a fetcher, using the on-disk cache with `cache`.
+    ///
+    /// With `refresh`, cached copies are never reused but still updated.
+    #[cfg_attr(not(feature = "network"), allow(unused_variables))]
+    pub fn new(cache: bool, refresh: bool) -> Self {
+        Self {
+            #[cfg(feature = "network")]
+            use_cache: cache,
+            #[cfg(feature = "network")]
+            cache: OnceLock::new(),
+            refresh,
+            wayback: false,
+            limiter: None,
+            #[cfg(feature = "network")]
+            agent: None,
+            #[cfg(any(feature = "rustls", feature = "native-tls"))]
+            tls: None,
+            #[cfg(feature = "network")]
+            authorization: None,
+            #[cfg(feature = "network")]
+            auth_hosts: Mutex::default(),
+            #[cfg(feature = "network")]
+            timeout: None,
+            #[cfg(feature = "network")]
+            offline: false,
+        }
+    }
+
+    /// Fall back to the Wayback Machine when a page is gone, blocked or paywalled.
+    pub fn with_wayback(mut self, wayback: bool) -> Self {
+        self.wayback = wayback;
+        self
+    }
+
+    /// Throttle requests per host, for fetching from several threads at once.
+    pub fn with_rate_limit(mut self, limiter: HostLimiter) -> Self {
+        self.limiter = Some(limiter);
+        self
+    }
+}
+
+#[cfg(not(feature = "network"))]
+impl Fetcher {
+    /// Accepted for the same flags, but nothing is ever fetched.
+    pub fn with_tls(self, _insecure: bool, _ca_file: Option<&Path>) -> Result<Self> {
+        Ok(self)
+    }
+
+    /// Accepted for the same flags, but nothing is ever fetched.
+    pub fn with_auth(self, _auth: Option<Auth>) -> Self {
+        self
+    }
+
+    /// Accepted for the same flags; this build is always offline.
+    pub fn with_offline(self, _offline: bool) -> Self {
+        self
+    }
+
+    /// Accepted for the same flags, but nothing is ever fetched.
+    #[cfg_attr(not(feature = "serve"), allow(dead_code))]
+    pub fn with_timeout(self, _timeout: Duration) -> Self {
+        self
+    }
+
+    /// Always fails: this build cannot make network requests.
+    pub fn fetch(&self, url: &Url) -> Result<Page> {
+        self.fetch_html(url).map(|html| Page {
+            html,
+            final_url: url.to_string(),
+            snapshot: None,
+        })
+    }
+
+    /// Always fails: this build cannot make network requests.
+    pub fn fetch_html(&self, url: &Url) -> Result<String> {
+        bail!(
+            "cannot fetch {}: readable was built without network support",
+            url
+        )
+    }
+
+    /// Always fails: this build cannot make network requests.
+    pub fn fetch_bytes(&self, url: &Url) -> Result<(Vec<u8>, Option<String>)> {
+        self.fetch_html(url).map(|html| (html.into_bytes(), None))
+    }
+}
+
+#[cfg(all(
+    feature = "network",
+    not(any(feature = "rustls", feature = "native-tls"))
+))]
+impl Fetcher {
+    /// Accepted for the same flags; this build has no TLS backend, so HTTPS
+    /// URLs fail either way.
+    pub fn with_tls(self, _insecure: bool, _ca_file: Option<&Path>) -> Result<Self> {
+        Ok(self)
+    }
+}
+
+#[cfg(any(feature = "rustls", feature = "native-tls"))]
+impl Fetcher {
+    /// Skip certificate verification with `insecure`, or trust only the
+    /// certificates in the PEM bundle `ca_file` instead of the built-in roots.
+    pub fn with_tls(mut self, insecure: bool, ca_file: Option<&Path>) -> Result<Self> {
+        if !insecure && ca_file.is_none() {
+            return Ok(self);
+        }
+        let mut tls = tls_config().disable_verification(insecure);
+        if let Some(path) = ca_file {
+            let pem = std::fs::read(path)
+                .wrap_err_with(|| format!("could not read CA bundle {:#?}", path))?;
+            let mut certs = Vec::new();
+            for item in ureq::tls::parse_pem(&pem) {
+                if let ureq::tls::PemItem::Certificate(cert) =
+                    item.wrap_err_with(|| format!("could not parse CA bundle {:#?}", path))?
+                {
+                    certs.push(cert);
+                }
+            }
+            if certs.is_empty() {
+                bail!("no certificates found in CA bundle {:#?}", path);
+            }
+            tls = tls.root_certs(ureq::tls::RootCerts::new_with_certs(&certs));
+        }
+        self.tls = Some(tls.build());
+        self.configure();
+        Ok(self)
+    }
+}
+
+#[cfg(feature = "network")]
+impl Fetcher {
+    /// Log in to the fetched pages with `auth`.
+    ///
+    /// Sent only to the hosts of URLs given to [`Fetcher::fetch`], with the
+    /// page and with images and alternate versions from the same host, and
+    /// kept across redirects within the same host. Never sent to the Wayback
+    /// Machine.
+    pub fn with_auth(mut self, auth: Option<Auth>) -> Self {
+        self.authorization = auth.as_ref().map(Auth::header);
+        if self.authorization.is_some() {
+            self.configure();
+        }
+        self
+    }
+
+    /// Give up on a request after `timeout`, redirects and reading the body
+    /// included, so a stalled server cannot hold on to the caller.
+    #[cfg_attr(not(feature = "serve"), allow(dead_code))]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self.configure();
+        self
+    }
+
+    /// Refuse all network access, so that URLs fail right away.
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// The on-disk cache, opened on first use.
+    ///
+    /// A cache that cannot be opened, such as without a writable home
+    /// directory, only costs revalidation, so fetching goes on without it.
+    fn cache(&self) -> Option<&HttpCache> {
+        if !self.use_cache {
+            return None;
+        }
+        self.cache
+            .get_or_init(|| match HttpCache::open_default() {
+                Ok(cache) => Some(cache),
+                Err(e) => {
+                    eprintln!("warning: {:#}; continuing without cache", e);
+                    None
+                }
+            })
+            .as_ref()
+    }
+
+    fn ensure_online(&self) -> Result<()> {
+        if self.offline {
+            bail!("network access is disabled by --offline");
+        }
+        Ok(())
+    }
+
+    /// Agent settings for the current TLS settings, credentials and timeout.
+    fn agent_config(&self) -> ureq::config::ConfigBuilder<ureq::typestate::AgentScope> {
+        let mut config = ureq::Agent::config_builder().timeout_global(self.timeout);
+        #[cfg(any(feature = "rustls", feature = "native-tls"))]
+        {
+            config = config.tls_config(self.tls.clone().unwrap_or_else(|| tls_config().build()));
+        }
+        if self.authorization.is_some() {
+            config = config.redirect_auth_headers(ureq::config::RedirectAuthHeaders::SameHost);
+        }
+        config
+    }
+
+    /// Replace the agent with one using the current settings.
+    fn configure(&mut self) {
+        self.agent = Some(self.agent_config().build().into());
+    }
+
+    /// Start a GET request with the configured settings.
+    fn get(&self, url: &str) -> ureq::RequestBuilder<ureq::typestate::WithoutBody> {
+        static DEFAULT: std::sync::OnceLock<ureq::Agent> = std::sync::OnceLock::new();
+        self.agent
+            .as_ref()
+            .unwrap_or_else(|| {
+                DEFAULT.get_or_init(|| Self::default().agent_config().build().into())
+            })
+            .get(url)
+    }
+
+    /// The `Authorization` header value for `url`, if it is on the host of
+    /// a page the user asked for.
+    ///
+    /// Articles embed images from CDNs and link to AMP caches, which have no
+    /// business seeing the user's credentials.
+    fn authorization_for(&self, url: &Url) -> Option<&str> {
+        let authorization = self.authorization.as_deref()?;
+        let host = url.host_str()?;
+        self.auth_hosts
+            .lock()
+            .unwrap()
+            .contains(host)
+            .then_some(authorization)
+    }
+
+    /// Start a GET request that also carries the configured credentials,
+    /// where they belong.
+    fn get_authorized(&self, url: &Url) -> ureq::RequestBuilder<ureq::typestate::WithoutBody> {
+        let request = self.get(url.as_str());
+        match self.authorization_for(url) {
+            Some(authorization) => request.header("Authorization", authorization),
+            None => request,
+        }
+    }
+
+    /// Fetch the page at `url`, falling back to an archived snapshot if enabled.
+    ///
+    /// `url` is taken to be one the user asked for, so its host gets the
+    /// configured credentials.
+    pub fn fetch(&self, url: &Url) -> Result<Page> {
+        if self.authorization.is_some()
+            && let Some(host) = url.host_str()
+        {
+            self.auth_hosts.lock().unwrap().insert(host.to_string());
+        }
+        self.ensure_online()?;
+        let (html, final_url) = match self.fetch_live(url, true) {
+            Ok((html, final_url)) if !self.wayback || !looks_paywalled(&html) => {
+                return Ok(Page {
+                    html,
+                    final_url,
+                    snapshot: None,
+                });
+            }
+            Ok(fetched) => fetched,
+            Err(ureq::Error::StatusCode(status))
+                if self.wayback && UNAVAILABLE_STATUSES.contains(&status) =>
+            {
+                return match self.fetch_snapshot(url)? {
+                    Some(page) => Ok(page),
+                    None => bail!(
+                        "requesting url: http status {}, and no archived snapshot is available",
+                        status
+                    ),
+                };
+            }
+            Err(e) => return Err(e).wrap_err("requesting url"),
+        };
+
+        // Paywalled: prefer the archive, but the teaser beats nothing
+        match self.fetch_snapshot(url) {
+            Ok(Some(page)) => Ok(page),
+            Ok(None) => Ok(Page {
+                html,
+                final_url,
+                snapshot: None,
+            }),
+            Err(e) => {
+                log::warn!("could not fetch archived snapshot of {}: {:#}", url, e);
+                Ok(Page {
+                    html,
+                    final_url,
+                    snapshot: None,
+                })
+            }
+        }
+    }
+
+    /// Fetch the page at `url` and return its body, without any fallback.
+    pub fn fetch_html(&self, url: &Url) -> Result<String> {
+        self.ensure_online()?;
+        self.fetch_live(url, true)
+            .map(|(html, _)| html)
+            .wrap_err("requesting url")
+    }
+
+    /// Fetch a resource such as an image, returning its body and content
+    /// type. Bypasses the cache, but not the rate limit.
+    #[cfg_attr(not(feature = "epub"), allow(dead_code))]
+    pub fn fetch_bytes(&self, url: &Url) -> Result<(Vec<u8>, Option<String>)> {
+        self.ensure_online()?;
+        let _permit = match (&self.limiter, url.host_str()) {
+            (Some(limiter), Some(host)) => Some(limiter.acquire(host)),
+            _ => None,
+        };
+        let mut response = self
+            .get_authorized(url)
+            .header("User-Agent", USER_AGENT)
+            .call()
+            .wrap_err("requesting url")?;
+        let content_type = response
+            .headers()
+            .get("Content-Type")
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let body = response
+            .body_mut()
+            .read_to_vec()
+            .wrap_err("reading response")?;
+        Ok((body, content_type))
+    }
+
+    /// Look up the closest snapshot with the Wayback availability API and fetch it.
+    fn fetch_snapshot(&self, url: &Url) -> Result<Option<Page>> {
+        let mut api = Url::parse("https://archive.org/wayback/available")?;
+        api.query_pairs_mut().append_pair("url", url.as_str());
+        let body = self
+            .get(api.as_str())
+            .header("User-Agent", USER_AGENT)
+            .call()
+            .wrap_err("querying the Wayback Machine")?
+            .body_mut()
+            .read_to_string()
+            .wrap_err("reading Wayback Machine response")?;
+        let availability: Availability =
+            serde_json::from_str(&body).wrap_err("parsing Wayback Machine response")?;
+
+        let Some(closest) = availability
+            .archived_snapshots
+            .closest
+            .filter(|closest| closest.available)
+        else {
+            return Ok(None);
+        };
+
+        // The `id_` flag serves the page as captured, without the archive's
+        // toolbar and link rewriting, so the original URL stays a valid base.
+        let raw = Url::parse(&format!(
+            "https://web.archive.org/web/{}id_/{}",
+            closest.timestamp, url
+        ))?;
+        let (html, final_url) = self
+            .fetch_live(&raw, false)
+            .wrap_err("fetching archived snapshot")?;
+
+        Ok(Some(Page {
+            html,
+            final_url,
+            snapshot: Some(Snapshot {
+                url: format!("https://web.archive.org/web/{}/{}", closest.timestamp, url),
+                timestamp: wayback_timestamp_to_iso(&closest.timestamp),
+            }),
+        }))
+    }
+
+    /// Fetch `url` with browser-like headers and return its body and the URL
+    /// it was served from after redirects.
+    ///
+    /// Cached pages are revalidated with `If-None-Match` / `If-Modified-Since`
+    /// and reused when the server answers `304 Not Modified`.
+    fn fetch_live(
+        &self,
+        url: &Url,
+        send_auth: bool,
+    ) -> std::result::Result<(String, String), ureq::Error> {
+        // The cache is shared by every run on the machine, and a page behind a
+        // login belongs to these credentials only
+        let authorized = send_auth && self.authorization_for(url).is_some();
+        let cache = if authorized { None } else { self.cache() };
+        let cached = match cache {
+            Some(cache) if !self.refresh => cache.get(url),
+            _ => None,
+        };
+
+        // Held until the body is read, which is when the host is done with us
+        let _permit = match (&self.limiter, url.host_str()) {
+            (Some(limiter), Some(host)) => Some(limiter.acquire(host)),
+            _ => None,
+        };
+
+        let request = if authorized {
+            self.get_authorized(url)
+        } else {
+            self.get(url.as_str())
+        };
+        let mut request = request
+            .header("Accept", ACCEPT)
+            .header("User-Agent", USER_AGENT);
+        if let Some((entry, _)) = &cached {
+            if let Some(etag) = &entry.etag {
+                request = request.header("If-None-Match", etag);
+            }
+            if let Some(last_modified) = &entry.last_modified {
+                request = request.header("If-Modified-Since", last_modified);
+            }
+        }
+
+        let mut response = request.call()?;
+        let final_url = response.get_uri().to_string();
+        if response.status() == 304
+            && let Some((_, body)) = cached
+        {
+            log::debug!("{} not modified, using cached copy", url);
+            return Ok((body, final_url));
+        }
+
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let entry = CacheEntry {
+            url: url.to_string(),
+            etag: header("ETag"),
+            last_modified: header("Last-Modified"),
+        };
+        let body = response.body_mut().read_to_string()?;
+
+        // Without validators a cached copy could never be reused safely
+        if let Some(cache) = cache
+            && (entry.etag.is_some() || entry.last_modified.is_some())
+            && let Err(e) = cache.put(&entry, &body)
+        {
+            log::warn!("could not cache {}: {:#}", url, e);
+        }
+
+        Ok((body, final_url))
+    }
+}
+
+/// Whether the page declares itself paywalled through schema.org metadata.
+#[cfg(feature = "network")]
+fn looks_paywalled(html: &str) -> bool {
+    html.match_indices("isAccessibleForFree").any(|(i, key)| {
+        let value: String = html[i + key.len()..]
+            .chars()
+            .take(16)
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        let value = value.to_ascii_lowercase();
+        value.starts_with("\":false") || value.starts_with("\":\"false\"")
+    })
+}
+
+/// Convert a Wayback `YYYYMMDDhhmmss` timestamp into ISO 8601.
+#[cfg(feature = "network")]
+fn wayback_timestamp_to_iso(timestamp: &str) -> String {
+    if timestamp.len() != 14 || !timestamp.bytes().all(|b| b.is_ascii_digit()) {
+        return timestamp.to_string();
+    }
+    format!(
+        "{}-{}-{}T{}:{}:{}Z",
+        &timestamp[0..4],
+        &timestamp[4..6],
+        &timestamp[6..8],
+        &timestamp[8..10],
+        &timestamp[10..12],
+        &timestamp[12..14]
+    )
+}
+
+/// Query parameters that only track where a visitor came from.
+const TRACKING_PARAMS: [&str; 12] = [
+    "fbclid", "gclid", "gclsrc", "dclid", "msclkid", "yclid", "igshid", "mc_cid", "mc_eid",
+    "mkt_tok", "_ga", "_gl",
+];
+
+/// Reduce `url` to the page it points at: drop the fragment and tracking
+/// parameters such as `utm_source`. The host is already lowercase once parsed.
+pub fn normalize_url(url: &mut Url) {
+    url.set_fragment(None);
+    let kept: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(key, _)| !key.starts_with("utm_") && !TRACKING_PARAMS.contains(&key.as_ref()))
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    if url.query_pairs().count() == kept.len() {
+        return;
+    }
+    if kept.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(kept);
+    }
+}
+
+pub fn try_parse_url(input: &str) -> Option<Url> {
+    // Helper function to validate URL
+    let is_valid_http_url = |url: &Url| -> bool {
+        (url.scheme() == "http" || url.scheme() == "https")
+            && url.host_str().is_some()
+            && !url.host_str().unwrap().is_empty()
+            && !url.host_str().unwrap().contains("..")
+            && url.host_str().unwrap().contains('.')
+    };
+
+    if let Ok(url) = Url::parse(input)
+        && is_valid_http_url(&url)
+    {
+        return Some(url);
+    }
+
+    let https_attempt = format!("https://{}", &input);
+    if let Ok(url) = Url::parse(&https_attempt)
+        && is_valid_http_url(&url)
+    {
+        return Some(url);
+    }
+    None
+}
+
+/// TLS settings for the backend this build was made with.
+#[cfg(any(feature = "rustls", feature = "native-tls"))]
+fn tls_config() -> ureq::tls::TlsConfigBuilder {
+    let builder = ureq::tls::TlsConfig::builder();
+    // The platform's TLS library comes with its certificate store
+    #[cfg(feature = "native-tls")]
+    let builder = builder
+        .provider(ureq::tls::TlsProvider::NativeTls)
+        .root_certs(ureq::tls::RootCerts::PlatformVerifier);
+    builder
+}
+
+#[cfg(all(test, feature = "network"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_basic_auth_header() {
+        let auth = Auth::parse_basic("Aladdin:open sesame").unwrap();
+        assert_eq!(auth.header(), "Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ==");
+    }
+
+    #[test]
+    fn test_credentials_only_go_to_requested_hosts() {
+        let fetcher = Fetcher::default()
+            .with_auth(Some(Auth::Bearer("secret".into())))
+            .with_offline(true);
+        let page = Url::parse("https://wiki.example.com/page").unwrap();
+        let image = Url::parse("https://wiki.example.com/image.png").unwrap();
+        let cdn = Url::parse("https://cdn.example.net/image.png").unwrap();
+        assert_eq!(fetcher.authorization_for(&page), None);
+
+        assert!(fetcher.fetch(&page).is_err());
+        assert_eq!(fetcher.authorization_for(&page), Some("Bearer secret"));
+        assert_eq!(fetcher.authorization_for(&image), Some("Bearer secret"));
+        assert_eq!(fetcher.authorization_for(&cdn), None);
+    }
+}
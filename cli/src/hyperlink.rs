@@ -0,0 +1,135 @@
+//! Clickable links in terminal output, using OSC 8 escape sequences.
+//!
+//! termimad prints Markdown links as their raw `[text](url)` source. Before
+//! rendering, [`mark`] swaps each link for its text between two control
+//! characters that take no room when wrapping; afterwards [`apply`] turns
+//! them into the escape sequences that open and close a hyperlink.
+
+const START: char = '\u{2}';
+const END: char = '\u{3}';
+
+/// Replace Markdown links with their marked-up text, returning the URLs in
+/// the order they appear.
+///
+/// Images and links inside code are left alone.
+pub fn mark(markdown: &str) -> (String, Vec<String>) {
+    let mut text = String::with_capacity(markdown.len());
+    let mut urls = Vec::new();
+    let mut fenced = false;
+
+    for line in markdown.split_inclusive('\n') {
+        // Stray markers in the input would be mistaken for ours
+        let line = line.replace([START, END], "");
+        if line.trim_start().starts_with("```") {
+            fenced = !fenced;
+        }
+        if fenced || line.starts_with("    ") || line.starts_with('\t') {
+            text.push_str(&line);
+            continue;
+        }
+        mark_line(&line, &mut text, &mut urls);
+    }
+    (text, urls)
+}
+
+fn mark_line(line: &str, text: &mut String, urls: &mut Vec<String>) {
+    let mut rest = line;
+    while let Some(i) = rest.find(['[', '`', '\\']) {
+        let (before, from) = rest.split_at(i);
+        text.push_str(before);
+
+        if let Some(escaped) = from.strip_prefix('\\') {
+            // Keep escapes, and whatever they escape, as they are
+            let len = escaped.chars().next().map_or(0, char::len_utf8) + 1;
+            text.push_str(&from[..len]);
+            rest = &from[len..];
+        } else if from.starts_with('`') {
+            let ticks = from.len() - from.trim_start_matches('`').len();
+            let fence = &from[..ticks];
+            match from[ticks..].find(fence) {
+                Some(end) => {
+                    let len = ticks + end + ticks;
+                    text.push_str(&from[..len]);
+                    rest = &from[len..];
+                }
+                None => {
+                    text.push_str(fence);
+                    rest = &from[ticks..];
+                }
+            }
+        } else if !before.ends_with('!')
+            && let Some((label, url, len)) = parse_link(from)
+        {
+            text.push(START);
+            text.push_str(label);
+            text.push(END);
+            urls.push(url.to_string());
+            rest = &from[len..];
+        } else {
+            text.push('[');
+            rest = &from[1..];
+        }
+    }
+    text.push_str(rest);
+}
+
+/// Parse `[label](url "title")` at the start of `s`, returning the label, the
+/// URL and the length of the whole link.
+fn parse_link(s: &str) -> Option<(&str, &str, usize)> {
+    let label_end = closing(s, '[', ']')?;
+    let target = s[label_end + 1..].strip_prefix('(')?;
+    let target_end = closing(&s[label_end + 1..], '(', ')')?;
+    let target = &target[..target_end - 1];
+    // A URL in angle brackets may contain spaces
+    let url = match target.trim_start().strip_prefix('<') {
+        Some(bracketed) => &bracketed[..bracketed.find('>')?],
+        None => target.split_whitespace().next()?,
+    };
+    Some((&s[1..label_end], url, label_end + 1 + target_end + 1))
+}
+
+/// Byte offset of the bracket closing the one `s` starts with.
+fn closing(s: &str, open: char, close: char) -> Option<usize> {
+    let mut depth = 0;
+    let mut escaped = false;
+    for (i, c) in s.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == open {
+            depth += 1;
+        } else if c == close {
+            depth -= 1;
+            if depth == 0 {
+                return Some(i);
+            }
+        } else if c == '\n' {
+            return None;
+        }
+    }
+    None
+}
+
+/// Turn the markers left by [`mark`] into OSC 8 hyperlinks to `urls`.
+pub fn apply(text: &str, urls: &[String]) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut urls = urls.iter();
+    for c in text.chars() {
+        match c {
+            START => {
+                // A control character in the URL could end the sequence early
+                let url: String = urls
+                    .next()
+                    .map_or("", String::as_str)
+                    .chars()
+                    .filter(|c| !c.is_control())
+                    .collect();
+                out.push_str(&format!("\x1b]8;;{}\x1b\\", url));
+            }
+            END => out.push_str("\x1b]8;;\x1b\\"),
+            c => out.push(c),
+        }
+    }
+    out
+}
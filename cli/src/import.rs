@@ -0,0 +1,149 @@
+use color_eyre::Result;
+use color_eyre::eyre::{Context, bail};
+use std::fs;
+use std::path::Path;
+
+use crate::archive::unix_to_iso;
+use crate::bookmarks::{self, BookmarkFilter};
+
+/// A page saved in a read-it-later service or as a bookmark, with what the
+/// service knew about it.
+#[derive(Clone, Debug, Default)]
+pub struct SavedItem {
+    pub url: String,
+    pub tags: Vec<String>,
+    /// When it was saved, in ISO 8601
+    pub saved_at: Option<String>,
+}
+
+/// Read a Pocket export (HTML or CSV) or an Instapaper export (CSV).
+///
+/// The format is told from the content: HTML is read as Pocket's list of
+/// links, CSV by its header row.
+pub fn read(path: &Path) -> Result<Vec<SavedItem>> {
+    let text =
+        fs::read_to_string(path).wrap_err_with(|| format!("could not read export {:#?}", path))?;
+    if text.trim_start().starts_with('<') {
+        return Ok(bookmarks::parse(&text, &BookmarkFilter::default()));
+    }
+    from_csv(&text).wrap_err_with(|| format!("could not read export {:#?}", path))
+}
+
+/// Read a CSV export by its column names: Pocket's `url`, `time_added` and
+/// `tags` (separated by `|`), or Instapaper's `URL`, `Timestamp`, `Folder`
+/// and `Tags`.
+fn from_csv(text: &str) -> Result<Vec<SavedItem>> {
+    let mut rows = parse_csv(text).into_iter();
+    let Some(header) = rows.next() else {
+        return Ok(Vec::new());
+    };
+    let column = |names: &[&str]| {
+        header
+            .iter()
+            .position(|name| names.iter().any(|n| name.trim().eq_ignore_ascii_case(n)))
+    };
+    let Some(url) = column(&["url"]) else {
+        bail!("not a Pocket or Instapaper export: there is no URL column");
+    };
+    let time = column(&["time_added", "timestamp"]);
+    let tags = column(&["tags"]);
+    let folder = column(&["folder"]);
+
+    let mut items = Vec::new();
+    for row in rows {
+        let field = |i: Option<usize>| i.and_then(|i| row.get(i)).map(|s| s.trim());
+        let Some(url) = field(Some(url)).filter(|url| !url.is_empty()) else {
+            continue;
+        };
+        let mut item_tags: Vec<String> = field(tags)
+            .unwrap_or_default()
+            // Instapaper writes tags as a JSON-like list
+            .trim_matches(['[', ']'])
+            .split(['|', ','])
+            .map(|tag| tag.trim().trim_matches('"').trim())
+            .filter(|tag| !tag.is_empty())
+            .map(str::to_string)
+            .collect();
+        // Instapaper folders are user-made, except for the built-in ones
+        if let Some(folder) = field(folder)
+            && !["", "Unread", "Archive", "Starred"].contains(&folder)
+            && !item_tags.iter().any(|tag| tag == folder)
+        {
+            item_tags.push(folder.to_string());
+        }
+        items.push(SavedItem {
+            url: url.to_string(),
+            tags: item_tags,
+            saved_at: field(time).and_then(|t| t.parse().ok()).map(unix_to_iso),
+        });
+    }
+    Ok(items)
+}
+
+/// Split CSV into rows of fields, with quoted fields as in RFC 4180.
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => row.push(std::mem::take(&mut field)),
+            '\n' if !quoted => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            '\r' if !quoted => {}
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows.retain(|row| row.iter().any(|field| !field.is_empty()));
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_csv_quoted_fields() {
+        let csv = "url,title\r\n\"https://a.example/?x=1,2\",\"Say \"\"hi\"\"\"\r\n\nhttps://b.example,\"two\nlines\"\n";
+        assert_eq!(
+            parse_csv(csv),
+            [
+                ["url", "title"],
+                ["https://a.example/?x=1,2", "Say \"hi\""],
+                ["https://b.example", "two\nlines"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_csv_pocket_and_instapaper() {
+        let pocket =
+            "title,url,time_added,tags,status\nA,https://a.example,1700000000,rust|web,unread\n";
+        let items = from_csv(pocket).unwrap();
+        assert_eq!(items[0].url, "https://a.example");
+        assert_eq!(items[0].tags, ["rust", "web"]);
+        assert_eq!(items[0].saved_at.as_deref(), Some("2023-11-14T22:13:20Z"));
+
+        let instapaper = "URL,Title,Selection,Folder,Timestamp,Tags\nhttps://b.example,B,,Reading,1700000000,\"[\"\"rust\"\"]\"\nhttps://c.example,C,,Unread,,\n";
+        let items = from_csv(instapaper).unwrap();
+        assert_eq!(items[0].tags, ["rust", "Reading"]);
+        assert!(items[1].tags.is_empty());
+        assert_eq!(items[1].saved_at, None);
+
+        assert!(from_csv("title,link\nA,https://a.example\n").is_err());
+    }
+}
@@ -1,13 +1,48 @@
-use clap::Parser;
+// Without `network` the cache and rate limiter are configured but never used
+#![cfg_attr(not(feature = "network"), allow(dead_code))]
+
+use clap::{Parser, Subcommand, ValueEnum};
 use color_eyre::Result;
 use color_eyre::eyre::{Context, bail};
-use readability_js::{Article, Direction, Readability};
+use readability_js::{Article, Direction, EngineInfo, LinkRel, Readability, ReadabilityOptions};
 use serde::Serialize;
+use std::collections::HashMap;
 use std::fs::File;
-use std::io::Write;
-use std::io::{self, Read};
-use std::path::PathBuf;
-use url::Url;
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+mod archive;
+mod batch;
+mod bookmarks;
+mod cache;
+mod check;
+mod clipboard;
+mod diff;
+mod epub;
+mod exit;
+mod explain;
+mod fetch;
+#[cfg(feature = "terminal")]
+mod hyperlink;
+mod import;
+mod naming;
+mod page;
+mod pager;
+mod ratelimit;
+#[cfg(feature = "serve")]
+mod serve;
+mod toc;
+#[cfg(feature = "epub")]
+mod xhtml;
+#[cfg(feature = "epub")]
+mod zip;
+
+use archive::Archive;
+use fetch::{Auth, Fetcher, Snapshot};
+use import::SavedItem;
+use naming::{FileNamer, NameTemplate};
+use ratelimit::HostLimiter;
 
 #[derive(Parser)]
 #[command(
@@ -25,24 +60,211 @@ EXAMPLES:
     curl -s https://egemengol.com/blog/readability/ | readable  # Process from stdin
 
     readable article.html > clean.md                                    # Save as Markdown
+    readable --input-list urls.txt --delay 2 > articles.md              # Batch, 2s between hits per host
+    readable --format ndjson --input-list urls.txt | jq .title          # One JSON article per line
+    readable --metadata=table --input-list urls.txt                     # Catalog without the bodies
     readable https://egemengol.com/blog/readability/ | bat -l markdown  # View in pager
+    readable --render-terminal https://egemengol.com/blog/readability/  # Read it right in the terminal
+
+    readable check --score article.html                         # Only test if it is readerable
+    readable serve --listen 127.0.0.1:3000                      # Serve extraction over HTTP
 
 INSTALLATION:
     cargo install readability-js-cli
 
 OUTPUT:
     By default outputs clean content as Markdown with YAML frontmatter containing
-    article metadata (title, author, etc). Use --html for raw HTML output or
-    --no-frontmatter for plain Markdown without metadata.
+    article metadata (title, author, etc). Use --html for raw HTML output,
+    --frontmatter toml or json for other frontmatter syntaxes, --no-frontmatter
+    for plain Markdown without metadata, or --format ndjson for one JSON
+    article per line.
+
+EXIT CODES:
+    0  success
+    1  other errors, or a page that is not readerable with `check`
+    2  a URL could not be fetched
+    3  the page failed the readability check (no article found, or rejected by
+       --min-words or --lang)
+    4  the HTML could not be parsed
+    5  an input or base URL is not a valid URL
+    Batch runs exit with the code shared by all failed inputs, or 1 if they differ.
 ",
-    version
+    version,
+    args_conflicts_with_subcommands = true
 )]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     #[arg(
-           help = "Input html file or URL (reads from stdin if not provided)",
+           help = "Input html files or URLs (reads from stdin if not provided)",
            value_hint = clap::ValueHint::AnyPath
        )]
-    input: Option<String>,
+    inputs: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Read additional inputs from a file, one per line (- for stdin)",
+        long_help = "Read additional input files or URLs from FILE, one per line. Empty lines and
+lines starting with # are ignored. Use - to read the list from stdin."
+    )]
+    input_list: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Extract every page bookmarked in a browser's bookmarks.html export",
+        long_help = "Add the http(s) URLs bookmarked in FILE to the inputs. FILE is a bookmarks.html
+export in the Netscape format that Firefox, Chrome, Safari and other browsers
+write. Narrow it down with --bookmark-folder and --bookmark-tag. Tags and the
+time each bookmark was added go into the front matter as `tags` and `saved_at`."
+    )]
+    bookmarks: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "NAME",
+        requires = "bookmarks",
+        help = "Only take bookmarks in this folder (or its subfolders)"
+    )]
+    bookmark_folder: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "TAG",
+        requires = "bookmarks",
+        help = "Only take bookmarks with this tag, as exported by Firefox"
+    )]
+    bookmark_tag: Option<String>,
+
+    #[arg(
+        long = "import",
+        value_name = "FILE",
+        help = "Extract every page saved in a Pocket or Instapaper export",
+        long_help = "Add the pages saved in FILE to the inputs. FILE is a Pocket export (HTML or
+CSV) or an Instapaper export (CSV). The tags and the time each page was saved
+are carried over into the front matter (and NDJSON records) as `tags` and
+`saved_at`, so an archive can be moved off those services with its metadata."
+    )]
+    import: Option<PathBuf>,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["inputs", "input_list", "bookmarks", "import"],
+        help = "Extract the HTML on the clipboard",
+        long_help = "Read the HTML to extract from the clipboard instead of a file, URL or stdin.
+Copy a page's source, or the page itself in browsers that copy HTML, and run
+`readable --from-clipboard`. Uses wl-paste, xclip or xsel on Linux, pbpaste
+on macOS and PowerShell on Windows."
+    )]
+    from_clipboard: bool,
+
+    #[arg(
+        long,
+        conflicts_with = "render_terminal",
+        help = "Copy the output to the clipboard instead of printing it",
+        long_help = "Put the output on the clipboard instead of printing it, using wl-copy, xclip or
+xsel on Linux, pbcopy on macOS and PowerShell on Windows. Together with
+--from-clipboard, turns copied page source into clean Markdown without temp files."
+    )]
+    to_clipboard: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["to_clipboard", "render_terminal"],
+        help = "Open the output in the default browser or editor",
+        long_help = "Save the output to a temporary file and open it in the default application for
+its type instead of printing it, e.g. `--format html-page --open` to go from a URL
+to a readable page in the browser in one command. Uses xdg-open on Linux, open
+on macOS and start on Windows."
+    )]
+    open: bool,
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "Keep the raw HTML of fetched pages in DIR",
+        long_help = "Save the raw HTML of every fetched URL in DIR, with a JSON sidecar holding the
+requested URL, the final URL after redirects and the fetch time, so the
+extraction can be run again later, e.g. when the algorithm improves. Files are
+named after the URL."
+    )]
+    archive: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "TEMPLATE",
+        value_parser = NameTemplate::parse,
+        conflicts_with_all = ["to_clipboard", "open", "render_terminal", "metadata"],
+        help = "Write each article to a file named after TEMPLATE, e.g. \"{date}-{slug}.md\"",
+        long_help = "Write each article to its own file instead of stdout, naming it after TEMPLATE,
+and print the paths written. TEMPLATE may contain directories, which are created
+as needed, and these fields:
+
+    {slug}    the title, lowercased, with other characters turned into -
+    {domain}  the host of the URL, without www. (local for files and stdin)
+    {date}    the published date as YYYY-MM-DD (undated when unknown)
+    {index}   the position among the inputs, from 1, zero-padded to sort
+
+Names already used in the run get -2, -3 and so on added before the extension."
+    )]
+    name_template: Option<NameTemplate>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["name_template", "metadata", "render_terminal", "to_clipboard", "open"],
+        help = "With --format epub, the EPUB file to write all articles to",
+        long_help = "Write all articles into one EPUB book at FILE, for --format epub. Each article
+becomes a chapter, listed in a table of contents, with its images downloaded into
+the book. The book is titled after the file name, so --bundle \"Weekly Reads.epub\"
+sends a reading list to an e-reader in one step."
+    )]
+    bundle: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Process every input as given, even repeated ones",
+        long_help = "Fetch URLs exactly as given and process repeated inputs again. By default,
+before a batch run, fragments and tracking parameters such as utm_source and
+fbclid are stripped from URLs and inputs that turn out the same are skipped."
+    )]
+    keep_duplicates: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Tune extraction for a kind of page",
+        long_help = "Tune extraction for a kind of page. `news` requires a long body of text and is
+strict about link lists, `docs` accepts short pages and keeps notes and
+structured layouts, `forum` accepts threads of short posts. Without this flag
+Readability's defaults are used."
+    )]
+    preset: Option<Preset>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "MODE",
+        help = "Rewrite the rel attribute of links",
+        long_help = "Rewrite the rel attribute of links in the article, for republishing it.
+`normalize` drops nofollow, sponsored and ugc, which only describe how the
+original site vouches for a link, and keeps the rest; `strip` removes rel."
+    )]
+    link_rel: Option<LinkRelMode>,
+
+    #[arg(long, help = "Replace links marked rel=sponsored with their text")]
+    unlink_sponsored: bool,
+
+    #[arg(
+        long,
+        help = "Tidy the whitespace of the article's plain text",
+        long_help = "Tidy the whitespace of the article's plain text (the text_content field of
+--format ndjson): non-breaking spaces become plain spaces, indentation is
+removed and runs of spaces and blank lines are collapsed."
+    )]
+    normalize_whitespace: bool,
 
     #[arg(
         long,
@@ -53,10 +275,82 @@ non-content elements, but the structure and formatting will remain as HTML."
     )]
     html: bool,
 
+    #[arg(
+        long,
+        value_enum,
+        conflicts_with = "html",
+        help = "Output format [default: markdown]",
+        long_help = "Output format. `text` writes the plain text content with paragraphs separated
+by blank lines, for text-to-speech and LLM ingestion. `rst` writes
+reStructuredText for Sphinx-based documentation. `html-page` writes a complete
+HTML document with reader-mode styles and the metadata in a header, viewable
+offline in any browser.
+
+`ndjson` writes one JSON object per input and line, with the article fields plus
+`source` (the input as given) and `url`. Inputs that fail are written as a line
+with `source` and `error` instead, so batch results stream cleanly into tools
+like jq."
+    )]
+    format: Option<Format>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "STYLE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "json",
+        conflicts_with_all = ["html", "format"],
+        help = "Print only article metadata, as JSON lines or a table",
+        long_help = "Print only the metadata of each article, without the body: title, byline,
+published and modified times, site name, excerpt, language and word count.
+STYLE is `json` (the default, one object per line) or `table` (tab-separated,
+with a header row), given as --metadata=table. Skipping the Markdown conversion
+makes this much faster for cataloging large URL lists."
+    )]
+    metadata: Option<MetadataStyle>,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["html", "format", "metadata"],
+        help = "Render the article for reading in the terminal",
+        long_help = "Render the article with headings, emphasis, lists and code blocks styled for
+the terminal, with paragraphs wrapped to its width, instead of printing Markdown
+source. Turns `readable URL` into a read-it-now tool. On a terminal, links show
+only their text and are clickable where the terminal supports OSC 8 hyperlinks."
+    )]
+    render_terminal: bool,
+
+    #[arg(
+        long,
+        help = "Don't page long output",
+        long_help = "Write straight to the terminal even when the output is longer than one screen.
+By default long output on a terminal is shown through $PAGER (less if unset),
+like git does."
+    )]
+    no_pager: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t,
+        help = "Color theme of --format html-page output"
+    )]
+    theme: page::Theme,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Stylesheet to embed in --format html-page output",
+        long_help = "Embed the stylesheet in FILE in --format html-page output. It is applied after
+the built-in reader styles, so its rules take precedence."
+    )]
+    css: Option<PathBuf>,
+
     #[arg(
         long = "no-frontmatter",
-        help = "Skip YAML frontmatter when outputting Markdown",
-        long_help = "Don't include YAML frontmatter with article metadata (title, author, URL, etc)
+        help = "Skip frontmatter when outputting Markdown",
+        long_help = "Don't include frontmatter with article metadata (title, author, URL, etc)
 at the top of Markdown output. Only affects Markdown output - has no effect when --html is used.
 
 Without this flag, Markdown output includes metadata like:
@@ -70,47 +364,989 @@ language: en
 With this flag, only the article content is output."
     )]
     no_frontmatter: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        conflicts_with = "no_frontmatter",
+        help = "Syntax of the frontmatter in Markdown output [default: yaml]",
+        long_help = "Syntax of the frontmatter in Markdown output, with the same fields in each:
+`yaml` between --- lines, `toml` between +++ lines, or `json` as a bare object.
+Hugo reads all three; Obsidian and most other tools only read YAML. [default: yaml]"
+    )]
+    frontmatter: Option<FrontmatterFormat>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Don't read or write the on-disk HTTP cache",
+        long_help = "Fetch URLs without touching the on-disk HTTP cache.
+
+By default fetched pages are cached under ~/.cache/readable/ and revalidated with
+conditional requests (ETag / Last-Modified) on later runs."
+    )]
+    no_cache: bool,
+
+    #[arg(
+        long,
+        global = true,
+        conflicts_with = "no_cache",
+        help = "Refetch URLs instead of reusing cached copies",
+        long_help = "Always download URLs in full instead of revalidating cached copies.
+The fresh responses still replace the cached ones."
+    )]
+    refresh: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Fall back to the Wayback Machine for dead, blocked or paywalled URLs",
+        long_help = "When fetching a URL fails with 403, 404, 410 or 451, or the page marks itself as
+paywalled, extract the closest snapshot from the Internet Archive instead.
+
+The snapshot URL and capture time are added to the frontmatter as
+archive_url and archived_at."
+    )]
+    wayback: bool,
+
+    #[arg(
+        long,
+        global = true,
+        conflicts_with_all = ["wayback", "refresh"],
+        help = "Never access the network; URLs fail right away",
+        long_help = "Refuse all network access: URL inputs fail immediately instead of being
+fetched, and images are not downloaded. Only files, stdin and the clipboard
+are read, and the page cache is never opened, so runs are safe inside
+sandboxes and reproducible builds."
+    )]
+    offline: bool,
+
+    #[arg(
+        long,
+        global = true,
+        conflicts_with = "ca_file",
+        help = "Fetch HTTPS pages without verifying their certificates",
+        long_help = "Accept any certificate when fetching over HTTPS, for intranet sites and
+capture proxies with self-signed certificates. Anyone on the network path can
+then read and alter the pages fetched."
+    )]
+    insecure: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "FILE",
+        help = "Trust the CA certificates in a PEM bundle",
+        long_help = "Verify HTTPS certificates against the CA certificates in this PEM file instead
+of the built-in roots, like curl's --cacert. To keep trusting public sites too,
+concatenate it with the system bundle, e.g. /etc/ssl/certs/ca-certificates.crt."
+    )]
+    ca_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "USER:PASSWORD",
+        value_parser = Auth::parse_basic,
+        env = "READABLE_AUTH",
+        hide_env_values = true,
+        conflicts_with = "bearer",
+        help = "Log in to fetched pages with HTTP Basic authentication",
+        long_help = "Send these credentials with HTTP Basic authentication, for intranet tools
+such as Confluence or an internal CMS. They go only to the hosts of the URLs
+you give: with the page, including redirects within the same host, and with
+images and --prefer-amp or --prefer-print versions on those hosts, never to
+CDNs, other sites or the Wayback Machine. Pages fetched with them are never
+stored in or served from the page cache. Prefer the READABLE_AUTH environment
+variable to keep the password out of your shell history."
+    )]
+    auth: Option<Auth>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "TOKEN",
+        env = "READABLE_BEARER",
+        hide_env_values = true,
+        help = "Log in to fetched pages with a bearer token",
+        long_help = "Send \"Authorization: Bearer TOKEN\" to the hosts of the URLs you give, for
+tools that accept API or personal access tokens. Like --auth, it goes only to
+those hosts, is kept across redirects within the same host, is never sent to
+other sites or the Wayback Machine, and pages fetched with it bypass the page
+cache. Can also be set with the READABLE_BEARER environment variable."
+    )]
+    bearer: Option<String>,
+
+    #[arg(
+        long,
+        default_value_t = 0.0,
+        value_name = "SECONDS",
+        help = "Minimum delay between requests to the same host",
+        long_help = "Minimum time in seconds between the start of two requests to the same host.
+Useful for batch runs over many URLs of one site."
+    )]
+    delay: f64,
+
+    #[arg(
+        long,
+        default_value_t = 1,
+        value_name = "N",
+        help = "Maximum concurrent requests to the same host",
+        long_help = "Maximum number of requests in flight to any single host. Batch runs fetch
+different hosts in parallel but never exceed this limit per host."
+    )]
+    max_per_host_concurrency: usize,
+
+    #[arg(
+        long,
+        help = "Extract the AMP version of pages that advertise one",
+        long_help = "When a fetched page advertises an AMP version with <link rel=\"amphtml\">, fetch
+and extract that instead. AMP pages are often lighter and cleaner than the
+canonical page. The AMP URL is recorded in the frontmatter as extracted_from."
+    )]
+    prefer_amp: bool,
+
+    #[arg(
+        long,
+        help = "Extract the print version of pages that link to one",
+        long_help = "When a fetched page links to a printer-friendly version (rel=\"alternate\"
+media=\"print\", or links like ?print=1), fetch and extract that instead. Print versions
+are usually much cleaner than the main page. Takes precedence over --prefer-amp.
+The print URL is recorded in the frontmatter as extracted_from."
+    )]
+    prefer_print: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Treat articles with fewer than N words as failures",
+        long_help = "Treat articles with fewer than N words as failures. Hub and landing pages often
+pass the readability check with only a few lines of text; this keeps them out of
+the output. Short articles exit with code 3, like pages without an article."
+    )]
+    min_words: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "LANGS",
+        value_delimiter = ',',
+        help = "Only accept articles in these languages, e.g. en,de",
+        long_help = "Only accept articles whose detected language is one of LANGS, a comma-separated
+list such as en,de. A region-specific language like en-US matches en. Articles in
+other languages are treated as failures with exit code 3; articles without a
+detected language are kept."
+    )]
+    lang: Vec<String>,
+
+    #[arg(
+        long,
+        help = "Show how the article's container was chosen, on stderr",
+        long_help = "Print the elements Readability scored as possible containers of the article to
+stderr, highest score first, with the link density and class weight behind each
+score. Useful for finding out why the wrong part of a page was extracted. The
+article is written as usual."
+    )]
+    explain: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["format", "html", "metadata", "render_terminal", "bundle", "name_template"],
+        help = "Show which parts of the page were kept, as a diff",
+        long_help = "Instead of the article, print a unified diff from the visible text of the page
+to the text of the article, one paragraph, heading or list item per line. Lines
+starting with - were dropped. A summary of how much text was kept goes to
+stderr, to spot extractions that silently lost part of an article."
+    )]
+    diff: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["metadata", "render_terminal"],
+        help = "Start Markdown and HTML output with a table of contents",
+        long_help = "Insert a linked table of contents, built from the article's headings, at the
+top of Markdown, HTML and html-page output. Anchors are derived from the heading
+text the way GitHub does, so links keep working across runs; in HTML the
+headings get matching ids. Articles with fewer than two headings are left as
+they are."
+    )]
+    toc: bool,
+}
+
+/// The HTML to extract and where it came from.
+struct Input {
+    html: String,
+    url: Option<String>,
+    snapshot: Option<Snapshot>,
+    /// URL `html` was served from after redirects, when it was fetched
+    final_url: Option<String>,
+    /// Set when the input came from a bookmarks or read-it-later export
+    saved: Option<SavedItem>,
+    /// Set when `html` was fetched from a variant of `url`, such as its print or AMP page
+    alternate_url: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Only check whether a page is likely readerable, exiting with 0 if so and 1 otherwise
+    Check(check::CheckArgs),
+    /// Run an HTTP server that extracts articles on `POST /extract`
+    #[cfg(feature = "serve")]
+    Serve(serve::ServeArgs),
 }
 
 fn main() -> Result<()> {
     color_eyre::install()?;
-    let args = Args::parse();
+    if let Err(e) = run(Args::parse()) {
+        eprintln!("Error: {:?}", e);
+        std::process::exit(exit::code(&e));
+    }
+    Ok(())
+}
 
-    let (html, urlstr) = get_html(args.input)?;
+fn run(args: Args) -> Result<()> {
+    #[cfg(feature = "serve")]
+    if let Some(Command::Serve(serve_args)) = args.command {
+        return serve::run(
+            serve_args,
+            args.offline,
+            args.insecure,
+            args.ca_file.as_deref(),
+        );
+    }
+
+    // Offline runs fetch nothing, so they have no use for the cache
+    let fetcher = Fetcher::new(!args.no_cache && !args.offline, args.refresh)
+        .with_wayback(args.wayback)
+        .with_rate_limit(HostLimiter::new(
+            Duration::from_secs_f64(args.delay.max(0.0)),
+            args.max_per_host_concurrency,
+        ))
+        .with_tls(args.insecure, args.ca_file.as_deref())?
+        .with_auth(args.auth.or(args.bearer.map(Auth::Bearer)))
+        .with_offline(args.offline);
 
-    let parser = Readability::new().wrap_err("could not create Readability")?;
-    let article = match urlstr {
-        Some(ref url) => parser.parse_with_url(&html, url),
-        None => parser.parse(&html),
+    if let Some(Command::Check(check_args)) = args.command {
+        if !check::run(check_args, &fetcher)? {
+            std::process::exit(1);
+        }
+        return Ok(());
     }
-    .wrap_err("extraction")?;
 
-    let convert_to_markdown = !args.html;
-    let write_frontmatter = convert_to_markdown && !args.no_frontmatter;
+    let variants: Vec<Variant> = [
+        (args.prefer_print, Variant::Print),
+        (args.prefer_amp, Variant::Amp),
+    ]
+    .into_iter()
+    .filter_map(|(enabled, variant)| enabled.then_some(variant))
+    .collect();
+    let format = args.format.unwrap_or(if args.html {
+        Format::Html
+    } else {
+        Format::Markdown
+    });
+    let css = args
+        .css
+        .map(|path| {
+            std::fs::read_to_string(&path)
+                .wrap_err_with(|| format!("could not read stylesheet {:#?}", path))
+        })
+        .transpose()?;
+    let mut book = match (format, &args.bundle) {
+        (Format::Epub, Some(path)) => {
+            let title = path
+                .file_stem()
+                .map_or("Articles".into(), |stem| stem.to_string_lossy());
+            Some(epub::Book::new(&title)?)
+        }
+        (Format::Epub, None) => bail!("--format epub needs the file to write, given with --bundle"),
+        (_, Some(_)) => bail!("--bundle only applies to --format epub"),
+        _ => None,
+    };
+    if args.toc && !matches!(format, Format::Markdown | Format::Html | Format::HtmlPage) {
+        bail!("--toc only applies to Markdown and HTML output");
+    }
+    let output = OutputOptions {
+        format,
+        frontmatter: (format == Format::Markdown && !args.no_frontmatter && !args.render_terminal)
+            .then(|| args.frontmatter.unwrap_or(FrontmatterFormat::Yaml)),
+        metadata: args.metadata,
+        render_terminal: args.render_terminal,
+        toc: args.toc,
+        page: page::PageStyle {
+            theme: args.theme,
+            css,
+        },
+    };
+    let filters = Filters {
+        min_words: args.min_words,
+        languages: args.lang,
+    };
+    let mut inputs = args.inputs;
+    if let Some(list) = args.input_list {
+        inputs.extend(read_input_list(&list)?);
+    }
+    // Tags and save times of bookmarked and imported pages, by normalized URL
+    let mut saved: HashMap<String, SavedItem> = HashMap::new();
+    if let Some(path) = &args.bookmarks {
+        let filter = bookmarks::BookmarkFilter {
+            folder: args.bookmark_folder,
+            tag: args.bookmark_tag,
+        };
+        let items = bookmarks::read(path, &filter)?;
+        if items.is_empty() {
+            bail!("no matching bookmarks in {:#?}", path);
+        }
+        add_saved(&mut inputs, &mut saved, items);
+    }
+    if let Some(path) = &args.import {
+        let items = import::read(path)?;
+        if items.is_empty() {
+            bail!("no saved pages in {:#?}", path);
+        }
+        add_saved(&mut inputs, &mut saved, items);
+    }
+    if inputs.len() > 1 && !args.keep_duplicates {
+        let (unique, dropped) = batch::dedupe(inputs);
+        if dropped > 0 {
+            eprintln!("skipping {} duplicate input(s)", dropped);
+        }
+        inputs = unique;
+    }
+
+    let archive = args.archive.as_deref().map(Archive::new).transpose()?;
+    let mut options = args
+        .preset
+        .map_or_else(ReadabilityOptions::new, Preset::options);
+    if let Some(mode) = args.link_rel {
+        options = options.link_rel(mode.into());
+    }
+    if args.unlink_sponsored {
+        options = options.unlink_sponsored(true);
+    }
+    if args.normalize_whitespace {
+        options = options
+            .normalize_spaces(true)
+            .trim_lines(true)
+            .collapse_whitespace(true);
+    }
+    let parser = Readability::builder()
+        .default_options(options)
+        .build()
+        .wrap_err("could not create Readability")?;
 
-    if convert_to_markdown {
-        let markdown = html2md::parse_html(&article.content);
-        let mut out = io::stdout().lock();
+    let mut out = if args.to_clipboard {
+        pager::Output::clipboard()
+    } else if args.open {
+        pager::Output::open(output.extension())
+    } else {
+        pager::Output::new(!args.no_pager)
+    };
+    if output.metadata == Some(MetadataStyle::Table) {
+        out.write_all(MetadataRecord::TABLE_HEADER.as_bytes())?;
+    }
+    let mut files = args
+        .name_template
+        .map(|template| FileNamer::new(template, inputs.len().max(1)));
+    let pipeline = Pipeline {
+        parser: &parser,
+        fetcher: &fetcher,
+        variants: &variants,
+        saved: &saved,
+        archive: archive.as_ref(),
+        filters: &filters,
+        output: &output,
+        explain: args.explain,
+        diff: args.diff,
+    };
+    if inputs.len() <= 1 {
+        let (name, html) = if args.from_clipboard {
+            let html = clipboard::read().map(|html| Input {
+                html,
+                url: None,
+                snapshot: None,
+                final_url: None,
+                saved: None,
+                alternate_url: None,
+            });
+            ("clipboard".to_string(), html)
+        } else {
+            let name = inputs.last().cloned().unwrap_or_else(|| "-".to_string());
+            (name, get_html(inputs.pop(), &fetcher))
+        };
+        let result = html
+            .and_then(|input| pipeline.process(&mut out, &mut book, &mut files, &name, input, 1));
+        if let Err(e) = &result
+            && output.format == Format::Ndjson
+        {
+            write_error(&mut out, &name, e)?;
+        }
+        if result.is_ok()
+            && let (Some(book), Some(path)) = (&book, &args.bundle)
+        {
+            book.write(path)?;
+            writeln!(out, "{}", path.display())?;
+        }
+        out.finish()?;
+        return result;
+    }
 
-        if write_frontmatter {
-            out.write_all("---\n".as_bytes())?;
-            let mut metadata = ArticleMetadata::from(article);
-            if let Some(urlstr) = urlstr {
-                metadata.url = Some(urlstr);
+    // Batch: report failures per input and keep going
+    let total = inputs.len();
+    let mut failed = 0;
+    let mut code = None;
+    let mut index = 0;
+    batch::for_each_input(inputs, &fetcher, |name, input| {
+        index += 1;
+        let result = input.and_then(|input| {
+            pipeline.process(&mut out, &mut book, &mut files, name, input, index)
+        });
+        if let Err(e) = result {
+            failed += 1;
+            code = match code {
+                None => Some(exit::code(&e)),
+                Some(c) if c == exit::code(&e) => Some(c),
+                Some(_) => Some(1),
+            };
+            eprintln!("{}: {:#}", name, e);
+            if output.format == Format::Ndjson
+                && let Err(e) = write_error(&mut out, name, &e)
+            {
+                eprintln!("{}: {:#}", name, e);
             }
-            serde_yaml::to_writer(&mut out, &metadata)?;
-            out.write_all("---\n".as_bytes())?;
         }
+    });
+    // Nothing to bundle if every input failed
+    if failed < total
+        && let (Some(book), Some(path)) = (&book, &args.bundle)
+    {
+        book.write(path)?;
+        writeln!(out, "{}", path.display())?;
+    }
+    out.finish()?;
+    if let Some(code) = code {
+        return Err(exit::BatchFailed {
+            failed,
+            total,
+            code,
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// Markdown, with frontmatter unless --no-frontmatter is given
+    Markdown,
+    /// The cleaned HTML content
+    Html,
+    /// Plain text, one paragraph per block, without Markdown syntax
+    Text,
+    /// reStructuredText, for Sphinx-based documentation
+    Rst,
+    /// A standalone HTML page with reader-mode styles, viewable offline
+    HtmlPage,
+    /// One JSON object per input and line
+    Ndjson,
+    /// An EPUB book of all articles, written to the --bundle file
+    Epub,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum FrontmatterFormat {
+    /// YAML between --- lines
+    Yaml,
+    /// TOML between +++ lines
+    Toml,
+    /// A JSON object
+    Json,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum MetadataStyle {
+    /// One JSON object per line
+    Json,
+    /// Tab-separated columns with a header row
+    Table,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Preset {
+    /// News sites and blogs
+    News,
+    /// Documentation, references and wikis
+    Docs,
+    /// Forum threads and Q&A sites
+    Forum,
+}
+
+impl Preset {
+    fn options(self) -> ReadabilityOptions {
+        match self {
+            Preset::News => ReadabilityOptions::preset_news(),
+            Preset::Docs => ReadabilityOptions::preset_docs(),
+            Preset::Forum => ReadabilityOptions::preset_forum(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum LinkRelMode {
+    /// Lowercased and deduplicated, without nofollow, sponsored and ugc
+    Normalize,
+    /// Removed entirely
+    Strip,
+}
+
+impl From<LinkRelMode> for LinkRel {
+    fn from(mode: LinkRelMode) -> Self {
+        match mode {
+            LinkRelMode::Normalize => LinkRel::Normalize,
+            LinkRelMode::Strip => LinkRel::Strip,
+        }
+    }
+}
+
+struct OutputOptions {
+    format: Format,
+    frontmatter: Option<FrontmatterFormat>,
+    metadata: Option<MetadataStyle>,
+    render_terminal: bool,
+    toc: bool,
+    page: page::PageStyle,
+}
+
+impl OutputOptions {
+    /// File extension for saving the output.
+    fn extension(&self) -> &'static str {
+        match (self.metadata, self.format) {
+            (Some(MetadataStyle::Json), _) | (None, Format::Ndjson) => "jsonl",
+            (Some(MetadataStyle::Table), _) => "tsv",
+            (None, Format::Markdown) => "md",
+            (None, Format::Html | Format::HtmlPage) => "html",
+            (None, Format::Text) => "txt",
+            (None, Format::Rst) => "rst",
+            (None, Format::Epub) => "epub",
+        }
+    }
+}
 
-        out.write_all(markdown.as_bytes())?;
+/// Conditions an extracted article must meet to be written.
+struct Filters {
+    min_words: Option<usize>,
+    /// Accepted languages; empty accepts any
+    languages: Vec<String>,
+}
+
+impl Filters {
+    fn check(&self, article: &Article) -> Result<()> {
+        if let Some(min) = self.min_words {
+            let words = article.text_content.split_whitespace().count();
+            if words < min {
+                return Err(exit::TooShort { words, min }.into());
+            }
+        }
+        if let Some(language) = &article.language
+            && !self.languages.is_empty()
+        {
+            let primary = language.split(['-', '_']).next().unwrap_or_default();
+            if !self.languages.iter().any(|lang| {
+                lang.eq_ignore_ascii_case(primary) || lang.eq_ignore_ascii_case(language)
+            }) {
+                return Err(exit::WrongLanguage(language.clone()).into());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// What happens to every input once it is read, shared by single and batch runs.
+struct Pipeline<'a> {
+    parser: &'a Readability,
+    fetcher: &'a Fetcher,
+    variants: &'a [Variant],
+    /// Tags and save times of bookmarked and imported pages, by normalized URL
+    saved: &'a HashMap<String, SavedItem>,
+    archive: Option<&'a Archive>,
+    filters: &'a Filters,
+    output: &'a OutputOptions,
+    explain: bool,
+    diff: bool,
+}
+
+impl Pipeline<'_> {
+    /// Archive `input`, extract its article and write it out, or add it to
+    /// `book`. `index` is the position of the input in the run, from 1.
+    fn process(
+        &self,
+        out: &mut impl Write,
+        book: &mut Option<epub::Book>,
+        files: &mut Option<FileNamer>,
+        name: &str,
+        input: Input,
+        index: usize,
+    ) -> Result<()> {
+        let input = Input {
+            saved: self
+                .saved
+                .get(&batch::normalize_input(name.to_string()))
+                .cloned(),
+            ..input
+        };
+        archive_input(self.archive, &input)?;
+        let input = use_variants(self.parser, self.fetcher, input, self.variants);
+        let article = extract(self.parser, &input, name, self.explain)?;
+        self.filters.check(&article)?;
+        if self.diff {
+            return diff::write(out, self.parser, name, &input.html, &article);
+        }
+        if let Some(book) = book {
+            book.add(&article, input.url.as_deref(), self.fetcher);
+            return Ok(());
+        }
+        let path = files
+            .as_mut()
+            .map(|files| files.next_path(&article, input.url.as_deref(), index));
+        write_output(out, path, self.parser, self.output, name, article, input)
+    }
+}
+
+/// Extract the article, printing how it was chosen to stderr when `explain` is set.
+fn extract(parser: &Readability, input: &Input, source: &str, explain: bool) -> Result<Article> {
+    let url = input.alternate_url.as_ref().or(input.url.as_ref());
+    if explain {
+        let explanation = parser
+            .parse_explain(&input.html, url.map(String::as_str), None)
+            .wrap_err("extraction")?;
+        eprint!("{}", explain::render(source, &explanation));
+        return Ok(explanation.article);
+    }
+    match url {
+        Some(url) => parser.parse_with_url(&input.html, url),
+        None => parser.parse(&input.html),
+    }
+    .wrap_err("extraction")
+}
+
+/// Alternative versions of a page that may extract better than the page itself.
+#[derive(Clone, Copy)]
+enum Variant {
+    Print,
+    Amp,
+}
+
+impl Variant {
+    fn name(self) -> &'static str {
+        match self {
+            Variant::Print => "print",
+            Variant::Amp => "AMP",
+        }
+    }
+
+    fn find(self, parser: &Readability, html: &str, url: &str) -> Result<Option<String>> {
+        Ok(match self {
+            Variant::Print => parser.find_print_url(html, Some(url))?,
+            Variant::Amp => parser.find_amp_url(html, Some(url))?,
+        })
+    }
+}
+
+/// Swap in the first available variant of a fetched page, keeping the original on failure.
+fn use_variants(
+    parser: &Readability,
+    fetcher: &Fetcher,
+    input: Input,
+    variants: &[Variant],
+) -> Input {
+    let Some(url) = &input.url else {
+        return input;
+    };
+
+    for &variant in variants {
+        let variant_url = match variant.find(parser, &input.html, url) {
+            Ok(Some(variant_url)) if variant_url != *url => variant_url,
+            Ok(_) => continue,
+            Err(e) => {
+                eprintln!(
+                    "warning: {}: could not look for a {} version: {:#}",
+                    url,
+                    variant.name(),
+                    e
+                );
+                continue;
+            }
+        };
+
+        let fetched = url::Url::parse(&variant_url)
+            .wrap_err("invalid url")
+            .and_then(|variant_url| fetcher.fetch_html(&variant_url));
+        match fetched {
+            Ok(html) => {
+                return Input {
+                    html,
+                    alternate_url: Some(variant_url),
+                    ..input
+                };
+            }
+            Err(e) => eprintln!(
+                "warning: {}: could not fetch {} version {}: {:#}",
+                url,
+                variant.name(),
+                variant_url,
+                e
+            ),
+        }
+    }
+    input
+}
+
+/// Write an article to the file at `path` and print the path, or to `out`
+/// when no path is given.
+fn write_output(
+    out: &mut impl Write,
+    path: Option<PathBuf>,
+    parser: &Readability,
+    output: &OutputOptions,
+    source: &str,
+    article: Article,
+    input: Input,
+) -> Result<()> {
+    let Some(path) = path else {
+        return write_article(out, parser, output, source, article, input);
+    };
+    if let Some(dir) = path.parent()
+        && !dir.as_os_str().is_empty()
+    {
+        std::fs::create_dir_all(dir)
+            .wrap_err_with(|| format!("could not create directory {:#?}", dir))?;
+    }
+    let mut file = io::BufWriter::new(
+        File::create(&path).wrap_err_with(|| format!("could not create {:#?}", path))?,
+    );
+    write_article(&mut file, parser, output, source, article, input)?;
+    file.flush()
+        .wrap_err_with(|| format!("could not write {:#?}", path))?;
+    writeln!(out, "{}", path.display())?;
+    Ok(())
+}
+
+fn write_article(
+    out: &mut impl Write,
+    parser: &Readability,
+    output: &OutputOptions,
+    source: &str,
+    mut article: Article,
+    input: Input,
+) -> Result<()> {
+    if let Some(style) = output.metadata {
+        let record = MetadataRecord::new(source, input.url, article);
+        match style {
+            MetadataStyle::Json => {
+                serde_json::to_writer(&mut *out, &record)?;
+                out.write_all(b"\n")?;
+            }
+            MetadataStyle::Table => out.write_all(record.table_row().as_bytes())?,
+        }
+        return Ok(());
+    }
+
+    let toc = if output.toc {
+        toc::Toc::of(&article)
+    } else {
+        None
+    };
+    if let Some(toc) = &toc
+        && output.format != Format::Markdown
+    {
+        article.content = toc.html() + &toc.anchor(&article.content);
+    }
+
+    match output.format {
+        Format::Markdown => {}
+        Format::Html => {
+            out.write_all(article.content.as_bytes())?;
+            return Ok(());
+        }
+        Format::Text => {
+            out.write_all(article.plain_text().as_bytes())?;
+            return Ok(());
+        }
+        Format::HtmlPage => {
+            let url = input.alternate_url.as_ref().or(input.url.as_ref());
+            let html = page::render(&article, url.map(String::as_str), &output.page);
+            out.write_all(html.as_bytes())?;
+            return Ok(());
+        }
+        Format::Rst => {
+            let rst = parser
+                .to_rst(&article)
+                .wrap_err("rendering reStructuredText")?;
+            out.write_all(rst.as_bytes())?;
+            return Ok(());
+        }
+        Format::Epub => unreachable!("EPUB chapters are collected into the --bundle book"),
+        Format::Ndjson => {
+            let snapshot = input.snapshot;
+            let saved = input.saved.unwrap_or_default();
+            let record = NdjsonRecord {
+                source: source.to_string(),
+                url: input.url,
+                extracted_from: input.alternate_url,
+                archive_url: snapshot.as_ref().map(|s| s.url.clone()),
+                archived_at: snapshot.map(|s| s.timestamp),
+                tags: saved.tags,
+                saved_at: saved.saved_at,
+                error: None,
+                article: Some(article),
+                engine: Some(Readability::engine_info()),
+            };
+            return write_record(out, &record);
+        }
+    }
+
+    let markdown = html2md::parse_html(&article.content);
+    if output.render_terminal {
+        let mut header = format!("# {}\n\n", article.title.trim());
+        let source: Vec<&str> = [article.byline.as_deref(), article.site_name.as_deref()]
+            .into_iter()
+            .flatten()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .collect();
+        if !source.is_empty() {
+            header.push_str(&format!("*{}*\n\n", source.join(" · ")));
+        }
+        // Only a terminal knows what to do with hyperlinks
+        let hyperlinks = io::stdout().is_terminal();
+        out.write_all(render_terminal(&(header + &markdown), hyperlinks)?.as_bytes())?;
+        return Ok(());
+    }
+    if let Some(frontmatter) = output.frontmatter {
+        let mut metadata = ArticleMetadata::from(article);
+        metadata.url = input.url;
+        metadata.extracted_from = input.alternate_url;
+        if let Some(snapshot) = input.snapshot {
+            metadata.archive_url = Some(snapshot.url);
+            metadata.archived_at = Some(snapshot.timestamp);
+        }
+        if let Some(saved) = input.saved {
+            metadata.tags = saved.tags;
+            metadata.saved_at = saved.saved_at;
+        }
+        write_frontmatter(out, frontmatter, &metadata)?;
+    }
+    if let Some(toc) = &toc {
+        out.write_all(toc.markdown().as_bytes())?;
+    }
+    out.write_all(markdown.as_bytes())?;
+    Ok(())
+}
+
+fn write_frontmatter(
+    out: &mut impl Write,
+    format: FrontmatterFormat,
+    metadata: &ArticleMetadata,
+) -> Result<()> {
+    match format {
+        FrontmatterFormat::Yaml => {
+            out.write_all(b"---\n")?;
+            serde_yaml::to_writer(&mut *out, metadata)?;
+            out.write_all(b"---\n")?;
+        }
+        FrontmatterFormat::Toml => {
+            out.write_all(b"+++\n")?;
+            out.write_all(toml::to_string(metadata)?.as_bytes())?;
+            out.write_all(b"+++\n")?;
+        }
+        FrontmatterFormat::Json => {
+            serde_json::to_writer_pretty(&mut *out, metadata)?;
+            out.write_all(b"\n\n")?;
+        }
+    }
+    Ok(())
+}
+
+/// Style Markdown for the terminal, wrapped to its width.
+///
+/// With `hyperlinks`, links show only their text and are made clickable with
+/// OSC 8 escape sequences, instead of printing the raw Markdown.
+#[cfg(feature = "terminal")]
+fn render_terminal(markdown: &str, hyperlinks: bool) -> Result<String> {
+    let skin = termimad::MadSkin::default();
+    if !hyperlinks {
+        return Ok(skin.term_text(markdown).to_string());
+    }
+    let (markdown, urls) = hyperlink::mark(markdown);
+    let text = skin.term_text(&markdown).to_string();
+    Ok(hyperlink::apply(&text, &urls))
+}
+
+#[cfg(not(feature = "terminal"))]
+fn render_terminal(_markdown: &str, _hyperlinks: bool) -> Result<String> {
+    bail!("readable was built without terminal rendering support")
+}
+
+/// Write the NDJSON line of an input that failed.
+fn write_error(out: &mut impl Write, source: &str, error: &color_eyre::Report) -> Result<()> {
+    let record = NdjsonRecord {
+        source: source.to_string(),
+        error: Some(format!("{:#}", error)),
+        ..Default::default()
+    };
+    write_record(out, &record)
+}
+
+fn write_record(out: &mut impl Write, record: &NdjsonRecord) -> Result<()> {
+    serde_json::to_writer(&mut *out, record)?;
+    out.write_all(b"\n")?;
+    Ok(())
+}
+
+fn read_input_list(path: &Path) -> Result<Vec<String>> {
+    let mut list = String::new();
+    if path == Path::new("-") {
+        io::stdin()
+            .lock()
+            .read_to_string(&mut list)
+            .wrap_err("could not read input list from stdin")?;
     } else {
-        io::stdout().lock().write_all(article.content.as_bytes())?;
+        File::open(path)
+            .and_then(|mut file| file.read_to_string(&mut list))
+            .wrap_err_with(|| format!("could not read input list {:#?}", path))?;
     }
+    Ok(list
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Add the URLs of `items` to `inputs` and remember what is known about them.
+fn add_saved(
+    inputs: &mut Vec<String>,
+    saved: &mut HashMap<String, SavedItem>,
+    items: Vec<SavedItem>,
+) {
+    for item in items {
+        inputs.push(item.url.clone());
+        saved.insert(batch::normalize_input(item.url.clone()), item);
+    }
+}
 
+/// Keep the raw HTML of a fetched input in `archive`, if given.
+fn archive_input(archive: Option<&Archive>, input: &Input) -> Result<()> {
+    if let Some(archive) = archive
+        && let (Some(url), Some(final_url)) = (&input.url, &input.final_url)
+    {
+        archive.save(url, final_url, &input.html, input.snapshot.as_ref())?;
+    }
     Ok(())
 }
 
-fn get_html(input: Option<String>) -> Result<(String, Option<String>)> {
+fn get_html(input: Option<String>, fetcher: &Fetcher) -> Result<Input> {
     if input.is_none() {
         // Nothing is given, read stdin
         let mut html = String::new();
@@ -118,7 +1354,14 @@ fn get_html(input: Option<String>) -> Result<(String, Option<String>)> {
             .lock()
             .read_to_string(&mut html)
             .wrap_err("could not read stdin")?;
-        return Ok((html, None));
+        return Ok(Input {
+            html,
+            url: None,
+            snapshot: None,
+            final_url: None,
+            saved: None,
+            alternate_url: None,
+        });
     }
     let input = input.unwrap();
 
@@ -133,48 +1376,120 @@ fn get_html(input: Option<String>) -> Result<(String, Option<String>)> {
             File::open(&path).wrap_err_with(|| format!("could not open file {:#?}", path))?;
         file.read_to_string(&mut html)
             .wrap_err_with(|| format!("could not read file {:#?}", path))?;
-        return Ok((html, None));
+        return Ok(Input {
+            html,
+            url: None,
+            snapshot: None,
+            final_url: None,
+            saved: None,
+            alternate_url: None,
+        });
+    }
+
+    if let Some(url) = fetch::try_parse_url(&input) {
+        let page = fetcher
+            .fetch(&url)
+            .wrap_err_with(|| exit::FetchFailed(url.to_string()))?;
+        return Ok(Input {
+            html: page.html,
+            url: Some(url.to_string()),
+            snapshot: page.snapshot,
+            final_url: Some(page.final_url),
+            saved: None,
+            alternate_url: None,
+        });
     }
 
-    if let Some(url) = try_parse_url(&input) {
-        let body: String = ureq::get(url.as_str())
-            .header("Accept", "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.")
-            .header("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/121.0.0.0 Safari/537.36")
-            .call()
-            .wrap_err("requesting url")?
-            .body_mut()
-            .read_to_string()
-            .wrap_err("reading response")?;
-        return Ok((body, Some(url.to_string())));
+    if input.contains("://") {
+        return Err(exit::InvalidUrl(input).into());
     }
 
     // error out with file not found
     bail!("file not found: {}", &input);
 }
 
-fn try_parse_url(input: &str) -> Option<Url> {
-    // Helper function to validate URL
-    let is_valid_http_url = |url: &Url| -> bool {
-        (url.scheme() == "http" || url.scheme() == "https")
-            && url.host_str().is_some()
-            && !url.host_str().unwrap().is_empty()
-            && !url.host_str().unwrap().contains("..")
-            && url.host_str().unwrap().contains('.')
-    };
+/// One line of `--format ndjson` output.
+#[derive(Debug, Default, Serialize)]
+struct NdjsonRecord {
+    source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    extracted_from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    archive_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    archived_at: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    saved_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(flatten)]
+    article: Option<Article>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    engine: Option<EngineInfo>,
+}
 
-    if let Ok(url) = Url::parse(input)
-        && is_valid_http_url(&url)
-    {
-        return Some(url);
+/// One article in `--metadata` output.
+#[derive(Debug, Serialize)]
+struct MetadataRecord {
+    source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    title: String,
+    byline: Option<String>,
+    published_time: Option<String>,
+    modified_time: Option<String>,
+    site_name: Option<String>,
+    excerpt: Option<String>,
+    language: Option<String>,
+    words: usize,
+}
+
+impl MetadataRecord {
+    const TABLE_HEADER: &str =
+        "source\ttitle\tbyline\tpublished\tmodified\tsite\tlanguage\twords\texcerpt\n";
+
+    fn new(source: &str, url: Option<String>, a: Article) -> Self {
+        Self {
+            source: source.to_string(),
+            url,
+            words: a.text_content.split_whitespace().count(),
+            title: a.title,
+            byline: a.byline,
+            published_time: a.published_time,
+            modified_time: a.modified_time,
+            site_name: a.site_name,
+            excerpt: a.excerpt,
+            language: a.language,
+        }
     }
 
-    let https_attempt = format!("https://{}", &input);
-    if let Ok(url) = Url::parse(&https_attempt)
-        && is_valid_http_url(&url)
-    {
-        return Some(url);
+    fn table_row(&self) -> String {
+        // Tabs and line breaks inside values would break the columns
+        let cell = |value: Option<&str>| {
+            value
+                .unwrap_or_default()
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let words = self.words.to_string();
+        let cells = [
+            cell(Some(&self.source)),
+            cell(Some(&self.title)),
+            cell(self.byline.as_deref()),
+            cell(self.published_time.as_deref()),
+            cell(self.modified_time.as_deref()),
+            cell(self.site_name.as_deref()),
+            cell(self.language.as_deref()),
+            words,
+            cell(self.excerpt.as_deref()),
+        ];
+        format!("{}\n", cells.join("\t"))
     }
-    None
 }
 
 #[derive(Debug, Serialize)]
@@ -194,6 +1509,24 @@ struct ArticleMetadata {
     language: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     published_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    modified_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    section: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    image: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    icon: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    extracted_from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    archive_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    archived_at: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    saved_at: Option<String>,
 }
 
 impl From<Article> for ArticleMetadata {
@@ -208,6 +1541,15 @@ impl From<Article> for ArticleMetadata {
             site_name: a.site_name,
             language: a.language,
             published_time: a.published_time,
+            modified_time: a.modified_time,
+            section: a.section,
+            image: a.lead_image_url,
+            icon: a.site_icon_url,
+            extracted_from: None,
+            archive_url: None,
+            archived_at: None,
+            tags: Vec::new(),
+            saved_at: None,
         }
     }
 }
@@ -0,0 +1,211 @@
+use readability_js::Article;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use url::Url;
+
+/// Fields a name template can use, for error messages.
+const FIELDS: &str = "{slug}, {domain}, {date}, {index}";
+
+#[derive(Clone, Debug)]
+enum Part {
+    Literal(String),
+    /// The article title, lowercased with runs of other characters turned into `-`
+    Slug,
+    /// The page's host, without a leading `www.`
+    Domain,
+    /// The published date as `YYYY-MM-DD`, or `undated`
+    Date,
+    /// Position among the inputs, starting at 1 and zero-padded to sort
+    Index,
+}
+
+/// A file name pattern such as `{date}-{slug}.md`.
+#[derive(Clone, Debug)]
+pub struct NameTemplate(Vec<Part>);
+
+impl NameTemplate {
+    pub fn parse(template: &str) -> Result<Self, String> {
+        let mut parts = Vec::new();
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            if start > 0 {
+                parts.push(Part::Literal(rest[..start].to_string()));
+            }
+            let Some(len) = rest[start..].find('}') else {
+                return Err(format!("unclosed {{ in {:?}", template));
+            };
+            parts.push(match &rest[start + 1..start + len] {
+                "slug" => Part::Slug,
+                "domain" => Part::Domain,
+                "date" => Part::Date,
+                "index" => Part::Index,
+                field => {
+                    return Err(format!(
+                        "unknown field {{{}}}, expected one of {}",
+                        field, FIELDS
+                    ));
+                }
+            });
+            rest = &rest[start + len + 1..];
+        }
+        if !rest.is_empty() {
+            parts.push(Part::Literal(rest.to_string()));
+        }
+        Ok(Self(parts))
+    }
+}
+
+/// Names output files after a [`NameTemplate`], never handing out the same
+/// path twice.
+pub struct FileNamer {
+    template: NameTemplate,
+    /// Digits `{index}` is padded to
+    width: usize,
+    used: HashSet<PathBuf>,
+}
+
+impl FileNamer {
+    /// Create a namer for a run over `total` inputs.
+    pub fn new(template: NameTemplate, total: usize) -> Self {
+        Self {
+            template,
+            width: total.to_string().len(),
+            used: HashSet::new(),
+        }
+    }
+
+    /// Path for the article from the `index`th input (counting from 1).
+    ///
+    /// When the name is already taken, `-2`, `-3` and so on are added before
+    /// the extension.
+    pub fn next_path(&mut self, article: &Article, url: Option<&str>, index: usize) -> PathBuf {
+        let mut name = String::new();
+        for part in &self.template.0 {
+            match part {
+                Part::Literal(text) => name.push_str(text),
+                Part::Slug => name.push_str(&slug(&article.title)),
+                Part::Domain => name.push_str(&domain(url)),
+                Part::Date => name.push_str(&date(article.published_time.as_deref())),
+                Part::Index => name.push_str(&format!("{:0width$}", index, width = self.width)),
+            }
+        }
+
+        let mut path = PathBuf::from(&name);
+        let mut n = 2;
+        while self.used.contains(&path) {
+            let numbered = match name.rfind('.').filter(|&dot| !name[dot..].contains('/')) {
+                Some(dot) => format!("{}-{}{}", &name[..dot], n, &name[dot..]),
+                None => format!("{}-{}", name, n),
+            };
+            path = PathBuf::from(numbered);
+            n += 1;
+        }
+        self.used.insert(path.clone());
+        path
+    }
+}
+
+fn slug(title: &str) -> String {
+    let mut slug = String::new();
+    for c in title.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug: String = slug.chars().take(80).collect();
+    match slug.trim_end_matches('-') {
+        "" => "untitled".to_string(),
+        slug => slug.to_string(),
+    }
+}
+
+fn domain(url: Option<&str>) -> String {
+    url.and_then(|url| Url::parse(url).ok())
+        .and_then(|url| url.host_str().map(str::to_string))
+        .map(|host| host.trim_start_matches("www.").to_string())
+        .unwrap_or_else(|| "local".to_string())
+}
+
+fn date(published: Option<&str>) -> String {
+    // Published times are ISO 8601 when known, so the date is the first part
+    match published.map(str::trim) {
+        Some(time)
+            if time.len() >= 10
+                && time.bytes().take(10).enumerate().all(|(i, b)| match i {
+                    4 | 7 => b == b'-',
+                    _ => b.is_ascii_digit(),
+                }) =>
+        {
+            time[..10].to_string()
+        }
+        _ => "undated".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn article(title: &str, published: Option<&str>) -> Article {
+        Article {
+            title: title.into(),
+            published_time: published.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_template() {
+        assert!(NameTemplate::parse("{date}-{slug}.md").is_ok());
+        assert!(NameTemplate::parse("plain.md").is_ok());
+        assert_eq!(
+            NameTemplate::parse("{title}.md").unwrap_err(),
+            "unknown field {title}, expected one of {slug}, {domain}, {date}, {index}"
+        );
+        assert_eq!(
+            NameTemplate::parse("{slug.md").unwrap_err(),
+            "unclosed { in \"{slug.md\""
+        );
+    }
+
+    #[test]
+    fn test_fields() {
+        let template = NameTemplate::parse("{date}-{domain}-{index}-{slug}.md").unwrap();
+        let mut namer = FileNamer::new(template, 12);
+        let path = namer.next_path(
+            &article("Hello, World! Ünïcode", Some("2024-05-06T10:00:00Z")),
+            Some("https://www.example.com/post"),
+            3,
+        );
+        assert_eq!(
+            path,
+            PathBuf::from("2024-05-06-example.com-03-hello-world-ünïcode.md")
+        );
+
+        let path = namer.next_path(&article("", None), None, 4);
+        assert_eq!(path, PathBuf::from("undated-local-04-untitled.md"));
+    }
+
+    #[test]
+    fn test_repeated_names_get_suffixes() {
+        let mut namer = FileNamer::new(NameTemplate::parse("{slug}.md").unwrap(), 3);
+        let names: Vec<_> = (1..=3)
+            .map(|i| namer.next_path(&article("Same title", None), None, i))
+            .collect();
+        assert_eq!(
+            names,
+            ["same-title.md", "same-title-2.md", "same-title-3.md"].map(PathBuf::from)
+        );
+
+        // A dot in a directory name is not an extension
+        let mut namer = FileNamer::new(NameTemplate::parse("{domain}/{slug}").unwrap(), 2);
+        let url = Some("https://example.com/a");
+        namer.next_path(&article("A", None), url, 1);
+        assert_eq!(
+            namer.next_path(&article("A", None), url, 2),
+            PathBuf::from("example.com/a-2")
+        );
+    }
+}
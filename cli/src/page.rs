@@ -0,0 +1,148 @@
+use clap::ValueEnum;
+use readability_js::{Article, Direction};
+
+/// Reader-mode styles embedded in every page; colors come from the theme.
+const READER_CSS: &str = "
+body {
+  margin: 0;
+  padding: 2rem 1.25rem 4rem;
+  font: 1.125rem/1.6 Georgia, 'Iowan Old Style', 'Times New Roman', serif;
+  background: var(--bg);
+  color: var(--fg);
+}
+main, header { max-width: 38em; margin: 0 auto; }
+header { margin-bottom: 2.5rem; padding-bottom: 1rem; border-bottom: 1px solid var(--rule); }
+h1, h2, h3, h4, h5, h6 { font-family: system-ui, sans-serif; line-height: 1.25; }
+header h1 { margin: 0 0 .5rem; font-size: 2rem; }
+.meta { margin: 0; color: var(--muted); font: .9rem/1.4 system-ui, sans-serif; }
+a { color: var(--link); }
+img, video, figure { max-width: 100%; height: auto; }
+figure { margin: 1.5rem 0; }
+figcaption { color: var(--muted); font-size: .9rem; }
+pre { overflow-x: auto; padding: 1rem; background: var(--code-bg); font-size: .9rem; }
+code { font-family: ui-monospace, Menlo, Consolas, monospace; }
+blockquote { margin: 1.5rem 0; padding-left: 1rem; border-left: 3px solid var(--rule); color: var(--muted); }
+table { border-collapse: collapse; }
+td, th { padding: .25rem .5rem; border: 1px solid var(--rule); }
+";
+
+/// Color scheme of a standalone page.
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum Theme {
+    /// Dark text on white
+    #[default]
+    Light,
+    /// Light text on a dark background
+    Dark,
+    /// Brown text on warm paper
+    Sepia,
+}
+
+impl Theme {
+    fn css(self) -> &'static str {
+        match self {
+            Theme::Light => {
+                ":root { --bg: #fff; --fg: #222; --muted: #666; --link: #0b57d0; --rule: #ddd; --code-bg: #f5f5f5; }"
+            }
+            Theme::Dark => {
+                ":root { --bg: #1b1b1d; --fg: #ddd; --muted: #999; --link: #8ab4f8; --rule: #3a3a3d; --code-bg: #26262a; }"
+            }
+            Theme::Sepia => {
+                ":root { --bg: #f4ecd8; --fg: #5b4636; --muted: #8a7560; --link: #7a4b1f; --rule: #d8c9aa; --code-bg: #ebe0c7; }"
+            }
+        }
+    }
+}
+
+/// Look of a standalone page.
+#[derive(Default)]
+pub struct PageStyle {
+    pub theme: Theme,
+    /// Extra stylesheet, applied after the built-in one so it can override it
+    pub css: Option<String>,
+}
+
+/// Wrap an article in a standalone HTML document for reading offline.
+///
+/// The metadata goes in a header above the content, and `url`, when
+/// known, is linked as the source.
+pub fn render(article: &Article, url: Option<&str>, style: &PageStyle) -> String {
+    let mut html = String::from("<!DOCTYPE html>\n<html");
+    if let Some(lang) = &article.language {
+        html.push_str(&format!(" lang=\"{}\"", escape(lang)));
+    }
+    if let Some(dir) = &article.direction {
+        html.push_str(match dir {
+            Direction::Ltr => " dir=\"ltr\"",
+            Direction::Rtl => " dir=\"rtl\"",
+        });
+    }
+    html.push_str(">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    html.push_str(&format!("<title>{}</title>\n", escape(&article.title)));
+    if let Some(excerpt) = &article.excerpt {
+        html.push_str(&format!(
+            "<meta name=\"description\" content=\"{}\">\n",
+            escape(excerpt)
+        ));
+    }
+    html.push_str(&format!(
+        "<style>{}\n{}</style>\n",
+        style.theme.css(),
+        READER_CSS
+    ));
+    if let Some(css) = &style.css {
+        // Keep a stray closing tag from ending the style element early
+        html.push_str(&format!(
+            "<style>\n{}\n</style>\n",
+            css.replace("</", "<\\/")
+        ));
+    }
+    html.push_str("</head>\n<body>\n<header>\n");
+    html.push_str(&format!("<h1>{}</h1>\n", escape(&article.title)));
+
+    let mut meta: Vec<String> = [
+        article.byline.as_deref(),
+        article.site_name.as_deref(),
+        article.published_time.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .map(str::trim)
+    .filter(|s| !s.is_empty())
+    .map(escape)
+    .collect();
+    if let Some(url) = url {
+        meta.push(format!("<a href=\"{0}\">{0}</a>", escape(url)));
+    }
+    if !meta.is_empty() {
+        html.push_str(&format!("<p class=\"meta\">{}</p>\n", meta.join(" · ")));
+    }
+    html.push_str("</header>\n<main>\n");
+    html.push_str(&article.content);
+    html.push_str("\n</main>\n</body>\n</html>\n");
+    html
+}
+
+/// Append `text` escaped for HTML and XML, dropping characters XML does not
+/// allow. Quotes are only escaped in attribute values.
+pub fn escape_into(out: &mut String, text: &str, attribute: bool) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' if attribute => out.push_str("&quot;"),
+            '\t' | '\n' | '\r' => out.push(c),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+}
+
+/// `text` escaped for HTML and XML, safe in attribute values too.
+pub fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    escape_into(&mut out, text, true);
+    out
+}
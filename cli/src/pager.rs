@@ -0,0 +1,156 @@
+use crate::clipboard;
+use color_eyre::Result;
+use color_eyre::eyre::{Context, bail};
+use std::env;
+use std::io::{self, IsTerminal, StdoutLock, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Standard output, shown through `$PAGER` when it is longer than the terminal.
+///
+/// Output meant for a terminal is held back until [`Output::finish`], which
+/// pages it if it doesn't fit on one screen. Anything else, such as a pipe or
+/// a file, is written straight through. With `--to-clipboard` or `--open`
+/// everything is held back and sent there instead.
+pub struct Output {
+    stdout: StdoutLock<'static>,
+    buffer: Option<Vec<u8>>,
+    target: Target,
+}
+
+enum Target {
+    Stdout,
+    Clipboard,
+    /// A file with this extension, opened in the default application
+    Open(&'static str),
+}
+
+impl Output {
+    pub fn new(paging: bool) -> Self {
+        let stdout = io::stdout().lock();
+        let buffer = (paging && stdout.is_terminal()).then(Vec::new);
+        Self {
+            stdout,
+            buffer,
+            target: Target::Stdout,
+        }
+    }
+
+    /// Output that is copied to the clipboard by [`Output::finish`].
+    pub fn clipboard() -> Self {
+        Self {
+            stdout: io::stdout().lock(),
+            buffer: Some(Vec::new()),
+            target: Target::Clipboard,
+        }
+    }
+
+    /// Output that [`Output::finish`] saves to a temporary file ending in
+    /// `extension` and opens in the default application for it.
+    pub fn open(extension: &'static str) -> Self {
+        Self {
+            stdout: io::stdout().lock(),
+            buffer: Some(Vec::new()),
+            target: Target::Open(extension),
+        }
+    }
+
+    /// Write out held-back output, through the pager if it is too long.
+    pub fn finish(mut self) -> Result<()> {
+        let Some(buffer) = self.buffer.take() else {
+            return Ok(self.stdout.flush()?);
+        };
+        match self.target {
+            Target::Stdout => {}
+            Target::Clipboard => return clipboard::write(&String::from_utf8_lossy(&buffer)),
+            Target::Open(extension) => {
+                let path =
+                    env::temp_dir().join(format!("readable-{}.{}", std::process::id(), extension));
+                std::fs::write(&path, &buffer)
+                    .wrap_err_with(|| format!("could not write {:#?}", path))?;
+                return open(&path);
+            }
+        }
+        let lines = buffer.iter().filter(|&&b| b == b'\n').count();
+        if lines >= terminal_height() && page(&buffer) {
+            return Ok(());
+        }
+        self.stdout.write_all(&buffer)?;
+        Ok(self.stdout.flush()?)
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match &mut self.buffer {
+            Some(buffer) => buffer.write(buf),
+            None => self.stdout.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.buffer {
+            Some(_) => Ok(()),
+            None => self.stdout.flush(),
+        }
+    }
+}
+
+/// Show `text` in `$PAGER` (`less` by default), returning whether that worked.
+fn page(text: &[u8]) -> bool {
+    let pager = env::var("PAGER").unwrap_or_else(|_| "less".to_string());
+    let mut words = pager.split_whitespace();
+    let Some(program) = words.next() else {
+        return false;
+    };
+
+    let mut command = Command::new(program);
+    command.args(words).stdin(Stdio::piped());
+    // Like git: keep colors, and quit right away if it fits after all
+    if env::var_os("LESS").is_none() {
+        command.env("LESS", "FRX");
+    }
+    let Ok(mut child) = command.spawn() else {
+        return false;
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        // The reader quitting early closes the pipe, which is not an error
+        let _ = stdin.write_all(text);
+    }
+    child.wait().is_ok()
+}
+
+/// Open `path` in the default application for its type.
+fn open(path: &Path) -> Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        // The empty argument is the window title `start` expects first
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    let status = command
+        .arg(path)
+        .status()
+        .wrap_err_with(|| format!("could not open {:#?}", path))?;
+    if !status.success() {
+        bail!("could not open {:#?}", path);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "terminal")]
+fn terminal_height() -> usize {
+    termimad::terminal_size().1 as usize
+}
+
+#[cfg(not(feature = "terminal"))]
+fn terminal_height() -> usize {
+    env::var("LINES")
+        .ok()
+        .and_then(|lines| lines.parse().ok())
+        .unwrap_or(24)
+}
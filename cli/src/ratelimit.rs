@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+#[cfg(feature = "serve")]
+use std::net::IpAddr;
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// Limits how hard a batch run hits any single host.
+///
+/// At most `max_concurrency` requests per host are in flight, and
+/// consecutive requests to the same host start at least `delay` apart.
+pub struct HostLimiter {
+    delay: Duration,
+    max_concurrency: usize,
+    hosts: Mutex<HashMap<String, HostState>>,
+    released: Condvar,
+}
+
+#[derive(Default)]
+struct HostState {
+    active: usize,
+    next_start: Option<Instant>,
+}
+
+/// Releases the host slot when dropped.
+pub struct HostPermit<'a> {
+    limiter: &'a HostLimiter,
+    host: String,
+}
+
+impl HostLimiter {
+    pub fn new(delay: Duration, max_concurrency: usize) -> Self {
+        Self {
+            delay,
+            max_concurrency: max_concurrency.max(1),
+            hosts: Mutex::new(HashMap::new()),
+            released: Condvar::new(),
+        }
+    }
+
+    /// Block until a request to `host` is allowed to start.
+    pub fn acquire(&self, host: &str) -> HostPermit<'_> {
+        let mut hosts = self.hosts.lock().unwrap();
+        loop {
+            let state = hosts.entry(host.to_string()).or_default();
+            if state.active < self.max_concurrency {
+                let now = Instant::now();
+                let start = state.next_start.map_or(now, |next| next.max(now));
+                state.active += 1;
+                state.next_start = Some(start + self.delay);
+                drop(hosts);
+
+                // Slots are reserved in order, so sleeping outside the lock is fair
+                if start > now {
+                    std::thread::sleep(start - now);
+                }
+                return HostPermit {
+                    limiter: self,
+                    host: host.to_string(),
+                };
+            }
+            hosts = self.released.wait(hosts).unwrap();
+        }
+    }
+}
+
+impl Drop for HostPermit<'_> {
+    fn drop(&mut self) {
+        let mut hosts = self.limiter.hosts.lock().unwrap();
+        if let Some(state) = hosts.get_mut(&self.host) {
+            state.active -= 1;
+        }
+        self.limiter.released.notify_all();
+    }
+}
+
+/// Limits how often each client may call the server, with a token bucket
+/// per IP address.
+///
+/// A client can make `per_minute` requests in a burst, after which its
+/// allowance refills at `per_minute` requests a minute.
+#[cfg(feature = "serve")]
+pub struct ClientLimiter {
+    per_minute: u32,
+    clients: Mutex<Clients>,
+}
+
+#[cfg(feature = "serve")]
+struct Clients {
+    buckets: HashMap<IpAddr, Bucket>,
+    /// When buckets that have been idle for a whole refill are next dropped
+    next_sweep: Instant,
+}
+
+#[cfg(feature = "serve")]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Time for an empty bucket to refill, whatever the rate. A bucket idle for
+/// that long is full, which is the same as no bucket at all.
+#[cfg(feature = "serve")]
+const REFILL: Duration = Duration::from_secs(60);
+
+/// Clients remembered at most; past that the least recently seen half is
+/// forgotten.
+#[cfg(feature = "serve")]
+const MAX_CLIENTS: usize = 4096;
+
+#[cfg(feature = "serve")]
+impl ClientLimiter {
+    pub fn new(per_minute: u32) -> Self {
+        Self {
+            per_minute: per_minute.max(1),
+            clients: Mutex::new(Clients {
+                buckets: HashMap::new(),
+                next_sweep: Instant::now() + REFILL,
+            }),
+        }
+    }
+
+    /// Take a request from the allowance of `client`, or tell how long it has
+    /// to wait before the next one is allowed.
+    pub fn check(&self, client: IpAddr) -> Result<(), Duration> {
+        self.check_at(client, Instant::now())
+    }
+
+    fn check_at(&self, client: IpAddr, now: Instant) -> Result<(), Duration> {
+        let capacity = f64::from(self.per_minute);
+        let per_second = capacity / REFILL.as_secs_f64();
+        let mut clients = self.clients.lock().unwrap();
+        clients.evict(now);
+        let bucket = clients.buckets.entry(client).or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+        });
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * per_second).min(capacity);
+        bucket.updated = now;
+        if bucket.tokens < 1.0 {
+            return Err(Duration::from_secs_f64((1.0 - bucket.tokens) / per_second));
+        }
+        bucket.tokens -= 1.0;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serve")]
+impl Clients {
+    /// Drop refilled buckets once per refill period, and make room when a
+    /// new client would go over [`MAX_CLIENTS`]. Both scans are rare enough
+    /// that a request costs O(1) on average.
+    fn evict(&mut self, now: Instant) {
+        if now >= self.next_sweep {
+            self.buckets
+                .retain(|_, bucket| now.duration_since(bucket.updated) < REFILL);
+            self.next_sweep = now + REFILL;
+        }
+        if self.buckets.len() >= MAX_CLIENTS {
+            let mut updated: Vec<Instant> = self.buckets.values().map(|b| b.updated).collect();
+            let cutoff = *updated.select_nth_unstable(MAX_CLIENTS / 2).1;
+            self.buckets.retain(|_, bucket| bucket.updated > cutoff);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serve"))]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    fn client(n: u32) -> IpAddr {
+        IpAddr::V4(Ipv4Addr::from(n))
+    }
+
+    #[test]
+    fn test_client_limiter_burst() {
+        let limiter = ClientLimiter::new(3);
+        let now = Instant::now();
+        for _ in 0..3 {
+            assert!(limiter.check_at(client(1), now).is_ok());
+        }
+        assert!(limiter.check_at(client(1), now).is_err());
+        // Other clients have their own allowance
+        assert!(limiter.check_at(client(2), now).is_ok());
+    }
+
+    #[test]
+    fn test_client_limiter_refill() {
+        let limiter = ClientLimiter::new(3);
+        let now = Instant::now();
+        for _ in 0..3 {
+            limiter.check_at(client(1), now).unwrap();
+        }
+        // One request every 20 seconds at 3 a minute
+        assert!(
+            limiter
+                .check_at(client(1), now + Duration::from_secs(10))
+                .is_err()
+        );
+        assert!(
+            limiter
+                .check_at(client(1), now + Duration::from_secs(20))
+                .is_ok()
+        );
+        assert!(
+            limiter
+                .check_at(client(1), now + Duration::from_secs(20))
+                .is_err()
+        );
+        // Never more than a full burst, however long the client stayed away
+        let later = now + Duration::from_secs(3600);
+        for _ in 0..3 {
+            assert!(limiter.check_at(client(1), later).is_ok());
+        }
+        assert!(limiter.check_at(client(1), later).is_err());
+    }
+
+    #[test]
+    fn test_client_limiter_wait() {
+        let limiter = ClientLimiter::new(6);
+        let now = Instant::now();
+        for _ in 0..6 {
+            limiter.check_at(client(1), now).unwrap();
+        }
+        let wait = limiter.check_at(client(1), now).unwrap_err();
+        assert!((wait.as_secs_f64() - 10.0).abs() < 1e-6, "{:?}", wait);
+        let wait = limiter
+            .check_at(client(1), now + Duration::from_secs(4))
+            .unwrap_err();
+        assert!((wait.as_secs_f64() - 6.0).abs() < 1e-6, "{:?}", wait);
+    }
+
+    #[test]
+    fn test_client_limiter_eviction() {
+        let limiter = ClientLimiter::new(1);
+        let now = Instant::now();
+        let len = |limiter: &ClientLimiter| limiter.clients.lock().unwrap().buckets.len();
+
+        for n in 0..MAX_CLIENTS as u32 * 3 {
+            limiter
+                .check_at(client(n), now + Duration::from_millis(u64::from(n)))
+                .unwrap();
+            assert!(len(&limiter) <= MAX_CLIENTS);
+        }
+        // The most recent clients are still limited
+        let last = MAX_CLIENTS as u32 * 3 - 1;
+        let at = now + Duration::from_millis(u64::from(last));
+        assert!(limiter.check_at(client(last), at).is_err());
+
+        // Idle clients are swept once their buckets have refilled
+        limiter
+            .check_at(client(u32::MAX), at + REFILL + Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(len(&limiter), 1);
+    }
+}
@@ -0,0 +1,628 @@
+use color_eyre::Result;
+use color_eyre::eyre::{Context, eyre};
+use readability_js::{Article, EngineInfo, Readability, ReadabilityError, ReadabilityOptions};
+use serde::{Deserialize, Serialize};
+use std::io::{Cursor, Read};
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+use tiny_http::{Header, Method, Request, Response, Server};
+use url::Url;
+
+use crate::fetch::Fetcher;
+use crate::ratelimit::ClientLimiter;
+
+#[derive(clap::Args)]
+pub struct ServeArgs {
+    #[arg(
+        long,
+        default_value = "127.0.0.1:3000",
+        help = "Address to listen on",
+        long_help = "Address and port the HTTP server binds to.
+Use 0.0.0.0 to accept connections from other hosts."
+    )]
+    listen: String,
+
+    #[arg(
+        long,
+        help = "Number of extraction workers [default: number of CPUs]",
+        long_help = "Number of extraction workers. Each worker owns its own JavaScript engine
+and handles one request at a time. Defaults to the number of available CPUs."
+    )]
+    workers: Option<usize>,
+
+    #[arg(
+        long,
+        default_value_t = 10 * 1024 * 1024,
+        help = "Maximum size of a document in bytes",
+        long_help = "Maximum size in bytes of an HTML document, whether posted directly or fetched
+from a URL. Larger documents are rejected with 413 Payload Too Large."
+    )]
+    max_document_size: usize,
+
+    #[arg(
+        long,
+        value_name = "BYTES",
+        help = "Maximum size of a request body in bytes [default: --max-document-size]",
+        long_help = "Maximum size in bytes of a POST /extract request body, raw HTML or JSON.
+Larger bodies are rejected with 413 Payload Too Large, before they are read
+when the request declares its Content-Length. Defaults to --max-document-size."
+    )]
+    max_body_size: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "REQUESTS",
+        help = "Maximum extraction requests per minute from each client IP",
+        long_help = "Maximum number of POST /extract requests per minute from each client IP
+address. Clients may use their allowance in a burst; further requests are
+answered with 429 Too Many Requests and a Retry-After header. Unlimited
+when not given."
+    )]
+    rate_limit: Option<u32>,
+
+    #[arg(
+        long = "api-key",
+        value_name = "KEY",
+        env = "READABLE_API_KEY",
+        hide_env_values = true,
+        value_delimiter = ',',
+        help = "Require this API key for extraction requests",
+        long_help = "Require clients to send this key with POST /extract, as
+\"Authorization: Bearer KEY\" or \"X-API-Key: KEY\"; other requests are answered
+with 401 Unauthorized. Repeat the flag to accept several keys while rotating
+them. GET /healthz and GET /metrics stay open for probes and scrapers.
+Prefer the READABLE_API_KEY environment variable (comma-separated) to keep
+keys out of the process list."
+    )]
+    api_keys: Vec<String>,
+
+    #[arg(
+        long,
+        default_value_t = 30,
+        help = "Maximum extraction time per request in seconds",
+        long_help = "Maximum time in seconds a single extraction may run. Slower extractions are
+interrupted and answered with 504 Gateway Timeout, freeing the worker. Fetching a `url` is
+held to the same limit, and a fetch that runs out of it is answered with 502 Bad Gateway."
+    )]
+    timeout: u64,
+}
+
+/// Body of a JSON `POST /extract` request.
+///
+/// When `html` is missing the page is fetched from `url`, otherwise `url`
+/// is only used as the base URL for link resolution.
+#[derive(Deserialize)]
+struct ExtractRequest {
+    url: Option<String>,
+    html: Option<String>,
+    options: Option<ReadabilityOptions>,
+}
+
+/// Response to a successful `POST /extract`: the article, with the engine
+/// that extracted it.
+#[derive(Serialize)]
+struct ExtractBody<'a> {
+    #[serde(flatten)]
+    article: Article,
+    engine: &'a EngineInfo,
+}
+
+#[derive(Serialize)]
+struct ErrorBody<'a> {
+    error: &'a str,
+}
+
+#[derive(Serialize)]
+struct HealthBody<'a> {
+    status: &'static str,
+    workers: usize,
+    engine: &'a EngineInfo,
+}
+
+struct HttpError {
+    status: u16,
+    message: String,
+    header: Option<Header>,
+}
+
+impl HttpError {
+    fn new(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+            header: None,
+        }
+    }
+
+    fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.header = Some(
+            Header::from_bytes(name.as_bytes(), value.as_bytes())
+                .expect("error headers are valid header values"),
+        );
+        self
+    }
+}
+
+impl From<ReadabilityError> for HttpError {
+    fn from(e: ReadabilityError) -> Self {
+        let status = match e {
+            ReadabilityError::InvalidOptions(_) => 400,
+            ReadabilityError::HtmlParseError(_)
+            | ReadabilityError::ReadabilityCheckFailed
+            | ReadabilityError::ExtractionError(_)
+            | ReadabilityError::ElementLimitExceeded(_)
+            | ReadabilityError::MemoryLimitExceeded => 422,
+            ReadabilityError::InputTooLarge { .. } => 413,
+            ReadabilityError::Timeout => 504,
+            ReadabilityError::Fetch(_) => 502,
+            ReadabilityError::JsEvaluation { .. }
+            | ReadabilityError::Serialization(_)
+            | ReadabilityError::Io(_)
+            | ReadabilityError::Internal(_) => 500,
+        };
+        Self::new(status, e.to_string())
+    }
+}
+
+/// Counters exposed on `/metrics` in the Prometheus text format.
+#[derive(Default)]
+struct Metrics {
+    workers: AtomicUsize,
+    busy_workers: AtomicUsize,
+    requests: AtomicU64,
+    extractions: AtomicU64,
+    failed_extractions: AtomicU64,
+    timeouts: AtomicU64,
+    too_large: AtomicU64,
+    rate_limited: AtomicU64,
+    unauthorized: AtomicU64,
+    extraction_millis: AtomicU64,
+}
+
+impl Metrics {
+    fn render(&self) -> String {
+        let metrics: [(&str, &str, &str, f64); 10] = [
+            (
+                "readable_workers",
+                "gauge",
+                "Number of extraction workers",
+                self.workers.load(Ordering::Relaxed) as f64,
+            ),
+            (
+                "readable_busy_workers",
+                "gauge",
+                "Number of workers currently handling a request",
+                self.busy_workers.load(Ordering::Relaxed) as f64,
+            ),
+            (
+                "readable_requests_total",
+                "counter",
+                "HTTP requests received",
+                self.requests.load(Ordering::Relaxed) as f64,
+            ),
+            (
+                "readable_extractions_total",
+                "counter",
+                "Successful extractions",
+                self.extractions.load(Ordering::Relaxed) as f64,
+            ),
+            (
+                "readable_failed_extractions_total",
+                "counter",
+                "Failed extractions, including timeouts and oversized documents",
+                self.failed_extractions.load(Ordering::Relaxed) as f64,
+            ),
+            (
+                "readable_timeouts_total",
+                "counter",
+                "Extractions interrupted by the time limit",
+                self.timeouts.load(Ordering::Relaxed) as f64,
+            ),
+            (
+                "readable_too_large_total",
+                "counter",
+                "Documents and request bodies rejected by the size limits",
+                self.too_large.load(Ordering::Relaxed) as f64,
+            ),
+            (
+                "readable_rate_limited_total",
+                "counter",
+                "Requests rejected by the per-client rate limit",
+                self.rate_limited.load(Ordering::Relaxed) as f64,
+            ),
+            (
+                "readable_unauthorized_total",
+                "counter",
+                "Requests rejected for a missing or wrong API key",
+                self.unauthorized.load(Ordering::Relaxed) as f64,
+            ),
+            (
+                "readable_extraction_seconds_total",
+                "counter",
+                "Time spent handling extraction requests",
+                self.extraction_millis.load(Ordering::Relaxed) as f64 / 1000.0,
+            ),
+        ];
+
+        let mut out = String::new();
+        for (name, kind, help, value) in metrics {
+            out.push_str(&format!(
+                "# HELP {name} {help}\n# TYPE {name} {kind}\n{name} {value}\n"
+            ));
+        }
+        out
+    }
+}
+
+struct Worker {
+    parser: Readability,
+    fetcher: Fetcher,
+    metrics: Arc<Metrics>,
+    max_document_size: usize,
+    max_body_size: usize,
+    limiter: Option<Arc<ClientLimiter>>,
+    api_keys: Arc<[String]>,
+    engine: EngineInfo,
+}
+
+/// Serve extraction requests; with `offline`, requests without HTML fail
+/// instead of fetching their URL. `insecure` and `ca_file` apply to those
+/// fetches as in [`Fetcher::with_tls`].
+pub fn run(args: ServeArgs, offline: bool, insecure: bool, ca_file: Option<&Path>) -> Result<()> {
+    simple_logger::init_with_level(log::Level::Info).wrap_err("could not initialize logger")?;
+
+    let server = Arc::new(
+        Server::http(&args.listen)
+            .map_err(|e| eyre!("could not listen on {}: {}", args.listen, e))?,
+    );
+    let metrics = Arc::new(Metrics::default());
+    let workers = args
+        .workers
+        .unwrap_or_else(|| thread::available_parallelism().map_or(1, |n| n.get()))
+        .max(1);
+    let limiter = args.rate_limit.map(|n| Arc::new(ClientLimiter::new(n)));
+    let api_keys: Arc<[String]> = args.api_keys.into();
+    if api_keys.iter().any(|key| key.trim().is_empty()) {
+        return Err(eyre!("API keys must not be empty"));
+    }
+
+    // Readability is !Send, so every worker builds its own instance
+    // and reports back once it is ready to take requests.
+    let (ready_tx, ready_rx) = mpsc::channel();
+    let mut handles = Vec::with_capacity(workers);
+    for id in 0..workers {
+        let server = server.clone();
+        let metrics = metrics.clone();
+        let ready_tx = ready_tx.clone();
+        let timeout = Duration::from_secs(args.timeout);
+        let max_document_size = args.max_document_size;
+        let max_body_size = args.max_body_size.unwrap_or(max_document_size);
+        let limiter = limiter.clone();
+        let api_keys = api_keys.clone();
+        let fetcher = Fetcher::default()
+            .with_tls(insecure, ca_file)?
+            .with_offline(offline)
+            .with_timeout(timeout);
+        let handle = thread::Builder::new()
+            .name(format!("worker-{}", id))
+            .spawn(move || {
+                let parser = match Readability::new() {
+                    Ok(parser) => parser.with_timeout(timeout),
+                    Err(e) => {
+                        let _ = ready_tx.send(Err(e));
+                        return;
+                    }
+                };
+                let _ = ready_tx.send(Ok(()));
+                let worker = Worker {
+                    parser,
+                    fetcher,
+                    metrics,
+                    max_document_size,
+                    max_body_size,
+                    limiter,
+                    api_keys,
+                    engine: Readability::engine_info(),
+                };
+                worker.metrics.workers.fetch_add(1, Ordering::Relaxed);
+                while let Ok(request) = server.recv() {
+                    worker.serve(request);
+                }
+            })
+            .wrap_err("could not spawn worker thread")?;
+        handles.push(handle);
+    }
+    for ready in ready_rx.iter().take(workers) {
+        ready.wrap_err("could not create Readability")?;
+    }
+
+    log::info!(
+        "listening on http://{} with {} workers",
+        args.listen,
+        workers
+    );
+    for handle in handles {
+        let _ = handle.join();
+    }
+
+    Ok(())
+}
+
+impl Worker {
+    fn serve(&self, mut request: Request) {
+        self.metrics.requests.fetch_add(1, Ordering::Relaxed);
+        self.metrics.busy_workers.fetch_add(1, Ordering::Relaxed);
+        let started = Instant::now();
+
+        let response = self.handle(&mut request);
+        log::info!(
+            "{} {} -> {} ({:?})",
+            request.method(),
+            request.url(),
+            response.status_code().0,
+            started.elapsed()
+        );
+        if let Err(e) = request.respond(response) {
+            log::warn!("could not send response: {}", e);
+        }
+
+        self.metrics.busy_workers.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    fn handle(&self, request: &mut Request) -> Response<Cursor<Vec<u8>>> {
+        let (path, query) = match request.url().split_once('?') {
+            Some((path, query)) => (path.to_string(), Some(query.to_string())),
+            None => (request.url().to_string(), None),
+        };
+
+        let result = match (request.method(), path.as_str()) {
+            (Method::Get, "/healthz") => {
+                return json_response(
+                    200,
+                    &HealthBody {
+                        status: "ok",
+                        workers: self.metrics.workers.load(Ordering::Relaxed),
+                        engine: &self.engine,
+                    },
+                );
+            }
+            (Method::Get, "/metrics") => {
+                return text_response(
+                    200,
+                    "text/plain; version=0.0.4",
+                    self.metrics.render().into_bytes(),
+                );
+            }
+            (Method::Post, "/extract") => self
+                .admit(request)
+                .and_then(|()| self.timed_extract(request, query.as_deref())),
+            (_, "/extract" | "/healthz" | "/metrics") => {
+                Err(HttpError::new(405, "method not allowed"))
+            }
+            _ => Err(HttpError::new(404, "not found")),
+        };
+
+        match result {
+            Ok(article) => json_response(
+                200,
+                &ExtractBody {
+                    article,
+                    engine: &self.engine,
+                },
+            ),
+            Err(e) => {
+                let response = json_response(e.status, &ErrorBody { error: &e.message });
+                match e.header {
+                    Some(header) => response.with_header(header),
+                    None => response,
+                }
+            }
+        }
+    }
+
+    /// Apply the rate limit and API key check to an extraction request.
+    ///
+    /// The rate limit comes first so it also slows down guessing keys.
+    fn admit(&self, request: &Request) -> std::result::Result<(), HttpError> {
+        if let (Some(limiter), Some(addr)) = (&self.limiter, request.remote_addr())
+            && let Err(wait) = limiter.check(addr.ip())
+        {
+            self.metrics.rate_limited.fetch_add(1, Ordering::Relaxed);
+            return Err(HttpError::new(429, "too many requests")
+                .with_header("Retry-After", &retry_after(wait)));
+        }
+
+        if !self.api_keys.is_empty() {
+            let given = request.headers().iter().find_map(|h| {
+                if h.field.equiv("X-API-Key") {
+                    Some(h.value.as_str().trim())
+                } else if h.field.equiv("Authorization") {
+                    h.value.as_str().strip_prefix("Bearer ").map(str::trim)
+                } else {
+                    None
+                }
+            });
+            let authorized = given.is_some_and(|given| {
+                self.api_keys
+                    .iter()
+                    .any(|key| constant_time_eq(key.as_bytes(), given.as_bytes()))
+            });
+            if !authorized {
+                self.metrics.unauthorized.fetch_add(1, Ordering::Relaxed);
+                return Err(HttpError::new(401, "missing or invalid API key")
+                    .with_header("WWW-Authenticate", "Bearer"));
+            }
+        }
+        Ok(())
+    }
+
+    fn timed_extract(
+        &self,
+        request: &mut Request,
+        query: Option<&str>,
+    ) -> std::result::Result<Article, HttpError> {
+        let started = Instant::now();
+        let result = self.extract(request, query);
+        self.metrics
+            .extraction_millis
+            .fetch_add(started.elapsed().as_millis() as u64, Ordering::Relaxed);
+
+        match &result {
+            Ok(_) => self.metrics.extractions.fetch_add(1, Ordering::Relaxed),
+            Err(e) => {
+                match e.status {
+                    413 => self.metrics.too_large.fetch_add(1, Ordering::Relaxed),
+                    504 => self.metrics.timeouts.fetch_add(1, Ordering::Relaxed),
+                    _ => 0,
+                };
+                self.metrics
+                    .failed_extractions
+                    .fetch_add(1, Ordering::Relaxed)
+            }
+        };
+        result
+    }
+
+    fn extract(
+        &self,
+        request: &mut Request,
+        query: Option<&str>,
+    ) -> std::result::Result<Article, HttpError> {
+        let is_json = request.headers().iter().any(|h| {
+            h.field.equiv("Content-Type") && h.value.as_str().starts_with("application/json")
+        });
+
+        let body_too_large = || {
+            HttpError::new(
+                413,
+                format!(
+                    "request body exceeds the maximum size of {} bytes",
+                    self.max_body_size
+                ),
+            )
+        };
+        if request
+            .body_length()
+            .is_some_and(|length| length > self.max_body_size)
+        {
+            return Err(body_too_large());
+        }
+
+        // Read one byte past the limit to tell "exactly at" from "over" the limit
+        let mut body = String::new();
+        request
+            .as_reader()
+            .take((self.max_body_size as u64).saturating_add(1))
+            .read_to_string(&mut body)
+            .map_err(|e| HttpError::new(400, format!("could not read request body: {}", e)))?;
+        if body.len() > self.max_body_size {
+            return Err(body_too_large());
+        }
+
+        if !is_json {
+            // Raw HTML body, with an optional `?url=` base URL
+            let base_url = query.and_then(|q| {
+                url::form_urlencoded::parse(q.as_bytes())
+                    .find(|(key, _)| key == "url")
+                    .map(|(_, value)| value.into_owned())
+            });
+            self.check_size(&body)?;
+            return Ok(self
+                .parser
+                .parse_with_options(&body, base_url.as_deref(), None)?);
+        }
+
+        let req: ExtractRequest = serde_json::from_str(&body)
+            .map_err(|e| HttpError::new(400, format!("invalid JSON body: {}", e)))?;
+
+        let html = match (req.html, &req.url) {
+            (Some(html), _) => {
+                self.check_size(&html)?;
+                html
+            }
+            (None, Some(url)) => {
+                let url = Url::parse(url)
+                    .map_err(|e| HttpError::new(400, format!("invalid url: {}", e)))?;
+                let html = self
+                    .fetcher
+                    .fetch_html(&url)
+                    .map_err(|e| HttpError::new(502, format!("{:#}", e)))?;
+                self.check_size(&html)?;
+                html
+            }
+            (None, None) => {
+                return Err(HttpError::new(400, "either `url` or `html` is required"));
+            }
+        };
+
+        Ok(self
+            .parser
+            .parse_with_options(&html, req.url.as_deref(), req.options)?)
+    }
+
+    fn check_size(&self, document: &str) -> std::result::Result<(), HttpError> {
+        if document.len() > self.max_document_size {
+            return Err(HttpError::new(
+                413,
+                format!(
+                    "document exceeds the maximum size of {} bytes",
+                    self.max_document_size
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Seconds for the Retry-After header, rounded up so that a client waiting
+/// that long is let through.
+fn retry_after(wait: Duration) -> String {
+    wait.as_secs_f64().ceil().max(1.0).to_string()
+}
+
+/// Compare secrets in time that depends only on their lengths, so response
+/// times don't reveal how much of a guessed key was right.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn json_response<T: Serialize>(status: u16, body: &T) -> Response<Cursor<Vec<u8>>> {
+    let json = serde_json::to_vec(body).expect("response bodies serialize to JSON");
+    text_response(status, "application/json", json)
+}
+
+fn text_response(status: u16, content_type: &str, body: Vec<u8>) -> Response<Cursor<Vec<u8>>> {
+    Response::from_data(body)
+        .with_status_code(status)
+        .with_header(
+            Header::from_bytes(&b"Content-Type"[..], content_type.as_bytes())
+                .expect("content type is a valid header value"),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_after() {
+        assert_eq!(retry_after(Duration::from_millis(1)), "1");
+        assert_eq!(retry_after(Duration::from_secs(1)), "1");
+        assert_eq!(retry_after(Duration::from_millis(1500)), "2");
+        assert_eq!(retry_after(Duration::from_secs(20)), "20");
+        assert_eq!(retry_after(Duration::ZERO), "1");
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(constant_time_eq(b"", b""));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret2"));
+        assert!(!constant_time_eq(b"secret", b""));
+    }
+}
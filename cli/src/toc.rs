@@ -0,0 +1,172 @@
+use readability_js::Article;
+use std::collections::HashSet;
+
+use crate::page::escape;
+
+/// A table of contents built from the headings of an article.
+pub struct Toc {
+    entries: Vec<Entry>,
+}
+
+struct Entry {
+    /// Depth in the outline, 0 for the highest heading level used
+    depth: usize,
+    text: String,
+    /// Anchor the Markdown link points to, the way GitHub and most
+    /// Markdown renderers derive it from the heading text
+    slug: String,
+    /// Anchor the HTML link points to: the heading's own id if it has one
+    id: String,
+    /// Whether the heading needs `id` added to the content
+    add_id: bool,
+}
+
+impl Toc {
+    /// Outline the headings of `article`, or `None` when there are fewer than
+    /// two, which is not worth a table of contents.
+    pub fn of(article: &Article) -> Option<Toc> {
+        let headings: Vec<(u8, String)> = article
+            .sections()
+            .into_iter()
+            .filter_map(|section| Some((section.level, section.heading?)))
+            .collect();
+        if headings.len() < 2 {
+            return None;
+        }
+        let top = headings.iter().map(|(level, _)| *level).min().unwrap_or(1);
+        let ids = heading_tags(&article.content).map(|(_, tag)| attribute(tag, "id"));
+
+        let mut used = HashSet::new();
+        let entries = headings
+            .into_iter()
+            .zip(ids)
+            .map(|((level, text), id)| {
+                let slug = unique_slug(&text, &mut used);
+                Entry {
+                    depth: (level - top) as usize,
+                    add_id: id.is_none(),
+                    id: id.map_or_else(|| slug.clone(), str::to_string),
+                    slug,
+                    text,
+                }
+            })
+            .collect();
+        Some(Toc { entries })
+    }
+
+    /// `content` with an id on every heading that lacks one, for the links of
+    /// [`Toc::html`] to land on.
+    pub fn anchor(&self, content: &str) -> String {
+        let mut out = String::with_capacity(content.len());
+        let mut copied = 0;
+        for ((at, _), entry) in heading_tags(content).zip(&self.entries) {
+            if entry.add_id {
+                // After the tag name, like `<h2`
+                let name_end = at + 3;
+                out.push_str(&content[copied..name_end]);
+                out.push_str(&format!(" id=\"{}\"", escape(&entry.id)));
+                copied = name_end;
+            }
+        }
+        out.push_str(&content[copied..]);
+        out
+    }
+
+    /// The table of contents as nested HTML lists in a `<nav>`.
+    pub fn html(&self) -> String {
+        let mut out = String::from("<nav class=\"toc\">");
+        let mut open = 0;
+        for entry in &self.entries {
+            let depth = entry.depth + 1;
+            if depth > open {
+                // Deeper levels nest in the current item
+                out.push_str(&"<ul><li>".repeat(depth - open));
+            } else {
+                out.push_str("</li>");
+                out.push_str(&"</ul></li>".repeat(open - depth));
+                out.push_str("<li>");
+            }
+            open = depth;
+            out.push_str(&format!(
+                "<a href=\"#{}\">{}</a>",
+                escape(&entry.id),
+                escape(&entry.text)
+            ));
+        }
+        out.push_str(&"</li></ul>".repeat(open));
+        out.push_str("</nav>");
+        out
+    }
+
+    /// The table of contents as a nested Markdown list.
+    pub fn markdown(&self) -> String {
+        let mut out = String::new();
+        for entry in &self.entries {
+            let text = entry.text.replace('[', "\\[").replace(']', "\\]");
+            out.push_str(&format!(
+                "{}- [{}](#{})\n",
+                "  ".repeat(entry.depth),
+                text,
+                entry.slug
+            ));
+        }
+        out.push('\n');
+        out
+    }
+}
+
+/// Opening `<h1>` to `<h6>` tags in `html`, with their offsets.
+fn heading_tags(html: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut from = 0;
+    std::iter::from_fn(move || {
+        loop {
+            let at = from + html[from..].find('<')?;
+            let rest = &html[at..];
+            if rest.starts_with("<!--") {
+                from = at + rest.find("-->").map_or(rest.len(), |end| end + 3);
+                continue;
+            }
+            let end = at + rest.find('>')? + 1;
+            from = end;
+            let bytes = rest.as_bytes();
+            if bytes.len() > 3
+                && bytes[1].eq_ignore_ascii_case(&b'h')
+                && (b'1'..=b'6').contains(&bytes[2])
+                && (bytes[3] == b'>' || bytes[3].is_ascii_whitespace())
+            {
+                return Some((at, &html[at..end]));
+            }
+        }
+    })
+}
+
+/// Value of a double-quoted attribute, as serialized by the DOM.
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let start = tag.find(&format!(" {}=\"", name))? + name.len() + 3;
+    let len = tag[start..].find('"')?;
+    Some(&tag[start..start + len]).filter(|value| !value.is_empty())
+}
+
+/// A GitHub-style anchor for `text`, numbered if it was already used.
+fn unique_slug(text: &str, used: &mut HashSet<String>) -> String {
+    let mut base: String = text
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect();
+    if base.is_empty() {
+        base = "section".into();
+    }
+    let mut slug = base.clone();
+    let mut n = 0;
+    while !used.insert(slug.clone()) {
+        n += 1;
+        slug = format!("{}-{}", base, n);
+    }
+    slug
+}
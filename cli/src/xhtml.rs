@@ -0,0 +1,212 @@
+use html5ever::driver::ParseOpts;
+use html5ever::parse_document;
+use html5ever::tendril::TendrilSink;
+use markup5ever_rcdom::{Handle, NodeData, RcDom};
+use url::Url;
+
+use crate::page::escape_into;
+
+/// Elements kept as they are; any other element is replaced by its children.
+const ELEMENTS: &[&str] = &[
+    "a",
+    "abbr",
+    "b",
+    "bdi",
+    "bdo",
+    "blockquote",
+    "br",
+    "caption",
+    "cite",
+    "code",
+    "col",
+    "colgroup",
+    "dd",
+    "del",
+    "dfn",
+    "div",
+    "dl",
+    "dt",
+    "em",
+    "figcaption",
+    "figure",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "hr",
+    "i",
+    "img",
+    "ins",
+    "kbd",
+    "li",
+    "mark",
+    "ol",
+    "p",
+    "pre",
+    "q",
+    "s",
+    "samp",
+    "small",
+    "span",
+    "strong",
+    "sub",
+    "sup",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "time",
+    "tr",
+    "u",
+    "ul",
+    "var",
+];
+
+/// Elements dropped along with their content.
+const DROPPED: &[&str] = &[
+    "script", "style", "noscript", "template", "iframe", "object", "embed", "form", "input",
+    "button", "select", "textarea", "svg", "math", "video", "audio", "canvas",
+];
+
+const VOID: &[&str] = &["br", "col", "hr", "img"];
+
+/// Attributes kept on the elements above.
+const ATTRIBUTES: &[&str] = &[
+    "alt", "colspan", "datetime", "dir", "href", "lang", "rowspan", "src", "title",
+];
+
+/// Convert an HTML fragment into well-formed XHTML for an EPUB chapter.
+///
+/// Relative links are made absolute against `base`. Each image is passed to
+/// `image`, which returns the path to use instead, or `None` to drop it in
+/// favor of its alt text.
+pub fn from_html(
+    html: &str,
+    base: Option<&Url>,
+    mut image: impl FnMut(&Url) -> Option<String>,
+) -> String {
+    let dom = parse_document(RcDom::default(), ParseOpts::default())
+        .from_utf8()
+        .read_from(&mut html.as_bytes())
+        .expect("reading from a string cannot fail");
+    let mut out = String::with_capacity(html.len());
+    let mut writer = Writer {
+        base,
+        image: &mut image,
+        out: &mut out,
+    };
+    // Only the body's content; the parser adds html, head and body around it
+    if let Some(body) = find(&dom.document, "body") {
+        writer.children(&body);
+    }
+    out
+}
+
+fn find(node: &Handle, name: &str) -> Option<Handle> {
+    if let NodeData::Element { name: qual, .. } = &node.data
+        && &*qual.local == name
+    {
+        return Some(node.clone());
+    }
+    node.children
+        .borrow()
+        .iter()
+        .find_map(|child| find(child, name))
+}
+
+struct Writer<'a> {
+    base: Option<&'a Url>,
+    image: &'a mut dyn FnMut(&Url) -> Option<String>,
+    out: &'a mut String,
+}
+
+impl Writer<'_> {
+    fn children(&mut self, node: &Handle) {
+        for child in node.children.borrow().iter() {
+            self.node(child);
+        }
+    }
+
+    fn node(&mut self, node: &Handle) {
+        match &node.data {
+            NodeData::Text { contents } => escape_into(self.out, &contents.borrow(), false),
+            NodeData::Element { name, attrs, .. } => {
+                let tag = &*name.local;
+                if DROPPED.contains(&tag) {
+                    return;
+                }
+                if !ELEMENTS.contains(&tag) {
+                    self.children(node);
+                    return;
+                }
+
+                let attrs = attrs.borrow();
+                let attr = |key: &str| {
+                    attrs
+                        .iter()
+                        .find(|a| &*a.name.local == key)
+                        .map(|a| a.value.to_string())
+                };
+                let mut kept: Vec<(&str, String)> = Vec::new();
+                if tag == "img" {
+                    let src = attr("src").and_then(|src| self.resolve(&src));
+                    let Some(path) = src.and_then(|src| (self.image)(&src)) else {
+                        escape_into(self.out, &attr("alt").unwrap_or_default(), false);
+                        return;
+                    };
+                    kept.push(("src", path));
+                    // Alt text is required in EPUB
+                    kept.push(("alt", attr("alt").unwrap_or_default()));
+                }
+                for a in attrs.iter() {
+                    let key = &*a.name.local;
+                    if !ATTRIBUTES.contains(&key) || kept.iter().any(|(k, _)| *k == key) {
+                        continue;
+                    }
+                    let value = match key {
+                        "src" => continue,
+                        "href" if !a.value.starts_with('#') => match self.resolve(&a.value) {
+                            Some(url) if matches!(url.scheme(), "http" | "https" | "mailto") => {
+                                url.to_string()
+                            }
+                            _ => continue,
+                        },
+                        _ => a.value.to_string(),
+                    };
+                    kept.push((key, value));
+                }
+
+                self.out.push('<');
+                self.out.push_str(tag);
+                for (key, value) in &kept {
+                    self.out.push(' ');
+                    self.out.push_str(key);
+                    self.out.push_str("=\"");
+                    escape_into(self.out, value, true);
+                    self.out.push('"');
+                }
+                if VOID.contains(&tag) {
+                    self.out.push_str("/>");
+                    return;
+                }
+                self.out.push('>');
+                self.children(node);
+                self.out.push_str("</");
+                self.out.push_str(tag);
+                self.out.push('>');
+            }
+            _ => {}
+        }
+    }
+
+    fn resolve(&self, href: &str) -> Option<Url> {
+        match self.base {
+            Some(base) => base.join(href.trim()).ok(),
+            None => Url::parse(href.trim()).ok(),
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use flate2::Compression;
+use flate2::Crc;
+use flate2::write::DeflateEncoder;
+use std::io::{self, Write};
+
+/// File recorded for the central directory.
+struct Entry {
+    name: String,
+    crc: u32,
+    compressed_size: u32,
+    size: u32,
+    method: u16,
+    offset: u32,
+}
+
+/// Minimal ZIP archive writer, enough for EPUB containers: no ZIP64, no
+/// timestamps, entries either stored or deflated.
+pub struct ZipWriter<W: Write> {
+    out: W,
+    entries: Vec<Entry>,
+    offset: u32,
+}
+
+impl<W: Write> ZipWriter<W> {
+    pub fn new(out: W) -> Self {
+        Self {
+            out,
+            entries: Vec::new(),
+            offset: 0,
+        }
+    }
+
+    /// Add a file, deflated unless `store` is set.
+    pub fn add(&mut self, name: &str, data: &[u8], store: bool) -> io::Result<()> {
+        let mut crc = Crc::new();
+        crc.update(data);
+        let (method, body) = if store {
+            (0, data.to_vec())
+        } else {
+            let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(data)?;
+            (8, encoder.finish()?)
+        };
+        let entry = Entry {
+            name: name.to_string(),
+            crc: crc.sum(),
+            compressed_size: size(body.len())?,
+            size: size(data.len())?,
+            method,
+            offset: self.offset,
+        };
+
+        let mut header = Vec::with_capacity(30 + name.len());
+        header.extend_from_slice(&0x04034b50u32.to_le_bytes());
+        header.extend_from_slice(&20u16.to_le_bytes()); // version needed
+        header.extend_from_slice(&0u16.to_le_bytes()); // flags
+        header.extend_from_slice(&entry.method.to_le_bytes());
+        header.extend_from_slice(&0u32.to_le_bytes()); // time and date
+        header.extend_from_slice(&entry.crc.to_le_bytes());
+        header.extend_from_slice(&entry.compressed_size.to_le_bytes());
+        header.extend_from_slice(&entry.size.to_le_bytes());
+        header.extend_from_slice(&size16(name.len())?.to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes()); // extra field length
+        header.extend_from_slice(name.as_bytes());
+        self.out.write_all(&header)?;
+        self.out.write_all(&body)?;
+
+        self.offset = self
+            .offset
+            .checked_add(size(header.len() + body.len())?)
+            .ok_or_else(too_large)?;
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// Write the central directory and return the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        let start = self.offset;
+        let mut directory = Vec::new();
+        for entry in &self.entries {
+            directory.extend_from_slice(&0x02014b50u32.to_le_bytes());
+            directory.extend_from_slice(&20u16.to_le_bytes()); // version made by
+            directory.extend_from_slice(&20u16.to_le_bytes()); // version needed
+            directory.extend_from_slice(&0u16.to_le_bytes()); // flags
+            directory.extend_from_slice(&entry.method.to_le_bytes());
+            directory.extend_from_slice(&0u32.to_le_bytes()); // time and date
+            directory.extend_from_slice(&entry.crc.to_le_bytes());
+            directory.extend_from_slice(&entry.compressed_size.to_le_bytes());
+            directory.extend_from_slice(&entry.size.to_le_bytes());
+            directory.extend_from_slice(&size16(entry.name.len())?.to_le_bytes());
+            // Extra field, comment, disk number, internal and external attributes
+            directory.extend_from_slice(&[0; 12]);
+            directory.extend_from_slice(&entry.offset.to_le_bytes());
+            directory.extend_from_slice(entry.name.as_bytes());
+        }
+        let count = size16(self.entries.len())?;
+        let directory_size = size(directory.len())?;
+        directory.extend_from_slice(&0x06054b50u32.to_le_bytes());
+        directory.extend_from_slice(&[0; 4]); // disk numbers
+        directory.extend_from_slice(&count.to_le_bytes());
+        directory.extend_from_slice(&count.to_le_bytes());
+        directory.extend_from_slice(&directory_size.to_le_bytes());
+        directory.extend_from_slice(&start.to_le_bytes());
+        directory.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        self.out.write_all(&directory)?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+fn size(len: usize) -> io::Result<u32> {
+    u32::try_from(len).map_err(|_| too_large())
+}
+
+fn size16(len: usize) -> io::Result<u16> {
+    u16::try_from(len).map_err(|_| too_large())
+}
+
+fn too_large() -> io::Error {
+    io::Error::other("archive is too large for ZIP without ZIP64")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::DeflateDecoder;
+    use std::io::Read;
+
+    fn u16_at(data: &[u8], at: usize) -> u16 {
+        u16::from_le_bytes([data[at], data[at + 1]])
+    }
+
+    fn u32_at(data: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes(data[at..at + 4].try_into().unwrap())
+    }
+
+    /// Read every entry back through the central directory, checking its CRC.
+    fn read(zip: &[u8]) -> Vec<(String, Vec<u8>)> {
+        let end = zip.len() - 22;
+        assert_eq!(u32_at(zip, end), 0x06054b50);
+        let count = u16_at(zip, end + 10);
+        let mut at = u32_at(zip, end + 16) as usize;
+
+        let mut entries = Vec::new();
+        for _ in 0..count {
+            assert_eq!(u32_at(zip, at), 0x02014b50);
+            let method = u16_at(zip, at + 10);
+            let crc = u32_at(zip, at + 16);
+            let compressed_size = u32_at(zip, at + 20) as usize;
+            let size = u32_at(zip, at + 24) as usize;
+            let name_len = u16_at(zip, at + 28) as usize;
+            let offset = u32_at(zip, at + 42) as usize;
+            let name = String::from_utf8(zip[at + 46..at + 46 + name_len].to_vec()).unwrap();
+            at += 46 + name_len;
+
+            assert_eq!(u32_at(zip, offset), 0x04034b50);
+            assert_eq!(u32_at(zip, offset + 14), crc);
+            let start = offset + 30 + u16_at(zip, offset + 26) as usize;
+            let body = &zip[start..start + compressed_size];
+            let data = match method {
+                0 => body.to_vec(),
+                8 => {
+                    let mut data = Vec::new();
+                    DeflateDecoder::new(body).read_to_end(&mut data).unwrap();
+                    data
+                }
+                method => panic!("unexpected method {}", method),
+            };
+            assert_eq!(data.len(), size);
+            let mut check = Crc::new();
+            check.update(&data);
+            assert_eq!(check.sum(), crc, "CRC of {}", name);
+            entries.push((name, data));
+        }
+        entries
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let text = "chapter text ".repeat(100);
+        let mut zip = ZipWriter::new(Vec::new());
+        zip.add("mimetype", b"application/epub+zip", true).unwrap();
+        zip.add("OEBPS/chapter-1.xhtml", text.as_bytes(), false)
+            .unwrap();
+        zip.add("empty", b"", false).unwrap();
+        let zip = zip.finish().unwrap();
+
+        // EPUB readers expect the mimetype first and uncompressed
+        assert_eq!(&zip[30..38], b"mimetype");
+        assert_eq!(
+            read(&zip),
+            [
+                ("mimetype".to_string(), b"application/epub+zip".to_vec()),
+                ("OEBPS/chapter-1.xhtml".to_string(), text.into_bytes()),
+                ("empty".to_string(), Vec::new()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,116 @@
+//! Prepares the embedded scripts.
+//!
+//! When building for the host, the scripts are precompiled to QuickJS
+//! bytecode; loading bytecode skips parsing, which is most of the cost of
+//! `Readability::new()`. Bytecode is specific to the QuickJS build and byte
+//! order, so cross builds embed the sources instead. With the `compress`
+//! feature, whichever form is embedded is deflated as well.
+//!
+//! The `readability-0.5` feature embeds the older vendored Readability.js
+//! release instead of the current one, for pinning extraction behavior.
+
+use rquickjs::{Context, Ctx, Runtime, qjs};
+use std::ffi::CString;
+use std::path::PathBuf;
+use std::{env, fs};
+
+fn main() {
+    println!("cargo::rustc-check-cfg=cfg(readability_bytecode)");
+
+    // `CARGO_FEATURE_*` keeps the dot of this name, so read the full list.
+    // Features are additive, so the older release wins whenever anything in
+    // the build asks for it.
+    let features = env::var("CARGO_CFG_FEATURE").unwrap_or_default();
+    let (version, dir) = if features.split(',').any(|f| f == "readability-0.5") {
+        ("0.5.0", "vendor/readability-0.5")
+    } else {
+        ("0.6.0", "vendor/readability")
+    };
+    println!("cargo::rustc-env=READABILITY_JS_VERSION={}", version);
+
+    let scripts = [
+        ("Readability", format!("{}/Readability.js", dir)),
+        (
+            "Readability-readerable",
+            format!("{}/Readability-readerable.js", dir),
+        ),
+        ("bundle", "js/bundled.js".to_string()),
+    ];
+    for (_, path) in &scripts {
+        println!("cargo::rerun-if-changed={}", path);
+    }
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let bytecode = env::var("HOST").ok() == env::var("TARGET").ok();
+    let compress = env::var_os("CARGO_FEATURE_COMPRESS").is_some();
+
+    let runtime = Runtime::new().expect("failed to create QuickJS runtime");
+    let context = Context::full(&runtime).expect("failed to create QuickJS context");
+    let mut hash = FNV_OFFSET;
+    context.with(|ctx| {
+        for (name, path) in &scripts {
+            let source =
+                fs::read(path).unwrap_or_else(|e| panic!("failed to read {}: {}", path, e));
+            hash = fnv1a(hash, &source);
+            let mut payload = if bytecode {
+                compile(&ctx, name, source)
+            } else {
+                source
+            };
+            if compress {
+                payload = miniz_oxide::deflate::compress_to_vec(&payload, 10);
+            }
+            fs::write(out_dir.join(format!("{}.bin", name)), payload)
+                .expect("failed to write script");
+        }
+    });
+
+    println!("cargo::rustc-env=READABILITY_BUNDLE_HASH={:016x}", hash);
+
+    if bytecode {
+        println!("cargo::rustc-cfg=readability_bytecode");
+    }
+}
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// Continue a 64-bit FNV-1a hash over `bytes`, stable across builds and
+/// platforms unlike the standard library's hashers.
+fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for &byte in bytes {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+/// Compile a global script without running it and serialize the result.
+fn compile(ctx: &Ctx<'_>, name: &str, source: Vec<u8>) -> Vec<u8> {
+    let len = source.len();
+    let source = CString::new(source).expect("script contains a NUL byte");
+    let file_name = CString::new(name).unwrap();
+    let raw = ctx.as_raw().as_ptr();
+
+    unsafe {
+        let function = qjs::JS_Eval(
+            raw,
+            source.as_ptr(),
+            len as _,
+            file_name.as_ptr(),
+            (qjs::JS_EVAL_TYPE_GLOBAL | qjs::JS_EVAL_FLAG_COMPILE_ONLY) as _,
+        );
+        if qjs::JS_IsException(function) {
+            panic!("failed to compile {}: {:?}", name, ctx.catch());
+        }
+
+        // Function sources are only kept for `toString()`, which nothing needs
+        let flags = qjs::JS_WRITE_OBJ_BYTECODE | qjs::JS_WRITE_OBJ_STRIP_SOURCE;
+        let mut size = 0;
+        let buf = qjs::JS_WriteObject(raw, &mut size, function, flags as _);
+        qjs::JS_FreeValue(raw, function);
+        assert!(!buf.is_null(), "failed to serialize {}", name);
+        let bytecode = std::slice::from_raw_parts(buf, size as usize).to_vec();
+        qjs::js_free(raw, buf.cast());
+        bytecode
+    }
+}
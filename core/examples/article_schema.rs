@@ -0,0 +1,17 @@
+//! Print the JSON Schema of the serialized [`Article`].
+//!
+//! Regenerate the shipped schema after changing `Article`:
+//!
+//! ```bash
+//! cargo run -p readability-js --example article_schema --features schemars > core/schema/article.schema.json
+//! ```
+
+use readability_js::Article;
+
+fn main() {
+    let schema = schemars::schema_for!(Article);
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&schema).expect("schema serializes to JSON")
+    );
+}
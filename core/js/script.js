@@ -4,6 +4,7 @@ import { DOMParser } from "linkedom";
 function extract(html, baseUrl, options) {
   try {
     const domParser = new DOMParser();
+    const parseStart = Date.now();
     let doc;
 
     try {
@@ -15,16 +16,56 @@ function extract(html, baseUrl, options) {
         error: "Failed to parse HTML: " + e.message,
       };
     }
+    const domParseMs = Date.now() - parseStart;
 
     // TODO maybe add a base element that holds url
     // for readability to resolve relative urls
 
+    // Comments are taken out before Readability sees the page, so they can
+    // never end up in the article content.
+    const commentsHtml =
+      options && options.extractComments ? takeComments(doc) : null;
+
+    // Metadata has to be read before Readability strips the document
+    let leadImage = metaLeadImage(doc);
+    const siteIcon = bestSiteIcon(doc);
+    const section = articleSection(doc);
+    const modified = modifiedTime(doc);
+    const h1 = options && options.preferH1Title ? headline(doc) : null;
+    const schema = schemaArticle(doc);
+    const documentTextLength = visibleTextLength(doc);
+    const entry = hEntry(doc);
+    const dc = dublinCore(doc);
+    const author = bylineFallback(doc);
+
     const reader = new Readability(doc, options || {});
+    const grabbed = trackGrabbedContent(reader);
+    // Hooked before the other patches, so nodes they spare aren't recorded
+    const removed =
+      options && options.recordRemoved ? recordRemovals(reader) : null;
+    const candidates = options && options.explain ? traceScores(reader) : null;
+    if (options && options.keepInlineStyles) {
+      keepInlineStyles(reader);
+    }
+    if (options && options.keepAsidesAbove) {
+      keepLongAsides(reader, options.keepAsidesAbove);
+    }
+    if (options && options.convertDivsToParagraphs === false) {
+      keepDivs(reader);
+    }
+    if (options && (options.linkRel || options.unlinkSponsored)) {
+      rewriteLinkRels(reader, options.linkRel, options.unlinkSponsored);
+    }
     let article;
 
+    const grabStart = Date.now();
     try {
       article = reader.parse();
     } catch (e) {
+      const limit = limitError(e);
+      if (limit) {
+        return limit;
+      }
       return {
         errorType: "RuntimeError",
         error: "Readability runtime error: " + e.message,
@@ -37,10 +78,69 @@ function extract(html, baseUrl, options) {
         error: "Failed to extract readable content",
       };
     }
+    if (options && options.collectStats) {
+      article.stats = {
+        domParseMs: domParseMs,
+        grabArticleMs: Date.now() - grabStart,
+      };
+    }
+    if (removed) {
+      article.removed = removed;
+    }
+    if (candidates) {
+      article.candidates = candidates();
+    }
+
+    article.commentsHtml = commentsHtml;
+    if (documentTextLength > 0) {
+      const retained = collapsedLength(article.textContent || "");
+      article.retainedRatio = Math.min(1, retained / documentTextLength);
+    }
+    if (grabbed.content) {
+      article.topCandidateScore = topCandidateScore(grabbed.content);
+      article.linkDensity = reader._getLinkDensity(grabbed.content);
+    }
+
+    if (!leadImage) {
+      leadImage = contentLeadImage(
+        domParser.parseFromString(article.content || "", "text/html"),
+      );
+    }
+    article.siteIconUrl = siteIcon;
+    article.section = section;
+    article.modifiedTime = modified;
+    // Fallback metadata, from the most to the least specific source
+    applySchemaArticle(article, schema);
+    applyHEntry(article, entry);
+    applyDublinCore(article, dc);
+    article.byline = article.byline || author;
+
+    if (h1) {
+      article.title = h1;
+    }
+    if (leadImage) {
+      article.leadImageUrl = leadImage.url;
+      article.leadImageWidth = leadImage.width;
+      article.leadImageHeight = leadImage.height;
+    }
+    if (options && options.htmlFormat) {
+      article.content = formatHtml(article.content || "", options.htmlFormat);
+    }
+    if (
+      options &&
+      options.maxOutputBytes &&
+      utf8Length(article.content || "") > options.maxOutputBytes
+    ) {
+      truncateArticle(article, options.maxOutputBytes);
+    }
 
     // Return article directly on success
     return article;
   } catch (e) {
+    const limit = limitError(e);
+    if (limit) {
+      return limit;
+    }
     return {
       errorType: "RuntimeError",
       error: "Unexpected error: " + e.message,
@@ -48,4 +148,1258 @@ function extract(html, baseUrl, options) {
   }
 }
 
+// Recognize errors caused by resource limits rather than by the page.
+function limitError(e) {
+  // QuickJS throws a null exception when it runs out of memory while
+  // already handling an out-of-memory error
+  const message = e ? String(e.message) : "out of memory";
+  if (message === "out of memory") {
+    return { errorType: "MemoryLimitExceeded", error: message };
+  }
+  // Readability's own check against maxElemsToParse
+  if (message.startsWith("Aborting parsing document")) {
+    return { errorType: "ElementLimitExceeded", error: message };
+  }
+  return null;
+}
+
+const BLOCK_TAGS = new Set([
+  "ADDRESS", "ARTICLE", "ASIDE", "BLOCKQUOTE", "BODY", "DD", "DETAILS", "DIV",
+  "DL", "DT", "FIELDSET", "FIGCAPTION", "FIGURE", "FOOTER", "FORM", "H1", "H2",
+  "H3", "H4", "H5", "H6", "HEADER", "HR", "LI", "MAIN", "NAV", "OL", "P",
+  "SECTION", "SUMMARY", "TABLE", "TBODY", "TD", "TFOOT", "TH", "THEAD", "TR",
+  "UL",
+]);
+
+// Elements whose whitespace is significant and must be kept verbatim.
+const PREFORMATTED_TAGS = new Set(["PRE", "TEXTAREA", "SCRIPT", "STYLE"]);
+
+function escapeText(text) {
+  return text
+    .replace(/&/g, "&amp;")
+    .replace(/</g, "&lt;")
+    .replace(/>/g, "&gt;")
+    .replace(/\u00a0/g, "&nbsp;");
+}
+
+// Re-serialize `html` with "pretty" (one indented block per line) or
+// "minified" whitespace.
+function formatHtml(html, format) {
+  const doc = new DOMParser().parseFromString(
+    "<!DOCTYPE html><html><head></head><body>" + html + "</body></html>",
+    "text/html",
+  );
+  const pretty = format === "pretty";
+  const lines = [];
+  formatChildren(doc.body, 0, pretty, lines);
+  return lines.join(pretty ? "\n" : "");
+}
+
+function formatChildren(parent, depth, pretty, lines) {
+  const indent = pretty ? "  ".repeat(depth) : "";
+  let inline = "";
+  const flush = () => {
+    const text = inline.trim();
+    if (text) {
+      lines.push(indent + text);
+    }
+    inline = "";
+  };
+
+  for (const node of Array.from(parent.childNodes)) {
+    // Comments and the like carry no content and are dropped
+    if (node.nodeType === 3) {
+      inline += escapeText(node.textContent).replace(/\s+/g, " ");
+    } else if (node.nodeType === 1) {
+      if (PREFORMATTED_TAGS.has(node.tagName)) {
+        flush();
+        lines.push(indent + node.outerHTML);
+      } else if (BLOCK_TAGS.has(node.tagName)) {
+        flush();
+        formatBlock(node, depth, pretty, lines);
+      } else {
+        inline += formatInline(node);
+      }
+    }
+  }
+  flush();
+}
+
+function formatBlock(element, depth, pretty, lines) {
+  const indent = pretty ? "  ".repeat(depth) : "";
+  const shell = element.cloneNode(false).outerHTML;
+  const close = "</" + element.tagName.toLowerCase() + ">";
+  const open = shell.endsWith(close) ? shell.slice(0, -close.length) : shell;
+  const hasBlocks = Array.from(element.children).some(
+    (child) => BLOCK_TAGS.has(child.tagName) || PREFORMATTED_TAGS.has(child.tagName),
+  );
+
+  if (!hasBlocks) {
+    // Inline-only content stays on the element's line
+    lines.push(indent + open + formatInlineChildren(element).trim() + close);
+    return;
+  }
+  lines.push(indent + open);
+  formatChildren(element, depth + 1, pretty, lines);
+  lines.push(indent + close);
+}
+
+function formatInline(element) {
+  const shell = element.cloneNode(false).outerHTML;
+  const close = "</" + element.tagName.toLowerCase() + ">";
+  if (!shell.endsWith(close)) {
+    // Void element such as <img> or <br>
+    return shell;
+  }
+  return shell.slice(0, -close.length) + formatInlineChildren(element) + close;
+}
+
+function formatInlineChildren(element) {
+  let html = "";
+  for (const node of Array.from(element.childNodes)) {
+    if (node.nodeType === 3) {
+      html += escapeText(node.textContent).replace(/\s+/g, " ");
+    } else if (node.nodeType === 1) {
+      html += PREFORMATTED_TAGS.has(node.tagName)
+        ? node.outerHTML
+        : formatInline(node);
+    }
+  }
+  return html;
+}
+
+// Byte length of `text` once encoded as UTF-8.
+function utf8Length(text) {
+  let bytes = 0;
+  for (let i = 0; i < text.length; i++) {
+    const code = text.charCodeAt(i);
+    if (code < 0x80) {
+      bytes += 1;
+    } else if (code < 0x800) {
+      bytes += 2;
+    } else if (code >= 0xd800 && code < 0xdc00) {
+      // Surrogate pair, one four-byte character
+      bytes += 4;
+      i++;
+    } else {
+      bytes += 3;
+    }
+  }
+  return bytes;
+}
+
+function serializedNode(node) {
+  if (node.nodeType === 1) {
+    return node.outerHTML;
+  }
+  if (node.nodeType === 8) {
+    return "<!--" + node.data + "-->";
+  }
+  return node.textContent || "";
+}
+
+// Drop trailing nodes until the content fits in `maxBytes`. When only one
+// element is left and it is still too big, continue inside it, so the cut
+// always falls on an element boundary.
+function truncateArticle(article, maxBytes) {
+  const doc = new DOMParser().parseFromString(
+    "<!DOCTYPE html><html><head></head><body>" + article.content + "</body></html>",
+    "text/html",
+  );
+  let size = utf8Length(doc.body.innerHTML);
+  let container = doc.body;
+  while (size > maxBytes) {
+    const last = container.lastChild;
+    if (!last) {
+      break;
+    }
+    if (last.nodeType === 1 && container.children.length === 1) {
+      container = last;
+      continue;
+    }
+    size -= utf8Length(serializedNode(last));
+    last.remove();
+  }
+
+  article.content = doc.body.innerHTML;
+  article.textContent = doc.body.textContent;
+  article.length = article.textContent.length;
+  article.truncated = true;
+}
+
+function collapsedLength(text) {
+  return text.replace(/\s+/g, " ").trim().length;
+}
+
+// Length of the document's readable text, ignoring scripts and styles.
+function visibleTextLength(doc) {
+  if (!doc.body) {
+    return 0;
+  }
+  const body = doc.body.cloneNode(true);
+  body
+    .querySelectorAll("script, style, noscript, template")
+    .forEach((node) => node.remove());
+  return collapsedLength(body.textContent);
+}
+
+// Remembers the content element of the last _grabArticle attempt, which is
+// the one that made it into the article.
+function trackGrabbedContent(reader) {
+  const grabbed = { content: null };
+  const grabArticle = reader._grabArticle;
+  reader._grabArticle = function (page) {
+    grabbed.content = grabArticle.call(this, page);
+    return grabbed.content;
+  };
+  return grabbed;
+}
+
+// Records every node Readability removes, with the step that removed it, so a
+// missing paragraph can be traced back to the rule that dropped it.
+function recordRemovals(reader) {
+  const removed = [];
+  const steps = [];
+  let pass = 1;
+  let bylineNode = null;
+
+  const record = (node, reason) => {
+    const text = (node.textContent || "").replace(/\s+/g, " ").trim();
+    removed.push({
+      tag: node.tagName ? node.tagName.toLowerCase() : node.nodeName,
+      className: (node.getAttribute && node.getAttribute("class")) || null,
+      id: (node.getAttribute && node.getAttribute("id")) || null,
+      reason: reason,
+      textPreview: text.length > 80 ? text.slice(0, 80) + "…" : text,
+      pass: pass,
+    });
+  };
+
+  const checkByline = reader._checkByline;
+  reader._checkByline = function (node, matchString) {
+    const isByline = checkByline.call(this, node, matchString);
+    if (isByline) {
+      bylineNode = node;
+    }
+    return isByline;
+  };
+
+  // Readability starts over with looser rules when a pass finds too little
+  // text, dropping one flag each time
+  const removeFlag = reader._removeFlag;
+  reader._removeFlag = function (flag) {
+    pass++;
+    return removeFlag.call(this, flag);
+  };
+
+  // _grabArticle removes nodes for several reasons in one loop; check them
+  // in the order Readability does
+  const grabReason = (node) => {
+    const matchString = node.className + " " + node.id;
+    if (!reader._isProbablyVisible(node)) {
+      return "hidden";
+    }
+    if (
+      node.getAttribute("aria-modal") == "true" &&
+      node.getAttribute("role") == "dialog"
+    ) {
+      return "dialog";
+    }
+    if (node === bylineNode) {
+      return "byline";
+    }
+    if (reader._headerDuplicatesTitle && reader._headerDuplicatesTitle(node)) {
+      return "duplicate title";
+    }
+    if (reader._flagIsActive(reader.FLAG_STRIP_UNLIKELYS)) {
+      if (
+        reader.REGEXPS.unlikelyCandidates.test(matchString) &&
+        !reader.REGEXPS.okMaybeItsACandidate.test(matchString)
+      ) {
+        return "unlikely candidate";
+      }
+      if (reader.UNLIKELY_ROLES.includes(node.getAttribute("role"))) {
+        return "unlikely role";
+      }
+    }
+    return "empty";
+  };
+
+  // Name the step currently running, so the removals made inside it can be
+  // attributed to it
+  const step = (name, reason) => {
+    const orig = reader[name];
+    if (typeof orig !== "function") {
+      return;
+    }
+    reader[name] = function (...args) {
+      steps.push(typeof reason === "function" ? reason(...args) : reason);
+      try {
+        return orig.apply(this, args);
+      } finally {
+        steps.pop();
+      }
+    };
+  };
+  step("_prepDocument", "style");
+  step("_removeScripts", "script");
+  step("_grabArticle", () => grabReason);
+  step("_prepArticle", "empty paragraph");
+  step("_clean", (e, tag) => "clean: " + tag);
+  step("_cleanConditionally", (e, tag) => "clean conditionally: " + tag);
+  step("_cleanHeaders", "header with negative class weight");
+  step("_cleanMatchedNodes", "share widget");
+  step("_simplifyNestedElements", "empty container");
+
+  const reasonFor = (node) => {
+    const reason = steps[steps.length - 1] || "other";
+    return typeof reason === "function" ? reason(node) : reason;
+  };
+
+  const removeNodes = reader._removeNodes;
+  reader._removeNodes = function (nodeList, filterFn) {
+    return removeNodes.call(this, nodeList, function (node, i, list) {
+      const remove = !filterFn || filterFn.call(this, node, i, list);
+      if (remove && node.parentNode) {
+        record(node, reasonFor(node));
+      }
+      return remove;
+    });
+  };
+
+  const removeAndGetNext = reader._removeAndGetNext;
+  reader._removeAndGetNext = function (node) {
+    record(node, reasonFor(node));
+    return removeAndGetNext.call(this, node);
+  };
+
+  return removed;
+}
+
+// Records every element Readability scores as a possible article container.
+// Returns a function listing them, highest score first, once parsing is done.
+function traceScores(reader) {
+  const traced = [];
+  // Entries still waiting for their link density
+  const unscaled = new Map();
+  let pass = 1;
+
+  const initializeNode = reader._initializeNode;
+  reader._initializeNode = function (node) {
+    initializeNode.call(this, node);
+    const entry = {
+      node: node,
+      classWeight: this._getClassWeight(node),
+      contentScore: null,
+      linkDensity: null,
+      pass: pass,
+    };
+    traced.push(entry);
+    unscaled.set(node, entry);
+  };
+
+  // Candidates are scaled by their link density right after scoring; the
+  // first call for each one sees the score before the penalty
+  const getLinkDensity = reader._getLinkDensity;
+  reader._getLinkDensity = function (element) {
+    const density = getLinkDensity.call(this, element);
+    const entry = unscaled.get(element);
+    if (entry && element.readability) {
+      entry.contentScore = element.readability.contentScore;
+      entry.linkDensity = density;
+      unscaled.delete(element);
+    }
+    return density;
+  };
+
+  const removeFlag = reader._removeFlag;
+  reader._removeFlag = function (flag) {
+    pass++;
+    return removeFlag.call(this, flag);
+  };
+
+  return () =>
+    traced
+      .map((entry) => {
+        const node = entry.node;
+        const score = node.readability ? node.readability.contentScore : 0;
+        const text = (node.textContent || "").replace(/\s+/g, " ").trim();
+        return {
+          tag: node.tagName.toLowerCase(),
+          className: node.getAttribute("class") || null,
+          id: node.getAttribute("id") || null,
+          contentScore: entry.contentScore === null ? score : entry.contentScore,
+          classWeight: entry.classWeight,
+          linkDensity:
+            entry.linkDensity === null
+              ? reader._getLinkDensity(node)
+              : entry.linkDensity,
+          score: score,
+          textPreview: text.length > 80 ? text.slice(0, 80) + "…" : text,
+          pass: entry.pass,
+        };
+      })
+      .sort((a, b) => b.score - a.score);
+}
+
+// Readability strips every style attribute along with the presentational
+// ones; put the style back after each element is cleaned.
+function keepInlineStyles(reader) {
+  const cleanStyles = reader._cleanStyles;
+  reader._cleanStyles = function (e) {
+    const style = e && e.getAttribute("style");
+    cleanStyles.call(this, e);
+    if (style) {
+      e.setAttribute("style", style);
+    }
+  };
+}
+
+// Readability turns <div>s without block children into <p>s and unwraps
+// <div>s holding a single <p>; skip both so structured layouts keep their
+// markup. The divs are still scored as paragraphs would be.
+function keepDivs(reader) {
+  const setNodeTag = reader._setNodeTag;
+  reader._setNodeTag = function (node, tag) {
+    if (node.tagName === "DIV" && tag === "P") {
+      return node;
+    }
+    return setNodeTag.call(this, node, tag);
+  };
+
+  const hasSingleTagInsideElement = reader._hasSingleTagInsideElement;
+  reader._hasSingleTagInsideElement = function (element, tag) {
+    if (element.tagName === "DIV" && tag === "P") {
+      return false;
+    }
+    return hasSingleTagInsideElement.call(this, element, tag);
+  };
+}
+
+// Readability removes every <aside>, and drops asides and blockquotes whose
+// class looks like a sidebar; spare the ones with at least `minChars` of
+// text, which are usually pull quotes or notes that belong to the piece.
+function keepLongAsides(reader, minChars) {
+  const isLong = (node) =>
+    (node.tagName === "ASIDE" || node.tagName === "BLOCKQUOTE") &&
+    reader._isProbablyVisible(node) &&
+    reader._getInnerText(node).length >= minChars;
+
+  const removeAndGetNext = reader._removeAndGetNext;
+  reader._removeAndGetNext = function (node) {
+    if (isLong(node)) {
+      return this._getNextNode(node);
+    }
+    return removeAndGetNext.call(this, node);
+  };
+
+  const clean = reader._clean;
+  reader._clean = function (e, tag) {
+    if (tag !== "aside") {
+      return clean.call(this, e, tag);
+    }
+    for (const aside of Array.from(e.getElementsByTagName("aside"))) {
+      if (!isLong(aside)) {
+        aside.remove();
+      }
+    }
+  };
+}
+
+// Link types that tell search engines how the original site vouches for a
+// link, which no longer holds once the content is republished.
+const SEO_LINK_TYPES = new Set(["nofollow", "sponsored", "ugc"]);
+
+// Rewrites the rel attribute of links in the article: "strip" removes it,
+// "normalize" lowercases and deduplicates it without the SEO link types.
+// With `unlinkSponsored`, sponsored links are replaced by their contents.
+function rewriteLinkRels(reader, mode, unlinkSponsored) {
+  const postProcessContent = reader._postProcessContent;
+  reader._postProcessContent = function (articleContent) {
+    postProcessContent.call(this, articleContent);
+    for (const link of Array.from(articleContent.getElementsByTagName("a"))) {
+      const types = (link.getAttribute("rel") || "")
+        .toLowerCase()
+        .split(/\s+/)
+        .filter(Boolean);
+      if (unlinkSponsored && types.includes("sponsored")) {
+        while (link.firstChild) {
+          link.parentNode.insertBefore(link.firstChild, link);
+        }
+        link.remove();
+      } else if (mode === "strip") {
+        link.removeAttribute("rel");
+      } else if (mode === "normalize" && link.hasAttribute("rel")) {
+        const kept = [...new Set(types)].filter((t) => !SEO_LINK_TYPES.has(t));
+        if (kept.length > 0) {
+          link.setAttribute("rel", kept.join(" "));
+        } else {
+          link.removeAttribute("rel");
+        }
+      }
+    }
+  };
+}
+
+// Highest content score Readability assigned within the grabbed content,
+// which is the score of the top candidate.
+function topCandidateScore(content) {
+  let best = null;
+  for (const node of [content, ...content.querySelectorAll("*")]) {
+    if (node.readability && (best === null || node.readability.contentScore > best)) {
+      best = node.readability.contentScore;
+    }
+  }
+  return best;
+}
+
+// Images with a declared size below this are icons, avatars or spacers.
+const MIN_LEAD_IMAGE_SIZE = 200;
+
+// Sizes and counts are read back as u32, so anything past that is treated as
+// undeclared rather than failing the whole extraction.
+function dimension(value) {
+  const n = parseInt(value, 10);
+  return n > 0 && n <= 0xffffffff ? n : null;
+}
+
+function metaContent(doc, selector) {
+  const meta = doc.querySelector(selector);
+  const content = meta && meta.getAttribute("content");
+  return content ? content.trim() : null;
+}
+
+// Picks the lead image from og:image, twitter:image or JSON-LD, in that order.
+function metaLeadImage(doc) {
+  const og = metaContent(
+    doc,
+    "meta[property='og:image:secure_url'], meta[property='og:image'], meta[property='og:image:url']",
+  );
+  if (og) {
+    return {
+      url: og,
+      width: dimension(metaContent(doc, "meta[property='og:image:width']")),
+      height: dimension(metaContent(doc, "meta[property='og:image:height']")),
+    };
+  }
+
+  const twitter = metaContent(
+    doc,
+    "meta[name='twitter:image'], meta[name='twitter:image:src'], meta[property='twitter:image']",
+  );
+  if (twitter) {
+    return { url: twitter, width: null, height: null };
+  }
+
+  const article = schemaArticle(doc);
+  for (const item of article ? [article, ...jsonLdItems(doc)] : jsonLdItems(doc)) {
+    const image = jsonLdImage(item.image || item.thumbnailUrl);
+    if (image) {
+      return image;
+    }
+  }
+  return null;
+}
+
+// All top-level JSON-LD objects of the page, including @graph members.
+function jsonLdItems(doc) {
+  const items = [];
+  for (const script of doc.querySelectorAll("script[type='application/ld+json']")) {
+    let data;
+    try {
+      data = JSON.parse(script.textContent);
+    } catch (e) {
+      continue;
+    }
+    for (const entry of Array.isArray(data) ? data : [data]) {
+      if (entry && Array.isArray(entry["@graph"])) {
+        items.push(...entry["@graph"].filter(Boolean));
+      } else if (entry) {
+        items.push(entry);
+      }
+    }
+  }
+  return items;
+}
+
+// Last modification time from meta tags, falling back to JSON-LD dateModified.
+function modifiedTime(doc) {
+  const meta = metaContent(
+    doc,
+    "meta[property='article:modified_time'], meta[property='og:updated_time'], meta[itemprop='dateModified']",
+  );
+  if (meta) {
+    return meta;
+  }
+  for (const item of jsonLdItems(doc)) {
+    if (typeof item.dateModified === "string" && item.dateModified.trim()) {
+      return item.dateModified.trim();
+    }
+  }
+  return null;
+}
+
+// Text of the first visible <h1>, preferring one inside the main content.
+function headline(doc) {
+  for (const selector of ["article h1", "main h1, [role='main'] h1", "h1"]) {
+    for (const h1 of doc.querySelectorAll(selector)) {
+      const text = h1.textContent.replace(/\s+/g, " ").trim();
+      if (text && isNodeVisible(h1)) {
+        return text;
+      }
+    }
+  }
+  return null;
+}
+
+// Dublin Core elements keyed by lower-cased name, e.g. "date.issued", from
+// both `DC.*` and `DCTERMS.*` meta tags.
+function dublinCore(doc) {
+  const dc = {};
+  for (const meta of doc.querySelectorAll("meta[content]")) {
+    const key = meta.getAttribute("name") || meta.getAttribute("property") || "";
+    const match = /^\s*dc(?:terms)?\s*[.:]\s*(\S+)\s*$/i.exec(key);
+    const content = meta.getAttribute("content").trim();
+    if (match && content) {
+      const element = match[1].toLowerCase();
+      if (!(element in dc)) {
+        dc[element] = content;
+      }
+    }
+  }
+  return dc;
+}
+
+// Plain text value of a microformats2 p-* or dt-* property element.
+function mfValue(node) {
+  const attr = (name) => node.getAttribute(name);
+  const value =
+    (node.matches("time, ins, del") && attr("datetime")) ||
+    (node.matches("abbr") && attr("title")) ||
+    (node.matches("data, input") && attr("value")) ||
+    (node.matches("img, area") && attr("alt")) ||
+    node.textContent;
+  return value ? value.replace(/\s+/g, " ").trim() || null : null;
+}
+
+// Properties of the first microformats2 h-entry on the page.
+function hEntry(doc) {
+  const entry = doc.querySelector(".h-entry");
+  if (!entry) {
+    return null;
+  }
+  // Properties of nested microformats (replies, author cards) belong to them
+  const property = (cls) => {
+    for (const node of entry.querySelectorAll("." + cls)) {
+      if (node.parentNode.closest(".h-entry, .h-card, .h-cite") === entry) {
+        return node;
+      }
+    }
+    return null;
+  };
+  const value = (cls) => {
+    const node = property(cls);
+    return node ? mfValue(node) : null;
+  };
+
+  let author = null;
+  const authorNode = property("p-author");
+  if (authorNode) {
+    const name = authorNode.matches(".h-card") && authorNode.querySelector(".p-name");
+    author = mfValue(name || authorNode);
+  }
+
+  return {
+    name: value("p-name"),
+    author,
+    published: value("dt-published"),
+    updated: value("dt-updated"),
+    summary: value("p-summary"),
+  };
+}
+
+// Fills metadata that Readability could not find from an h-entry, which
+// IndieWeb blogs use instead of OpenGraph.
+function applyHEntry(article, entry) {
+  if (!entry) {
+    return;
+  }
+  article.title = article.title || entry.name || "";
+  article.byline = article.byline || entry.author;
+  article.excerpt = article.excerpt || entry.summary;
+  article.publishedTime = article.publishedTime || entry.published;
+  article.modifiedTime = article.modifiedTime || entry.updated;
+}
+
+// Fills metadata that Readability could not find from Dublin Core, which is
+// often the only metadata on academic, government and library sites.
+function applyDublinCore(article, dc) {
+  const first = (...elements) => {
+    for (const element of elements) {
+      if (dc[element]) {
+        return dc[element];
+      }
+    }
+    return null;
+  };
+  article.title = article.title || first("title") || "";
+  article.byline = article.byline || first("creator", "contributor");
+  article.excerpt = article.excerpt || first("description", "abstract");
+  article.siteName = article.siteName || first("publisher");
+  article.lang = article.lang || first("language");
+  article.publishedTime =
+    article.publishedTime ||
+    first("date.issued", "issued", "date", "date.created", "created");
+  article.modifiedTime = article.modifiedTime || first("date.modified", "modified");
+}
+
+// The first JSON-LD object typed as an article (NewsArticle, BlogPosting, ...).
+function schemaArticle(doc) {
+  const articleTypes = Readability.prototype.REGEXPS.jsonLdArticleTypes;
+  for (const item of jsonLdItems(doc)) {
+    const types = Array.isArray(item["@type"]) ? item["@type"] : [item["@type"]];
+    if (types.some((type) => typeof type === "string" && articleTypes.test(type))) {
+      return item;
+    }
+  }
+  return null;
+}
+
+// Names of a schema.org author/publisher value: a string, a Person or
+// Organization, or a list of those.
+function schemaNames(value) {
+  const names = [];
+  for (const entry of Array.isArray(value) ? value : [value]) {
+    const name = typeof entry === "string" ? entry : entry && entry.name;
+    if (typeof name === "string" && name.trim()) {
+      names.push(name.trim());
+    }
+  }
+  return names.length ? names.join(", ") : null;
+}
+
+// Maps a schema.org article onto the fields Readability left empty. Values
+// Readability found itself (including from JSON-LD) always win, since they
+// went through its own sanity checks.
+function applySchemaArticle(article, item) {
+  if (!item) {
+    return;
+  }
+  const text = (value) =>
+    typeof value === "string" && value.trim() ? value.trim() : null;
+  article.title = article.title || text(item.headline) || text(item.name) || "";
+  article.byline = article.byline || schemaNames(item.author);
+  article.excerpt = article.excerpt || text(item.description);
+  article.siteName = article.siteName || schemaNames(item.publisher);
+  article.publishedTime = article.publishedTime || text(item.datePublished);
+  article.modifiedTime = article.modifiedTime || text(item.dateModified);
+  article.lang = article.lang || text(item.inLanguage);
+  article.wordCount = dimension(item.wordCount);
+}
+
+// Author meta tags Readability doesn't read, by how reliably they hold a name.
+// meta name=author is left out on purpose: Readability already takes its
+// byline from it, so the fallback only runs on pages without one.
+const AUTHOR_META_SELECTORS = [
+  "meta[name='byl']",
+  "meta[name='sailthru.author']",
+  "meta[property='article:author']",
+  "meta[itemprop='author']",
+];
+
+// The author from the remaining places pages declare one, for when
+// Readability finds no byline: JSON-LD of any type, author meta tags,
+// microdata and rel=author links.
+function bylineFallback(doc) {
+  const clean = (value) => {
+    const name = value && value.replace(/\s+/g, " ").trim().replace(/^by\s+/i, "");
+    // article:author often holds the author's profile URL instead
+    return name && !/^https?:\/\//i.test(name) ? name : null;
+  };
+  for (const item of jsonLdItems(doc)) {
+    const names = clean(schemaNames(item.author || item.creator));
+    if (names) {
+      return names;
+    }
+  }
+  for (const selector of AUTHOR_META_SELECTORS) {
+    const name = clean(metaContent(doc, selector));
+    if (name) {
+      return name;
+    }
+  }
+  for (const node of doc.querySelectorAll("[itemprop~='author'], a[rel~='author']")) {
+    const nameNode = node.querySelector("[itemprop~='name']") || node;
+    const name = clean(nameNode.getAttribute("content") || nameNode.textContent);
+    if (name && name.length <= 100) {
+      return name;
+    }
+  }
+  return null;
+}
+
+// Section from article:section, falling back to JSON-LD articleSection.
+function articleSection(doc) {
+  const meta = metaContent(doc, "meta[property='article:section']");
+  if (meta) {
+    return meta;
+  }
+  for (const item of jsonLdItems(doc)) {
+    let section = item.articleSection;
+    if (Array.isArray(section)) {
+      section = section[0];
+    }
+    if (typeof section === "string" && section.trim()) {
+      return section.trim();
+    }
+  }
+  return null;
+}
+
+// JSON-LD images are a URL, an ImageObject, or a list of either.
+function jsonLdImage(image) {
+  if (Array.isArray(image)) {
+    image = image[0];
+  }
+  if (typeof image === "string") {
+    return { url: image, width: null, height: null };
+  }
+  if (image && typeof image.url === "string") {
+    return {
+      url: image.url,
+      width: dimension(image.width),
+      height: dimension(image.height),
+    };
+  }
+  return null;
+}
+
+// Falls back to the first image of the extracted content that is not
+// declared smaller than MIN_LEAD_IMAGE_SIZE.
+function contentLeadImage(doc) {
+  for (const img of doc.querySelectorAll("img[src]")) {
+    const width = dimension(img.getAttribute("width"));
+    const height = dimension(img.getAttribute("height"));
+    if (
+      (width && width < MIN_LEAD_IMAGE_SIZE) ||
+      (height && height < MIN_LEAD_IMAGE_SIZE)
+    ) {
+      continue;
+    }
+    return { url: img.getAttribute("src"), width, height };
+  }
+  return null;
+}
+
+// Largest declared side of a `sizes` attribute; "any" is used by scalable icons.
+function iconSize(sizes) {
+  if (!sizes) {
+    return 0;
+  }
+  if (sizes.toLowerCase().includes("any")) {
+    return Infinity;
+  }
+  let best = 0;
+  for (const size of sizes.toLowerCase().split(/\s+/)) {
+    best = Math.max(best, dimension(size.split("x")[0]) || 0);
+  }
+  return best;
+}
+
+// Picks the largest declared icon, preferring apple-touch-icon on ties since
+// it is meant for display at a readable size.
+function bestSiteIcon(doc) {
+  let best = null;
+  let bestRank = -1;
+  for (const link of doc.querySelectorAll("link[rel][href]")) {
+    const rel = link.getAttribute("rel").toLowerCase().split(/\s+/);
+    const touch =
+      rel.includes("apple-touch-icon") ||
+      rel.includes("apple-touch-icon-precomposed");
+    if (!touch && !rel.includes("icon")) {
+      continue;
+    }
+    // apple-touch-icon defaults to 180x180 when no size is given
+    const size = iconSize(link.getAttribute("sizes")) || (touch ? 180 : 16);
+    const rank = size * 2 + (touch ? 1 : 0);
+    if (rank > bestRank) {
+      best = link.getAttribute("href").trim();
+      bestRank = rank;
+    }
+  }
+  return best || null;
+}
+
+const COMMENTS_SELECTOR = [
+  "#comments",
+  "#disqus_thread",
+  ".comments-area",
+  ".comment-list",
+  ".commentlist",
+  "section.comments",
+  "[itemtype$='schema.org/Comment']",
+  "[itemprop='comment']",
+].join(", ");
+
+const COMMENTS_NOISE =
+  "script, style, noscript, form, iframe, button, input, textarea, select";
+
+// Removes the comments section from doc and returns it as cleaned HTML,
+// or null when there is no comment text (e.g. an empty Disqus placeholder).
+function takeComments(doc) {
+  let container = doc.querySelector(COMMENTS_SELECTOR);
+  if (!container) {
+    return null;
+  }
+  // Individual schema.org comments are gathered under their common parent
+  if (
+    container.matches("[itemtype$='schema.org/Comment'], [itemprop='comment']") &&
+    container.parentNode !== doc.body
+  ) {
+    container = container.parentNode;
+  }
+  container.remove();
+
+  container.querySelectorAll(COMMENTS_NOISE).forEach((node) => node.remove());
+  for (const node of [container, ...container.querySelectorAll("*")]) {
+    for (const attr of Array.from(node.attributes)) {
+      if (attr.name === "style" || attr.name.startsWith("on")) {
+        node.removeAttribute(attr.name);
+      }
+    }
+  }
+
+  if (!container.textContent.trim()) {
+    return null;
+  }
+  return container.outerHTML;
+}
+
+// Mirrors the scoring loop of isProbablyReaderable without stopping once
+// minScore is reached, so callers can see how readerable a page is.
+function readerableScore(doc, options) {
+  const regexps = Readability.prototype.REGEXPS;
+  let nodes = doc.querySelectorAll("p, pre, article");
+  const brNodes = doc.querySelectorAll("div > br");
+  if (brNodes.length) {
+    const set = new Set(nodes);
+    brNodes.forEach((node) => set.add(node.parentNode));
+    nodes = Array.from(set);
+  }
+
+  let score = 0;
+  for (const node of nodes) {
+    if (!isNodeVisible(node)) {
+      continue;
+    }
+    const matchString = node.className + " " + node.id;
+    if (
+      regexps.unlikelyCandidates.test(matchString) &&
+      !regexps.okMaybeItsACandidate.test(matchString)
+    ) {
+      continue;
+    }
+    if (node.matches("li p")) {
+      continue;
+    }
+    const textContentLength = node.textContent.trim().length;
+    if (textContentLength < options.minContentLength) {
+      continue;
+    }
+    score += Math.sqrt(textContentLength - options.minContentLength);
+  }
+  return score;
+}
+
+function check(html, options) {
+  try {
+    const domParser = new DOMParser();
+    let doc;
+
+    try {
+      doc = domParser.parseFromString(html, "text/html");
+    } catch (e) {
+      return {
+        errorType: "HtmlParseError",
+        error: "Failed to parse HTML: " + e.message,
+      };
+    }
+
+    const checkOptions = Object.assign(
+      { minScore: 20, minContentLength: 140 },
+      options || {},
+    );
+    return {
+      readerable: isProbablyReaderable(doc, checkOptions),
+      score: readerableScore(doc, checkOptions),
+    };
+  } catch (e) {
+    return {
+      errorType: "RuntimeError",
+      error: "Unexpected error: " + e.message,
+    };
+  }
+}
+
+// Underline characters of each heading level, after the "=" over- and
+// underlined document title.
+const RST_HEADINGS = { H1: "=", H2: "-", H3: "~", H4: "^", H5: '"', H6: '"' };
+
+// Render an article as reStructuredText, for Sphinx-based toolchains.
+function toRst(title, html) {
+  try {
+    const doc = new DOMParser().parseFromString(
+      "<!DOCTYPE html><html><head></head><body>" + html + "</body></html>",
+      "text/html",
+    );
+    const blocks = [];
+    const heading = rstEscape((title || "").replace(/\s+/g, " ").trim());
+    if (heading) {
+      const bar = "=".repeat(heading.length);
+      blocks.push(bar + "\n" + heading + "\n" + bar);
+    }
+    rstBlocks(doc.body, blocks);
+    return blocks.join("\n\n") + "\n";
+  } catch (e) {
+    return {
+      errorType: "RuntimeError",
+      error: "Unexpected error: " + e.message,
+    };
+  }
+}
+
+function rstEscape(text) {
+  return text.replace(/[\\*`_|]/g, "\\$&");
+}
+
+function rstIndent(text, width) {
+  const pad = " ".repeat(width);
+  return text
+    .split("\n")
+    .map((line) => (line ? pad + line : line))
+    .join("\n");
+}
+
+// Append the blocks of `parent`'s children, joining runs of inline content
+// into paragraphs.
+function rstBlocks(parent, blocks) {
+  let inline = "";
+  const flush = () => {
+    const text = inline.replace(/\s+/g, " ").trim();
+    if (text) {
+      blocks.push(text);
+    }
+    inline = "";
+  };
+
+  for (const node of Array.from(parent.childNodes)) {
+    if (node.nodeType === 3) {
+      inline += rstEscape(node.textContent);
+    } else if (node.nodeType === 1) {
+      const block =
+        BLOCK_TAGS.has(node.tagName) ||
+        PREFORMATTED_TAGS.has(node.tagName) ||
+        node.tagName === "IMG";
+      if (block) {
+        flush();
+        rstBlock(node, blocks);
+      } else {
+        inline += rstInline(node);
+      }
+    }
+  }
+  flush();
+}
+
+function rstBlock(element, blocks) {
+  const tag = element.tagName;
+  if (RST_HEADINGS[tag]) {
+    const text = rstInlineChildren(element).replace(/\s+/g, " ").trim();
+    if (text) {
+      blocks.push(text + "\n" + RST_HEADINGS[tag].repeat(text.length));
+    }
+  } else if (tag === "PRE") {
+    const code = element.textContent.replace(/\s+$/, "");
+    if (code) {
+      blocks.push("::\n\n" + rstIndent(code, 4));
+    }
+  } else if (tag === "BLOCKQUOTE") {
+    const quote = [];
+    rstBlocks(element, quote);
+    if (quote.length) {
+      // An empty comment keeps the quote from continuing a preceding
+      // literal block or list item
+      if (blocks.length) {
+        blocks.push("..");
+      }
+      blocks.push(rstIndent(quote.join("\n\n"), 4));
+    }
+  } else if (tag === "UL" || tag === "OL") {
+    const marker = tag === "OL" ? "#. " : "- ";
+    const items = [];
+    for (const item of Array.from(element.children)) {
+      const body = [];
+      rstBlocks(item, body);
+      const text = body.join("\n\n");
+      // Continuation lines line up with the first line's text
+      items.push(marker + rstIndent(text, marker.length).slice(marker.length));
+    }
+    if (items.length) {
+      blocks.push(items.join("\n"));
+    }
+  } else if (tag === "TABLE") {
+    rstTable(element, blocks);
+  } else if (tag === "HR") {
+    blocks.push("----");
+  } else if (tag === "IMG") {
+    const src = element.getAttribute("src");
+    if (src) {
+      const alt = element.getAttribute("alt");
+      blocks.push(".. image:: " + src + (alt ? "\n   :alt: " + alt : ""));
+    }
+  } else {
+    rstBlocks(element, blocks);
+  }
+}
+
+function rstTable(table, blocks) {
+  const rows = Array.from(table.querySelectorAll("tr"))
+    .map((row) =>
+      Array.from(row.children).filter(
+        (cell) => cell.tagName === "TD" || cell.tagName === "TH",
+      ),
+    )
+    .filter((cells) => cells.length);
+  if (!rows.length) {
+    return;
+  }
+  const header = rows[0].every((cell) => cell.tagName === "TH");
+  const lines = [".. list-table::"];
+  if (header) {
+    lines.push("   :header-rows: 1");
+  }
+  lines.push("");
+  for (const cells of rows) {
+    cells.forEach((cell, i) => {
+      const text = rstInlineChildren(cell).replace(/\s+/g, " ").trim();
+      lines.push((i === 0 ? "   * - " : "     - ") + text);
+    });
+  }
+  blocks.push(lines.join("\n"));
+}
+
+function rstInline(element) {
+  const tag = element.tagName;
+  if (tag === "BR") {
+    return " ";
+  }
+  if (tag === "IMG") {
+    return rstEscape(element.getAttribute("alt") || "");
+  }
+  if (tag === "CODE" || tag === "KBD" || tag === "SAMP" || tag === "TT") {
+    return rstWrap(element.textContent, "``");
+  }
+  const inner = rstInlineChildren(element);
+  if (tag === "STRONG" || tag === "B") {
+    return rstWrap(inner, "**");
+  }
+  if (tag === "EM" || tag === "I") {
+    return rstWrap(inner, "*");
+  }
+  if (tag === "A") {
+    const href = element.getAttribute("href");
+    const text = inner.replace(/\s+/g, " ").trim();
+    if (!href) {
+      return inner;
+    }
+    // Anonymous targets, so links with the same text don't clash
+    return "`" + (text || rstEscape(href)) + " <" + href + ">`__";
+  }
+  return inner;
+}
+
+function rstInlineChildren(element) {
+  let text = "";
+  for (const node of Array.from(element.childNodes)) {
+    if (node.nodeType === 3) {
+      text += rstEscape(node.textContent);
+    } else if (node.nodeType === 1) {
+      text += rstInline(node);
+    }
+  }
+  return text;
+}
+
+// Wrap inline markup around `text`, keeping surrounding whitespace outside.
+function rstWrap(text, marker) {
+  const trimmed = text.replace(/\s+/g, " ").trim();
+  if (!trimmed) {
+    return text;
+  }
+  const lead = /^\s/.test(text) ? " " : "";
+  const trail = /\s$/.test(text) ? " " : "";
+  return lead + marker + trimmed + marker + trail;
+}
+
+// Returns the href of the first element matching selector, or null.
+function findLink(html, selector) {
+  try {
+    const doc = new DOMParser().parseFromString(html, "text/html");
+    const link = doc.querySelector(selector);
+    return link ? link.getAttribute("href") : null;
+  } catch (e) {
+    return {
+      errorType: "RuntimeError",
+      error: "Unexpected error: " + e.message,
+    };
+  }
+}
+
+// The visible text of a document or fragment, one entry per block, with
+// whitespace collapsed.
+function textBlocks(html) {
+  try {
+    const doc = new DOMParser().parseFromString(html, "text/html");
+    const blocks = [];
+    let text = "";
+    const flush = () => {
+      const block = text.replace(/\s+/g, " ").trim();
+      if (block) {
+        blocks.push(block);
+      }
+      text = "";
+    };
+    const walk = (parent) => {
+      for (const node of Array.from(parent.childNodes)) {
+        if (node.nodeType === 3) {
+          text += node.textContent;
+        } else if (node.nodeType === 1 && !INVISIBLE_TAGS.has(node.tagName)) {
+          const block = BLOCK_TAGS.has(node.tagName) || node.tagName === "PRE";
+          if (block || node.tagName === "BR") {
+            flush();
+          }
+          walk(node);
+          if (block) {
+            flush();
+          }
+        }
+      }
+    };
+    walk(doc.body || doc.documentElement);
+    flush();
+    return blocks;
+  } catch (e) {
+    return {
+      errorType: "RuntimeError",
+      error: "Unexpected error: " + e.message,
+    };
+  }
+}
+
+const INVISIBLE_TAGS = new Set(["SCRIPT", "STYLE", "NOSCRIPT", "TEMPLATE"]);
+
 globalThis.extract = extract;
+globalThis.check = check;
+globalThis.findLink = findLink;
+globalThis.toRst = toRst;
+globalThis.textBlocks = textBlocks;
@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+use crate::engine::{Engine, EngineConfig};
+use crate::readability::{Readability, ReadabilityOptions, Result};
+
+/// Configures a [`Readability`] parser before its engine starts.
+///
+/// Created with [`Readability::builder`]. Every setting is optional; an
+/// unconfigured builder produces the same parser as [`Readability::new`].
+#[derive(Debug, Clone, Default)]
+pub struct ReadabilityBuilder {
+    engine: EngineConfig,
+    timeout: Option<Duration>,
+    cache_capacity: Option<usize>,
+    default_options: Option<ReadabilityOptions>,
+}
+
+impl ReadabilityBuilder {
+    /// Creates a builder with default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cap the memory the JavaScript heap may allocate.
+    ///
+    /// Extractions that exceed the cap fail instead of growing the process.
+    ///
+    /// # Arguments
+    /// * `bytes` - Heap limit in bytes
+    pub fn memory_limit(mut self, bytes: usize) -> Self {
+        self.engine.memory_limit = Some(bytes);
+        self
+    }
+
+    /// Cap the JavaScript stack size.
+    ///
+    /// Deeply nested documents recurse deeply; a larger stack lets them
+    /// parse, a smaller one fails them sooner.
+    ///
+    /// # Arguments
+    /// * `bytes` - Stack limit in bytes
+    pub fn max_stack_size(mut self, bytes: usize) -> Self {
+        self.engine.max_stack_size = Some(bytes);
+        self
+    }
+
+    /// Run the garbage collector whenever the heap grows by `bytes`.
+    ///
+    /// Lower thresholds keep memory flatter at the cost of more frequent
+    /// collection pauses; higher ones trade memory for throughput. Pair a
+    /// high threshold with [`Readability::run_gc`] to collect at moments
+    /// of your choosing.
+    ///
+    /// # Arguments
+    /// * `bytes` - Allocation growth that triggers a collection
+    pub fn gc_threshold(mut self, bytes: usize) -> Self {
+        self.engine.gc_threshold = Some(bytes);
+        self
+    }
+
+    /// Rebuild the JavaScript runtime after every `parses` extractions.
+    ///
+    /// Long-lived runtimes accumulate garbage the collector can't reclaim,
+    /// so resident memory creeps up over millions of documents. Recycling
+    /// starts a fresh runtime between calls; results are unaffected.
+    ///
+    /// # Arguments
+    /// * `parses` - Number of calls after which the runtime is replaced
+    pub fn recycle_after(mut self, parses: usize) -> Self {
+        self.engine.recycle_after = Some(parses);
+        self
+    }
+
+    /// Rebuild the JavaScript runtime once its heap exceeds `bytes`.
+    ///
+    /// Checked after every call; see [`ReadabilityBuilder::recycle_after`].
+    ///
+    /// # Arguments
+    /// * `bytes` - Heap size that triggers a rebuild
+    pub fn recycle_above(mut self, bytes: usize) -> Self {
+        self.engine.recycle_above = Some(bytes);
+        self
+    }
+
+    /// Limit how long a single extraction may run.
+    ///
+    /// See [`Readability::with_timeout`].
+    ///
+    /// # Arguments
+    /// * `timeout` - Maximum time per extraction
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Cache up to `capacity` extracted articles.
+    ///
+    /// See [`Readability::with_cache`].
+    ///
+    /// # Arguments
+    /// * `capacity` - Maximum number of cached articles
+    pub fn cache(mut self, capacity: usize) -> Self {
+        self.cache_capacity = Some(capacity);
+        self
+    }
+
+    /// Evaluate an extra script after Readability.js is loaded.
+    ///
+    /// Scripts run once per engine, in the order added, and can define
+    /// globals or patch `Readability.prototype`.
+    ///
+    /// # Arguments
+    /// * `name` - Name used in load errors
+    /// * `source` - JavaScript source to evaluate
+    pub fn script(mut self, name: impl Into<String>, source: impl Into<String>) -> Self {
+        self.engine.scripts.push((name.into(), source.into()));
+        self
+    }
+
+    /// Options used for extractions that don't set them.
+    ///
+    /// Options passed to a call take precedence field by field; anything
+    /// they leave unset falls back to these.
+    ///
+    /// # Arguments
+    /// * `options` - Default extraction options
+    pub fn default_options(mut self, options: ReadabilityOptions) -> Self {
+        self.default_options = Some(options);
+        self
+    }
+
+    /// Start the engine and create the parser.
+    ///
+    /// Fails with [`InvalidOptions`](crate::ReadabilityError::InvalidOptions) if the
+    /// [default options](Self::default_options) don't pass
+    /// [`ReadabilityOptions::validate`].
+    pub fn build(self) -> Result<Readability> {
+        if let Some(options) = &self.default_options {
+            options.validate()?;
+        }
+        let engine = Engine::new(self.engine)?;
+        let reader = Readability::from_parts(engine, self.timeout, self.default_options);
+        Ok(match self.cache_capacity {
+            Some(capacity) => reader.with_cache(capacity),
+            None => reader,
+        })
+    }
+}
@@ -0,0 +1,191 @@
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+
+use crate::readability::{Article, ReadabilityOptions};
+
+/// Hit/miss counters of the result cache enabled with
+/// [`Readability::with_cache`](crate::Readability::with_cache).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Extractions answered from the cache
+    pub hits: u64,
+
+    /// Extractions that had to run Readability.js
+    pub misses: u64,
+
+    /// Number of articles currently cached
+    pub entries: usize,
+
+    /// Maximum number of cached articles
+    pub capacity: usize,
+}
+
+/// The inputs of an extraction, kept whole so that a hit is never a hash
+/// collision with someone else's document.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Key {
+    html: String,
+    base_url: Option<String>,
+    /// Options through their `Debug` representation, so that every field,
+    /// including ones added later, takes part in the key
+    options: Option<String>,
+}
+
+impl Key {
+    pub(crate) fn new(
+        html: &str,
+        base_url: Option<&str>,
+        options: Option<&ReadabilityOptions>,
+    ) -> Self {
+        Self {
+            html: html.to_string(),
+            base_url: base_url.map(str::to_string),
+            options: options.map(|o| format!("{:?}", o)),
+        }
+    }
+}
+
+struct Entry {
+    article: Article,
+    /// Tick of the last use, the entry's position in `ResultCache::order`
+    used: u64,
+}
+
+/// Least-recently-used cache of extracted articles.
+pub(crate) struct ResultCache {
+    capacity: usize,
+    entries: HashMap<Rc<Key>, Entry>,
+    // Keys by the tick of their last use, least recently used first
+    order: BTreeMap<u64, Rc<Key>>,
+    tick: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl ResultCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            tick: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    pub(crate) fn get(&mut self, key: &Key) -> Option<Article> {
+        let Some(entry) = self.entries.get_mut(key) else {
+            self.misses += 1;
+            return None;
+        };
+        self.hits += 1;
+        self.tick += 1;
+        if let Some(key) = self.order.remove(&entry.used) {
+            self.order.insert(self.tick, key);
+        }
+        entry.used = self.tick;
+        Some(entry.article.clone())
+    }
+
+    pub(crate) fn insert(&mut self, key: Key, article: Article) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.tick += 1;
+        let key = Rc::new(key);
+        let entry = Entry {
+            article,
+            used: self.tick,
+        };
+        match self.entries.insert(Rc::clone(&key), entry) {
+            Some(replaced) => {
+                self.order.remove(&replaced.used);
+            }
+            None if self.entries.len() > self.capacity => {
+                if let Some((_, oldest)) = self.order.pop_first() {
+                    self.entries.remove(&oldest);
+                }
+            }
+            None => {}
+        }
+        self.order.insert(self.tick, key);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+
+    pub(crate) fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            entries: self.entries.len(),
+            capacity: self.capacity,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn article(title: &str) -> Article {
+        Article {
+            title: title.into(),
+            ..Default::default()
+        }
+    }
+
+    fn key(html: &str) -> Key {
+        Key::new(html, None, None)
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let mut cache = ResultCache::new(2);
+        cache.insert(key("1"), article("one"));
+        cache.insert(key("2"), article("two"));
+        assert!(cache.get(&key("1")).is_some());
+
+        cache.insert(key("3"), article("three"));
+        assert!(cache.get(&key("2")).is_none());
+        assert_eq!(cache.get(&key("1")).unwrap().title, "one");
+        assert_eq!(cache.get(&key("3")).unwrap().title, "three");
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (3, 1, 2));
+    }
+
+    #[test]
+    fn test_key_depends_on_all_inputs() {
+        let options = ReadabilityOptions::new().char_threshold(100);
+        let base = Key::new("<p>a</p>", None, None);
+        assert_eq!(base, Key::new("<p>a</p>", None, None));
+        assert_ne!(base, Key::new("<p>b</p>", None, None));
+        assert_ne!(
+            base,
+            Key::new("<p>a</p>", Some("https://example.com"), None)
+        );
+        assert_ne!(base, Key::new("<p>a</p>", None, Some(&options)));
+    }
+
+    #[test]
+    fn test_reinsert_replaces_without_evicting() {
+        let mut cache = ResultCache::new(2);
+        cache.insert(key("1"), article("one"));
+        cache.insert(key("2"), article("two"));
+        cache.insert(key("1"), article("uno"));
+        assert_eq!(cache.stats().entries, 2);
+        assert_eq!(cache.get(&key("1")).unwrap().title, "uno");
+        assert_eq!(cache.get(&key("2")).unwrap().title, "two");
+    }
+
+    #[test]
+    fn test_large_capacity_does_not_preallocate() {
+        let mut cache = ResultCache::new(usize::MAX);
+        cache.insert(key("1"), article("one"));
+        assert_eq!(cache.stats().entries, 1);
+    }
+}
@@ -0,0 +1,175 @@
+//! Loading [`ReadabilityOptions`] from configuration files.
+
+use crate::readability::{ReadabilityError, ReadabilityOptions};
+
+/// Keys accepted in a configuration file, the field names of
+/// [`ReadabilityOptions`].
+const KEYS: &[&str] = &[
+    "max_elems_to_parse",
+    "nb_top_candidates",
+    "char_threshold",
+    "classes_to_preserve",
+    "keep_classes",
+    "disable_jsonld",
+    "link_density_modifier",
+    "extract_comments",
+    "clean_title",
+    "prefer_h1_title",
+    "generate_excerpt",
+    "strip_data_uris_above",
+    "max_data_uri_bytes",
+    "max_output_bytes",
+    "html_format",
+    "keep_inline_styles",
+    "keep_asides_above",
+    "convert_divs_to_paragraphs",
+    "collect_stats",
+    "record_removed",
+    "link_rel",
+    "unlink_sponsored",
+    "collapse_whitespace",
+    "trim_lines",
+    "normalize_spaces",
+];
+
+impl ReadabilityOptions {
+    /// Read options from a TOML document.
+    ///
+    /// Keys are the field names of [`ReadabilityOptions`]; keys left out keep
+    /// their default. Lets services keep extraction tuning in configuration
+    /// instead of code.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use readability_js::ReadabilityOptions;
+    ///
+    /// let options = ReadabilityOptions::from_toml(
+    ///     r#"
+    ///     char_threshold = 300
+    ///     classes_to_preserve = ["highlight"]
+    ///     "#,
+    /// )?;
+    /// assert_eq!(options.char_threshold, Some(300));
+    /// # Ok::<(), readability_js::ReadabilityError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReadabilityError::InvalidOptions`] for malformed TOML, an
+    /// unknown key or a value of the wrong type, naming the key at fault, and
+    /// for values [`ReadabilityOptions::validate`] rejects.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(toml: &str) -> Result<Self, ReadabilityError> {
+        let table: toml::Table = toml::from_str(toml).map_err(|e| {
+            let line = e
+                .span()
+                .map_or(1, |span| toml[..span.start].matches('\n').count() + 1);
+            invalid(format!("malformed TOML at line {}: {}", line, e.message()))
+        })?;
+        check_keys(table.keys())?;
+        let options: Self = serde_path_to_error::deserialize(toml::Value::Table(table))
+            .map_err(|e| invalid(format!("`{}`: {}", e.path(), e.inner().message())))?;
+        options.validate()?;
+        Ok(options)
+    }
+
+    /// Read options from a JSON object.
+    ///
+    /// Accepts the same keys as [`ReadabilityOptions::from_toml`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use readability_js::ReadabilityOptions;
+    ///
+    /// let options = ReadabilityOptions::from_json(r#"{"nb_top_candidates": 10}"#)?;
+    /// assert_eq!(options.nb_top_candidates, Some(10));
+    ///
+    /// let error = ReadabilityOptions::from_json(r#"{"char_threshold": "many"}"#).unwrap_err();
+    /// assert!(error.to_string().contains("char_threshold"));
+    /// # Ok::<(), readability_js::ReadabilityError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// The same as [`ReadabilityOptions::from_toml`].
+    #[cfg(feature = "json")]
+    pub fn from_json(json: &str) -> Result<Self, ReadabilityError> {
+        let value: serde_json::Value =
+            serde_json::from_str(json).map_err(|e| invalid(format!("malformed JSON: {}", e)))?;
+        let serde_json::Value::Object(object) = &value else {
+            return Err(invalid("expected a JSON object".into()));
+        };
+        check_keys(object.keys())?;
+        let options: Self = serde_path_to_error::deserialize(value)
+            .map_err(|e| invalid(format!("`{}`: {}", e.path(), e.inner())))?;
+        options.validate()?;
+        Ok(options)
+    }
+}
+
+/// Reject keys that are not options, which are most likely typos.
+fn check_keys<'a>(keys: impl IntoIterator<Item = &'a String>) -> Result<(), ReadabilityError> {
+    match keys.into_iter().find(|key| !KEYS.contains(&key.as_str())) {
+        Some(key) => Err(invalid(format!("unknown option `{}`", key))),
+        None => Ok(()),
+    }
+}
+
+fn invalid(message: String) -> ReadabilityError {
+    ReadabilityError::InvalidOptions(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys_match_fields() {
+        let value = serde_json::to_value(ReadabilityOptions::default()).unwrap();
+        let fields: Vec<&str> = value
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        let mut keys = KEYS.to_vec();
+        keys.sort_unstable();
+        assert_eq!(fields, keys);
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_from_toml() {
+        let options = ReadabilityOptions::from_toml(
+            "nb_top_candidates = 8\nlink_density_modifier = 0\nhtml_format = \"Minified\"",
+        )
+        .unwrap();
+        assert_eq!(options.nb_top_candidates, Some(8));
+        assert_eq!(options.link_density_modifier, Some(0.0));
+        assert_eq!(options.html_format, Some(crate::HtmlFormat::Minified));
+
+        let error = ReadabilityOptions::from_toml("char_treshold = 100").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid options: unknown option `char_treshold`"
+        );
+        let error = ReadabilityOptions::from_toml("classes_to_preserve = [\"a\", 1]").unwrap_err();
+        assert!(
+            error.to_string().contains("`classes_to_preserve[1]`"),
+            "{}",
+            error
+        );
+        assert!(ReadabilityOptions::from_toml("char_threshold = ").is_err());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_from_json() {
+        let error = ReadabilityOptions::from_json(r#"{"keep_classes": "yes"}"#).unwrap_err();
+        assert!(error.to_string().contains("`keep_classes`"), "{}", error);
+        assert!(ReadabilityOptions::from_json("[]").is_err());
+        assert!(ReadabilityOptions::from_json("{").is_err());
+    }
+}
@@ -0,0 +1,214 @@
+//! Decoding response bodies the way browsers do, guided by `Content-Type`.
+
+use encoding_rs::{Encoding, UTF_8, UTF_16BE, UTF_16LE, WINDOWS_1252, X_USER_DEFINED};
+
+/// Bytes of the document searched for a `<meta>` charset declaration.
+const PRESCAN_BYTES: usize = 1024;
+
+/// Elements that never have content, so `<br/>` means the same in HTML.
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Decode `body` to markup the HTML parser can take.
+///
+/// The encoding comes from a byte order mark, then the `charset` parameter
+/// of `content_type`, then a `<meta>` declaration near the start, and
+/// defaults to UTF-8. Charsets are looked up by their WHATWG labels, as
+/// browsers do, and unknown ones are ignored. XHTML is rewritten so the
+/// HTML parser reads it as an XML parser would.
+pub(crate) fn decode(body: &[u8], content_type: Option<&str>) -> String {
+    let (mime, header_charset) = content_type.map_or((None, None), parse);
+
+    let (encoding, bom_length) = Encoding::for_bom(body)
+        .or_else(|| {
+            let header = header_charset.and_then(|label| Encoding::for_label(label.as_bytes()));
+            header
+                .or_else(|| meta_charset(body))
+                .map(|encoding| (encoding, 0))
+        })
+        .unwrap_or((UTF_8, 0));
+    let (text, _) = encoding.decode_without_bom_handling(&body[bom_length..]);
+
+    match mime.as_deref() {
+        Some("application/xhtml+xml") => xhtml_to_html(&text),
+        _ => text.into_owned(),
+    }
+}
+
+/// Split a `Content-Type` value into its lower-cased MIME type and charset.
+fn parse(content_type: &str) -> (Option<String>, Option<String>) {
+    let mut parts = content_type.split(';');
+    let mime = parts
+        .next()
+        .map(|m| m.trim().to_ascii_lowercase())
+        .filter(|m| !m.is_empty());
+    let charset = parts.find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case("charset")
+            .then(|| value.trim().trim_matches(['"', '\'']).to_ascii_lowercase())
+    });
+    (mime, charset)
+}
+
+/// Find `charset=` in the first bytes of the document, as in
+/// `<meta charset="...">` or `<meta http-equiv content="...; charset=...">`.
+fn meta_charset(body: &[u8]) -> Option<&'static Encoding> {
+    let head = String::from_utf8_lossy(&body[..body.len().min(PRESCAN_BYTES)]).to_ascii_lowercase();
+    let start = head.find("<meta")?;
+    let value = &head[start..][head[start..].find("charset=")? + "charset=".len()..];
+    let charset: String = value
+        .trim_start_matches(['"', '\''])
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':' | '.'))
+        .collect();
+    // Bytes that were readable as ASCII cannot be UTF-16, and browsers
+    // override the user-defined encoding too
+    match Encoding::for_label(charset.as_bytes())? {
+        encoding if encoding == UTF_16LE || encoding == UTF_16BE => Some(UTF_8),
+        encoding if encoding == X_USER_DEFINED => Some(WINDOWS_1252),
+        encoding => Some(encoding),
+    }
+}
+
+/// Rewrite XML-only syntax: drop the XML declaration and expand
+/// self-closing non-void elements like `<div/>`, which an HTML parser would
+/// treat as an opening tag.
+fn xhtml_to_html(xhtml: &str) -> String {
+    let mut rest = xhtml.trim_start();
+    if rest.starts_with("<?xml")
+        && let Some(end) = rest.find("?>")
+    {
+        rest = &rest[end + 2..];
+    }
+
+    let mut html = String::with_capacity(rest.len());
+    while let Some(start) = rest.find('<') {
+        html.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        // Comments and CDATA can contain anything, copy them as they are
+        let skip_to = if rest.starts_with("<!--") {
+            Some("-->")
+        } else if rest.starts_with("<![CDATA[") {
+            Some("]]>")
+        } else {
+            None
+        };
+        if let Some(close) = skip_to {
+            let end = rest.find(close).map_or(rest.len(), |i| i + close.len());
+            html.push_str(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+
+        let Some(end) = tag_end(rest) else {
+            break;
+        };
+        let tag = &rest[..end];
+        let name: String = tag[1..]
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | ':' | '_'))
+            .collect();
+        let name = name.to_ascii_lowercase();
+        if tag.ends_with("/>") && !name.is_empty() && !VOID_ELEMENTS.contains(&name.as_str()) {
+            html.push_str(tag[..tag.len() - 2].trim_end());
+            html.push_str("></");
+            html.push_str(&name);
+            html.push('>');
+        } else {
+            html.push_str(tag);
+        }
+        rest = &rest[end..];
+    }
+    html.push_str(rest);
+    html
+}
+
+/// Byte index just past the `>` closing the tag at the start of `text`,
+/// skipping over quoted attribute values.
+fn tag_end(text: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in text.char_indices().skip(1) {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '>') => return Some(i + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_uses_header_then_meta_charset() {
+        let body = b"<p>caf\xE9 \x93quoted\x94</p>";
+        assert_eq!(
+            decode(body, Some("text/html; charset=ISO-8859-1")),
+            "<p>café \u{201C}quoted\u{201D}</p>"
+        );
+
+        let body = b"<meta charset=\"windows-1252\"><p>caf\xE9</p>";
+        assert!(decode(body, Some("text/html")).ends_with("<p>café</p>"));
+        assert_eq!(decode("<p>café</p>".as_bytes(), None), "<p>café</p>");
+    }
+
+    #[test]
+    fn test_decode_legacy_encodings() {
+        assert_eq!(
+            decode(
+                b"<p>\x93\xFA\x96{</p>",
+                Some("text/html; charset=Shift_JIS")
+            ),
+            "<p>日本</p>"
+        );
+        assert_eq!(
+            decode(
+                b"<p>\xC7\xD1\xB1\xB9</p>",
+                Some("text/html; charset=euc-kr")
+            ),
+            "<p>한국</p>"
+        );
+        let body = b"<meta charset=koi8-r><p>\xD0\xD2\xC9\xD7\xC5\xD4</p>";
+        assert!(decode(body, None).ends_with("<p>привет</p>"));
+        let body = b"<meta http-equiv=\"Content-Type\" content=\"text/html; charset=iso-8859-2\"><p>\xB3\xF3d\xBC</p>";
+        assert!(decode(body, None).ends_with("<p>łódź</p>"));
+    }
+
+    #[test]
+    fn test_decode_bom_and_unknown_labels() {
+        assert_eq!(
+            decode(b"\xFF\xFE<\0p\0>\0", Some("text/html; charset=koi8-r")),
+            "<p>"
+        );
+        assert_eq!(decode(b"\xEF\xBB\xBF<p>", None), "<p>");
+        assert_eq!(
+            decode(
+                "<p>café</p>".as_bytes(),
+                Some("text/html; charset=no-such-charset")
+            ),
+            "<p>café</p>"
+        );
+        // A meta declaration read as ASCII cannot really be UTF-16
+        assert_eq!(
+            decode(b"<meta charset=utf-16><p>", None),
+            "<meta charset=utf-16><p>"
+        );
+    }
+
+    #[test]
+    fn test_xhtml_self_closing_elements_are_expanded() {
+        let xhtml = r#"<?xml version="1.0"?><html><body><div id="a"/><p title="a/>b">x<br/></p></body></html>"#;
+        assert_eq!(
+            decode(xhtml.as_bytes(), Some("application/xhtml+xml")),
+            r#"<html><body><div id="a"></div><p title="a/>b">x<br/></p></body></html>"#
+        );
+    }
+}
@@ -0,0 +1,87 @@
+//! Handling of inlined `data:` URIs, as produced by tools like SingleFile.
+
+use std::borrow::Cow;
+
+/// Marker SingleFile writes into the comment at the top of saved pages.
+const SINGLEFILE_MARKER: &str = "Page saved with SingleFile";
+
+/// The original address of a page saved by SingleFile, from the
+/// `url: ...` line of its header comment.
+pub(crate) fn singlefile_url(html: &str) -> Option<&str> {
+    let comment_start = html.find("<!--")?;
+    let comment = &html[comment_start..];
+    let comment = &comment[..comment.find("-->")?];
+    if !comment.contains(SINGLEFILE_MARKER) {
+        return None;
+    }
+    comment
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("url:"))
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+}
+
+/// Remove `data:` URIs longer than `max_bytes` from attribute values and
+/// CSS `url()`s, leaving the attribute or `url()` empty.
+pub(crate) fn strip_large(html: &str, max_bytes: usize) -> Cow<'_, str> {
+    let mut out = String::new();
+    let mut copied = 0;
+    let mut search = 0;
+
+    while let Some(found) = html[search..].find("data:") {
+        let start = search + found;
+        search = start + "data:".len();
+        let Some(opener) = html[..start].chars().next_back() else {
+            continue;
+        };
+        // Where the URI ends depends on what it started after
+        let rest = &html[start..];
+        let len = match opener {
+            '"' | '\'' => rest.find(opener),
+            '(' => rest.find([')', '"', '\'']),
+            ' ' | '\t' | '\n' | '=' | ',' => rest.find([' ', '\t', '\n', '"', '\'', '>']),
+            _ => continue,
+        }
+        .unwrap_or(rest.len());
+
+        if len > max_bytes {
+            out.push_str(&html[copied..start]);
+            copied = start + len;
+            search = copied;
+        }
+    }
+
+    if copied == 0 {
+        return Cow::Borrowed(html);
+    }
+    out.push_str(&html[copied..]);
+    Cow::Owned(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_large_data_uris() {
+        let big = format!("data:image/png;base64,{}", "A".repeat(100));
+        let html = format!(
+            r#"<img src="{big}"><img src='data:image/gif;base64,R0'><div style="background:url({big})">"#
+        );
+        assert_eq!(
+            strip_large(&html, 50),
+            r#"<img src=""><img src='data:image/gif;base64,R0'><div style="background:url()">"#
+        );
+        assert!(matches!(
+            strip_large("<p>no data: here</p>", 5),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn test_singlefile_url() {
+        let html = "<!DOCTYPE html> <html><!--\n Page saved with SingleFile \n url: https://example.com/post \n saved date: Mon Jan 01 2024\n--><head>";
+        assert_eq!(singlefile_url(html), Some("https://example.com/post"));
+        assert_eq!(singlefile_url("<!-- url: https://example.com -->"), None);
+    }
+}
@@ -0,0 +1,221 @@
+//! The JavaScript engine running Readability.js.
+//!
+//! Everything engine-specific about setting up and driving QuickJS lives
+//! here: runtime creation, loading the scripts, and interrupting runaway
+//! extractions. [`Readability`](crate::Readability) only talks to the loaded
+//! scripts through [`Engine::run`].
+
+use rquickjs::{Context, Ctx, Runtime, qjs};
+use std::cell::Cell;
+use std::ffi::CStr;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crate::readability::{JsResultExt, ReadabilityError, Result};
+
+/// Scripts evaluated into every context, in order, as prepared by the build
+/// script: bytecode or source, deflated with the `compress` feature.
+const SCRIPTS: [(&str, &[u8]); 3] = [
+    (
+        "Readability",
+        include_bytes!(concat!(env!("OUT_DIR"), "/Readability.bin")),
+    ),
+    (
+        "Readability-readerable",
+        include_bytes!(concat!(env!("OUT_DIR"), "/Readability-readerable.bin")),
+    ),
+    (
+        "bundle",
+        include_bytes!(concat!(env!("OUT_DIR"), "/bundle.bin")),
+    ),
+];
+
+/// Which algorithm and engine produced an extraction, see
+/// [`Readability::engine_info`](crate::Readability::engine_info).
+///
+/// Stored next to extracted articles, it tells which ones to re-extract
+/// after an upgrade changes the results.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct EngineInfo {
+    /// Release of the embedded Readability.js, such as `0.6.0`
+    pub readability_js_version: String,
+    /// Hash of the embedded scripts, Readability.js and this crate's glue
+    ///
+    /// Changes whenever the JavaScript does, even within one release of
+    /// Readability.js.
+    pub bundle_hash: String,
+    /// Version of the QuickJS engine running the scripts
+    pub quickjs_version: String,
+}
+
+impl EngineInfo {
+    pub(crate) fn current() -> Self {
+        // SAFETY: JS_GetVersion returns a pointer to a static C string
+        let quickjs_version = unsafe { CStr::from_ptr(qjs::JS_GetVersion()) };
+        Self {
+            readability_js_version: crate::READABILITY_JS_VERSION.to_string(),
+            bundle_hash: env!("READABILITY_BUNDLE_HASH").to_string(),
+            quickjs_version: quickjs_version.to_string_lossy().into_owned(),
+        }
+    }
+}
+
+/// Runtime settings applied when an engine starts.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct EngineConfig {
+    pub(crate) memory_limit: Option<usize>,
+    pub(crate) max_stack_size: Option<usize>,
+    pub(crate) gc_threshold: Option<usize>,
+    /// Extra `(name, source)` scripts evaluated after the bundle.
+    pub(crate) scripts: Vec<(String, String)>,
+    /// Replace the runtime after this many runs.
+    pub(crate) recycle_after: Option<usize>,
+    /// Replace the runtime once the heap grows past this many bytes.
+    pub(crate) recycle_above: Option<usize>,
+}
+
+pub(crate) struct Engine {
+    runtime: Runtime,
+    context: Context,
+    deadline: Rc<Cell<Option<Instant>>>,
+    config: EngineConfig,
+    runs: Cell<usize>,
+}
+
+impl Engine {
+    /// Start a runtime and load Readability.js, the bundle and any extra
+    /// scripts into it.
+    pub(crate) fn new(config: EngineConfig) -> Result<Self> {
+        let runtime = Runtime::new().js_context("Failed to create runtime")?;
+        if let Some(limit) = config.memory_limit {
+            runtime.set_memory_limit(limit);
+        }
+        if let Some(limit) = config.max_stack_size {
+            runtime.set_max_stack_size(limit);
+        }
+        if let Some(threshold) = config.gc_threshold {
+            runtime.set_gc_threshold(threshold);
+        }
+        let deadline: Rc<Cell<Option<Instant>>> = Rc::default();
+        let handler_deadline = deadline.clone();
+        runtime.set_interrupt_handler(Some(Box::new(move || {
+            handler_deadline
+                .get()
+                .is_some_and(|deadline| Instant::now() >= deadline)
+        })));
+        let context = Context::full(&runtime).js_context("Failed to create context")?;
+
+        context.with(|ctx| {
+            for (name, code) in SCRIPTS {
+                #[cfg(feature = "compress")]
+                let code = &miniz_oxide::inflate::decompress_to_vec(code).map_err(|e| {
+                    ReadabilityError::JsEvaluation {
+                        context: format!("Failed to decompress {}: {}", name, e),
+                        source: rquickjs::Error::Unknown,
+                    }
+                })?;
+                load(&ctx, code).js_context(&format!("Failed to load {}", name))?;
+            }
+            for (name, source) in &config.scripts {
+                ctx.eval::<(), _>(source.as_str())
+                    .js_context(&format!("Failed to load {}", name))?;
+            }
+            Ok::<_, ReadabilityError>(())
+        })?;
+
+        Ok(Self {
+            runtime,
+            context,
+            deadline,
+            config,
+            runs: Cell::new(0),
+        })
+    }
+
+    /// Collect garbage now.
+    pub(crate) fn run_gc(&self) {
+        self.runtime.run_gc();
+    }
+
+    /// Bytes currently allocated by the JavaScript heap.
+    pub(crate) fn memory_usage(&self) -> usize {
+        self.runtime.memory_usage().memory_used_size.max(0) as usize
+    }
+
+    /// Override the heap limit; `None` restores the configured one.
+    pub(crate) fn override_memory_limit(&self, bytes: Option<usize>) {
+        let limit = bytes.or(self.config.memory_limit);
+        // QuickJS treats 0 as unlimited
+        self.runtime.set_memory_limit(limit.unwrap_or(0));
+    }
+
+    /// Whether the recycling policy calls for a fresh runtime.
+    pub(crate) fn needs_recycle(&self) -> bool {
+        self.config
+            .recycle_after
+            .is_some_and(|runs| self.runs.get() >= runs)
+            || self
+                .config
+                .recycle_above
+                .is_some_and(|bytes| self.memory_usage() > bytes)
+    }
+
+    /// Start a replacement engine with the same configuration.
+    pub(crate) fn recycled(&self) -> Result<Self> {
+        Self::new(self.config.clone())
+    }
+
+    /// Run `f` inside the JavaScript context, interrupting it after `timeout`.
+    pub(crate) fn run<T>(
+        &self,
+        timeout: Option<Duration>,
+        f: impl for<'js> FnOnce(Ctx<'js>) -> Result<T>,
+    ) -> Result<T> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        self.deadline.set(deadline);
+        let result = self.context.with(f);
+        self.deadline.set(None);
+        self.runs.set(self.runs.get() + 1);
+
+        match result {
+            Err(ReadabilityError::JsEvaluation { .. })
+                if deadline.is_some_and(|deadline| Instant::now() >= deadline) =>
+            {
+                Err(ReadabilityError::Timeout)
+            }
+            result => result,
+        }
+    }
+}
+
+#[cfg(not(readability_bytecode))]
+fn load(ctx: &Ctx<'_>, source: &[u8]) -> rquickjs::Result<()> {
+    ctx.eval(source)
+}
+
+/// Run a script compiled by the build script.
+#[cfg(readability_bytecode)]
+fn load(ctx: &Ctx<'_>, bytecode: &[u8]) -> rquickjs::Result<()> {
+    let raw = ctx.as_raw().as_ptr();
+    // SAFETY: the bytecode was written by the same QuickJS version for this
+    // target, and every value returned here is either freed or an exception.
+    unsafe {
+        let function = qjs::JS_ReadObject(
+            raw,
+            bytecode.as_ptr(),
+            bytecode.len() as _,
+            qjs::JS_READ_OBJ_BYTECODE as _,
+        );
+        if qjs::JS_IsException(function) {
+            return Err(rquickjs::Error::Exception);
+        }
+        let result = qjs::JS_EvalFunction(raw, function);
+        if qjs::JS_IsException(result) {
+            return Err(rquickjs::Error::Exception);
+        }
+        qjs::JS_FreeValue(raw, result);
+    }
+    Ok(())
+}
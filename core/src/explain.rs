@@ -0,0 +1,140 @@
+//! Tracing how Readability scored the candidates for the article.
+
+use rquickjs::{Array, Object, Value};
+
+use crate::readability::{
+    Article, JsResultExt, Readability, ReadabilityOptions, Result, optional_f64, optional_string,
+    optional_u32,
+};
+
+/// An article together with how Readability chose it, see
+/// [`Readability::parse_explain`].
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Explanation {
+    /// The extracted article
+    pub article: Article,
+    /// Every element Readability scored, highest score first
+    pub candidates: Vec<CandidateScore>,
+}
+
+/// An element Readability scored as a possible container of the article.
+///
+/// Each paragraph adds points to its parent and, halved, to its grandparent,
+/// for its length and commas. The candidate with the highest
+/// [`score`](Self::score) is the top candidate; siblings that score close to
+/// it are pulled into the article as well.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct CandidateScore {
+    /// Lowercase tag name, such as `div`
+    pub tag: String,
+    /// Value of the `class` attribute
+    pub class: Option<String>,
+    /// Value of the `id` attribute
+    pub id: Option<String>,
+    /// Score from the tag, the class weight and the paragraphs inside, before
+    /// the link density penalty
+    pub content_score: f64,
+    /// Points for `class` and `id` matching Readability's positive or
+    /// negative patterns, 25 each way
+    pub class_weight: f64,
+    /// Share of its text inside links, from 0 to 1
+    pub link_density: f64,
+    /// `content_score` scaled by `1 - link_density`, which candidates are
+    /// ranked by
+    pub score: f64,
+    /// Start of its text, with whitespace collapsed
+    pub text_preview: String,
+    /// Which search scored it, starting at 1
+    ///
+    /// Readability searches the page again with looser rules when a search
+    /// finds too little text.
+    pub pass: u32,
+}
+
+impl Readability {
+    /// Extract the article and trace how its container was chosen.
+    ///
+    /// Meant for tuning options such as
+    /// [`ReadabilityOptions::link_density_modifier`] or
+    /// [`ReadabilityOptions::classes_to_preserve`] for a site, by showing
+    /// which elements competed to be the article and why they scored as they
+    /// did. Tracing makes extraction slower, and the result is never cached.
+    ///
+    /// # Arguments
+    ///
+    /// * `html` - The HTML content to extract from
+    /// * `base_url` - Optional URL for link resolution
+    /// * `options` - Custom parsing options
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use readability_js::Readability;
+    ///
+    /// # let html = "<html><body><p>Content</p></body></html>";
+    /// let reader = Readability::new()?;
+    /// let explanation = reader.parse_explain(html, None, None)?;
+    /// for candidate in explanation.candidates.iter().take(5) {
+    ///     println!("{:>8.1} <{}>", candidate.score, candidate.tag);
+    /// }
+    /// # Ok::<(), readability_js::ReadabilityError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// The same as [`parse_with_options`](Self::parse_with_options).
+    pub fn parse_explain(
+        &self,
+        html: &str,
+        base_url: Option<&str>,
+        options: Option<ReadabilityOptions>,
+    ) -> Result<Explanation> {
+        let mut candidates = Vec::new();
+        let article = self.extract_uncached(
+            html,
+            base_url,
+            self.with_default_options(options),
+            Some(&mut candidates),
+        )?;
+        Ok(Explanation {
+            article,
+            candidates,
+        })
+    }
+}
+
+/// Read the candidates `extract` traced when called with `explain` set.
+pub(crate) fn candidates(result: &Value) -> Result<Vec<CandidateScore>> {
+    let Some(list) = result
+        .as_object()
+        .map(|obj| obj.get::<_, Option<Array>>("candidates"))
+        .transpose()
+        .js_context("failed to get candidates")?
+        .flatten()
+    else {
+        return Ok(Vec::new());
+    };
+    list.iter::<Object>()
+        .map(|candidate| {
+            let candidate = candidate.js_context("failed to get candidate")?;
+            let number = |key: &str, name: &str| -> Result<f64> {
+                Ok(optional_f64(&candidate, key, name)?.unwrap_or_default())
+            };
+            Ok(CandidateScore {
+                tag: optional_string(&candidate, "tag", "candidate tag")?.unwrap_or_default(),
+                class: optional_string(&candidate, "className", "candidate class")?,
+                id: optional_string(&candidate, "id", "candidate id")?,
+                content_score: number("contentScore", "candidate content_score")?,
+                class_weight: number("classWeight", "candidate class_weight")?,
+                link_density: number("linkDensity", "candidate link_density")?,
+                score: number("score", "candidate score")?,
+                text_preview: optional_string(&candidate, "textPreview", "candidate text_preview")?
+                    .unwrap_or_default(),
+                pass: optional_u32(&candidate, "pass", "candidate pass")?.unwrap_or(1),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,422 @@
+//! Fetching pages over HTTP before extraction.
+
+#[cfg(feature = "fetch")]
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[cfg(feature = "fetch")]
+use ureq::ResponseExt;
+
+use crate::fetcher::FetchError;
+#[cfg(feature = "fetch")]
+use crate::fetcher::{FetchResponse, Fetcher};
+#[cfg(feature = "fetch")]
+use crate::readability::Readability;
+#[cfg(feature = "async-fetch")]
+use crate::readability::ReadabilityError;
+use crate::readability::{Article, ReadabilityOptions, Result};
+
+const ACCEPT: &str = "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8";
+const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/121.0.0.0 Safari/537.36";
+
+/// How `Readability::fetch_and_parse` and `fetch_and_parse_async` request
+/// a page.
+///
+/// The defaults send browser-like headers, since many sites serve a stripped
+/// or blocked page to anything that doesn't look like a browser.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::time::Duration;
+/// use readability_js::FetchOptions;
+///
+/// let options = FetchOptions::new()
+///     .timeout(Duration::from_secs(10))
+///     .header("Accept-Language", "de");
+/// ```
+#[derive(Debug, Clone)]
+pub struct FetchOptions {
+    pub user_agent: String,
+    pub timeout: Duration,
+    pub max_redirects: u32,
+    pub max_body_bytes: u64,
+    /// Extra request headers, sent after the defaults
+    pub headers: Vec<(String, String)>,
+    /// Options for extracting the fetched page
+    pub options: Option<ReadabilityOptions>,
+    /// Client shared by every request made with these options and their clones
+    #[cfg(feature = "fetch")]
+    agent: AgentCache,
+}
+
+/// A ureq agent, kept so that pages and images fetched with the same options
+/// reuse connections instead of paying for a new TLS handshake each.
+///
+/// The fields it was built from are public, so it remembers them and is
+/// rebuilt when they change.
+#[cfg(feature = "fetch")]
+#[derive(Clone, Default)]
+struct AgentCache(Arc<Mutex<Option<(AgentSettings, ureq::Agent)>>>);
+
+/// Timeout, redirect limit and user agent.
+#[cfg(feature = "fetch")]
+type AgentSettings = (Duration, u32, String);
+
+#[cfg(feature = "fetch")]
+impl std::fmt::Debug for AgentCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AgentCache").finish_non_exhaustive()
+    }
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self {
+            user_agent: USER_AGENT.to_string(),
+            timeout: Duration::from_secs(30),
+            max_redirects: 10,
+            max_body_bytes: 10 * 1024 * 1024,
+            headers: Vec::new(),
+            options: None,
+            #[cfg(feature = "fetch")]
+            agent: AgentCache::default(),
+        }
+    }
+}
+
+impl FetchOptions {
+    /// Creates options with the default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Identify as something other than a desktop browser.
+    ///
+    /// # Arguments
+    /// * `user_agent` - Value of the `User-Agent` header (default: a recent Chrome)
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Give up on the whole request, redirects and body included, after this long.
+    ///
+    /// # Arguments
+    /// * `timeout` - Maximum duration (default: 30 seconds)
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Follow at most this many redirects; 0 disables following them.
+    ///
+    /// # Arguments
+    /// * `count` - Maximum number of redirects (default: 10)
+    pub fn max_redirects(mut self, count: u32) -> Self {
+        self.max_redirects = count;
+        self
+    }
+
+    /// Refuse responses larger than this.
+    ///
+    /// Exceeding it returns [`ReadabilityError::Fetch`](crate::ReadabilityError::Fetch).
+    ///
+    /// # Arguments
+    /// * `bytes` - Maximum body size in bytes (default: 10 MiB)
+    pub fn max_body_bytes(mut self, bytes: u64) -> Self {
+        self.max_body_bytes = bytes;
+        self
+    }
+
+    /// Send an extra request header, such as `Cookie` or `Accept-Language`.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Extract the fetched page with these options.
+    pub fn options(mut self, options: ReadabilityOptions) -> Self {
+        self.options = Some(options);
+        self
+    }
+}
+
+#[cfg(feature = "fetch")]
+impl Readability {
+    /// Fetch a page over HTTP and extract its article.
+    ///
+    /// Redirects are followed, and links in the article are resolved against
+    /// the URL the page was finally served from. The body is decoded using
+    /// the `Content-Type` charset, a byte order mark or a `<meta charset>`,
+    /// like [`parse_with_content_type`](Self::parse_with_content_type).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use readability_js::{FetchOptions, Readability};
+    ///
+    /// let reader = Readability::new()?;
+    /// let article = reader.fetch_and_parse("https://example.com/post", FetchOptions::new())?;
+    /// println!("{}", article.title);
+    /// # Ok::<(), readability_js::ReadabilityError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * The URL is invalid, the request fails, the server answers with an
+    ///   error status or the body is too large ([`ReadabilityError::Fetch`](crate::ReadabilityError::Fetch),
+    ///   with a [`FetchError`] telling which)
+    /// * Extraction fails, as for [`parse_with_options`](Self::parse_with_options)
+    pub fn fetch_and_parse(&self, url: &str, options: FetchOptions) -> Result<Article> {
+        self.fetch_with(url, &options, options.options.clone())
+    }
+}
+
+#[cfg(feature = "fetch")]
+impl FetchOptions {
+    /// The agent for the current settings, built on first use.
+    fn agent(&self) -> ureq::Agent {
+        let settings = (self.timeout, self.max_redirects, self.user_agent.clone());
+        let mut cached = self.agent.0.lock().unwrap_or_else(|e| e.into_inner());
+        match &*cached {
+            Some((built_for, agent)) if *built_for == settings => agent.clone(),
+            _ => {
+                let agent: ureq::Agent = ureq::Agent::config_builder()
+                    .timeout_global(Some(self.timeout))
+                    .max_redirects(self.max_redirects)
+                    .user_agent(self.user_agent.as_str())
+                    .build()
+                    .into();
+                *cached = Some((settings, agent.clone()));
+                agent
+            }
+        }
+    }
+}
+
+/// Fetches with ureq, sending the configured headers.
+#[cfg(feature = "fetch")]
+impl Fetcher for FetchOptions {
+    fn fetch(
+        &self,
+        url: &str,
+    ) -> std::result::Result<FetchResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let agent = self.agent();
+        let mut request = agent.get(url).header("Accept", ACCEPT);
+        for (name, value) in &self.headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+
+        let mut response = request.call().map_err(|e| ureq_error(url, e))?;
+        let final_url = response.get_uri().to_string();
+        let content_type = response
+            .headers()
+            .get("Content-Type")
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let body = response
+            .body_mut()
+            .with_config()
+            .limit(self.max_body_bytes)
+            .read_to_vec()
+            .map_err(|e| match e {
+                ureq::Error::BodyExceedsLimit(limit) => FetchError::TooLarge {
+                    url: url.to_string(),
+                    limit,
+                },
+                ureq::Error::Timeout(_) => FetchError::Timeout {
+                    url: url.to_string(),
+                },
+                e => FetchError::Body {
+                    url: url.to_string(),
+                    reason: e.to_string(),
+                },
+            })?;
+        Ok(FetchResponse {
+            body,
+            content_type,
+            url: final_url,
+        })
+    }
+}
+
+/// Classify a failed ureq request.
+#[cfg(feature = "fetch")]
+fn ureq_error(url: &str, error: ureq::Error) -> FetchError {
+    let url = url.to_string();
+    match error {
+        ureq::Error::StatusCode(status) => FetchError::Status { url, status },
+        ureq::Error::BadUri(reason) => FetchError::InvalidUrl { url, reason },
+        ureq::Error::HostNotFound => FetchError::Dns {
+            url,
+            reason: "host not found".into(),
+        },
+        ureq::Error::Timeout(_) => FetchError::Timeout { url },
+        ureq::Error::Io(e) if e.kind() == std::io::ErrorKind::TimedOut => {
+            FetchError::Timeout { url }
+        }
+        ureq::Error::ConnectionFailed | ureq::Error::Io(_) => FetchError::Connect {
+            url,
+            reason: error.to_string(),
+        },
+        ureq::Error::Tls(_) | ureq::Error::Pem(_) | ureq::Error::Rustls(_) => FetchError::Tls {
+            url,
+            reason: error.to_string(),
+        },
+        ureq::Error::BodyExceedsLimit(limit) => FetchError::TooLarge { url, limit },
+        error => FetchError::Other {
+            url,
+            source: Box::new(error),
+        },
+    }
+}
+
+/// Fetch a page over HTTP and extract its article, without blocking the
+/// async runtime.
+///
+/// Like `Readability::fetch_and_parse`, but the download runs on reqwest
+/// and the extraction on Tokio's blocking pool, with that thread's
+/// [`Readability::thread_local`](crate::Readability::thread_local) parser.
+/// The returned future is `Send`, so it can be spawned or awaited in any
+/// handler. Compressed responses (gzip, deflate, brotli) are decoded.
+///
+/// Must be called within a Tokio runtime.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use readability_js::{FetchOptions, fetch_and_parse_async};
+///
+/// # async fn run() -> Result<(), readability_js::ReadabilityError> {
+/// let article = fetch_and_parse_async("https://example.com/post", FetchOptions::new()).await?;
+/// println!("{}", article.title);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// * The URL is invalid, the request fails, the server answers with an
+///   error status or the body is too large ([`ReadabilityError::Fetch`](crate::ReadabilityError::Fetch),
+///   with a [`FetchError`] telling which)
+/// * Extraction fails, as for [`Readability::parse_with_options`](crate::Readability::parse_with_options)
+#[cfg(feature = "async-fetch")]
+pub async fn fetch_and_parse_async(url: &str, options: FetchOptions) -> Result<Article> {
+    let fail = |e: reqwest::Error| ReadabilityError::Fetch(reqwest_error(url, e));
+
+    let client = reqwest::Client::builder()
+        .user_agent(options.user_agent.as_str())
+        .timeout(options.timeout)
+        .redirect(reqwest::redirect::Policy::limited(
+            options.max_redirects as usize,
+        ))
+        .build()
+        .map_err(fail)?;
+    let mut request = client.get(url).header("Accept", ACCEPT);
+    for (name, value) in &options.headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+
+    let mut response = request
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(fail)?;
+    let final_url = response.url().to_string();
+    let content_type = response
+        .headers()
+        .get("Content-Type")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let too_large = || {
+        ReadabilityError::Fetch(FetchError::TooLarge {
+            url: url.to_string(),
+            limit: options.max_body_bytes,
+        })
+    };
+    if response
+        .content_length()
+        .is_some_and(|length| length > options.max_body_bytes)
+    {
+        return Err(too_large());
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(fail)? {
+        if (body.len() + chunk.len()) as u64 > options.max_body_bytes {
+            return Err(too_large());
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    let extract_options = options.options;
+    tokio::task::spawn_blocking(move || {
+        let html = crate::content_type::decode(&body, content_type.as_deref());
+        crate::Readability::thread_local()?.extract(&html, Some(&final_url), extract_options)
+    })
+    .await
+    .map_err(|e| ReadabilityError::Internal(format!("extraction task failed: {}", e)))?
+}
+
+/// Classify a failed reqwest request.
+#[cfg(feature = "async-fetch")]
+fn reqwest_error(url: &str, error: reqwest::Error) -> FetchError {
+    let url = url.to_string();
+    if error.is_timeout() {
+        return FetchError::Timeout { url };
+    }
+    if let Some(status) = error.status() {
+        return FetchError::Status {
+            url,
+            status: status.as_u16(),
+        };
+    }
+    // The causes of connection errors are hyper and TLS internals, told
+    // apart by their messages
+    let mut reason = error.to_string();
+    let mut source = std::error::Error::source(&error);
+    while let Some(cause) = source {
+        reason = format!("{}: {}", reason, cause);
+        source = cause.source();
+    }
+    let lowercase = reason.to_lowercase();
+    if error.is_builder() {
+        FetchError::InvalidUrl { url, reason }
+    } else if error.is_connect() && lowercase.contains("dns error") {
+        FetchError::Dns { url, reason }
+    } else if error.is_connect()
+        && ["certificate", "tls", "handshake"]
+            .iter()
+            .any(|hint| lowercase.contains(hint))
+    {
+        FetchError::Tls { url, reason }
+    } else if error.is_connect() {
+        FetchError::Connect { url, reason }
+    } else if error.is_body() || error.is_decode() {
+        FetchError::Body { url, reason }
+    } else {
+        FetchError::Other {
+            url,
+            source: Box::new(error),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "fetch"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_agent_is_reused_until_settings_change() {
+        let options = FetchOptions::new();
+        let agent = options.agent();
+        assert!(std::ptr::eq(agent.config(), options.agent().config()));
+        assert!(std::ptr::eq(
+            agent.config(),
+            options.clone().agent().config()
+        ));
+
+        let options = options.timeout(Duration::from_secs(1));
+        assert!(!std::ptr::eq(agent.config(), options.agent().config()));
+    }
+}
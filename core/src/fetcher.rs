@@ -0,0 +1,477 @@
+//! Extraction features that need to download more than the page itself.
+
+use std::collections::HashSet;
+
+use crate::readability::{Article, Readability, ReadabilityError, ReadabilityOptions, Result};
+
+/// Most pages [`Readability::fetch_pages_with`] follows when not told otherwise.
+const DEFAULT_MAX_PAGES: usize = 10;
+
+/// A response from a [`Fetcher`].
+#[derive(Debug, Clone, Default)]
+pub struct FetchResponse {
+    pub body: Vec<u8>,
+    /// The `Content-Type` header, used to decode the body
+    pub content_type: Option<String>,
+    /// URL the body was served from, after any redirects
+    pub url: String,
+}
+
+/// Why downloading a page failed, see [`ReadabilityError::Fetch`].
+///
+/// The built-in fetchers tell apart the failures worth handling
+/// differently, such as retrying a timeout but not a 404. Every variant
+/// carries the URL that was requested.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum FetchError {
+    /// The URL could not be parsed or uses an unsupported scheme
+    #[error("{url}: invalid URL: {reason}")]
+    InvalidUrl { url: String, reason: String },
+
+    /// The host name could not be resolved
+    #[error("{url}: could not resolve host: {reason}")]
+    Dns { url: String, reason: String },
+
+    /// The connection could not be established or broke off
+    #[error("{url}: connection failed: {reason}")]
+    Connect { url: String, reason: String },
+
+    /// The TLS handshake failed, typically over an untrusted certificate
+    #[error("{url}: TLS handshake failed: {reason}")]
+    Tls { url: String, reason: String },
+
+    /// The server did not answer within the timeout
+    #[error("{url}: timed out")]
+    Timeout { url: String },
+
+    /// The server answered with an error status
+    #[error("{url}: server answered with status {status}")]
+    Status { url: String, status: u16 },
+
+    /// The body was larger than allowed
+    #[error("{url}: body exceeds {limit} bytes")]
+    TooLarge { url: String, limit: u64 },
+
+    /// The body could not be read or decompressed
+    #[error("{url}: could not read body: {reason}")]
+    Body { url: String, reason: String },
+
+    /// Any other failure, including errors of custom [`Fetcher`]s
+    #[error("{url}")]
+    Other {
+        url: String,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+impl FetchError {
+    /// The URL that was requested.
+    pub fn url(&self) -> &str {
+        match self {
+            FetchError::InvalidUrl { url, .. }
+            | FetchError::Dns { url, .. }
+            | FetchError::Connect { url, .. }
+            | FetchError::Tls { url, .. }
+            | FetchError::Timeout { url }
+            | FetchError::Status { url, .. }
+            | FetchError::TooLarge { url, .. }
+            | FetchError::Body { url, .. }
+            | FetchError::Other { url, .. } => url,
+        }
+    }
+}
+
+/// The HTTP stack used by the methods that download pages or assets.
+///
+/// Implement it over your own client to bring authentication, caching,
+/// proxies or rate limiting; the crate only decides what to fetch and what
+/// to do with it. With the `fetch` feature, [`FetchOptions`](crate::FetchOptions)
+/// implements it with ureq.
+///
+/// # Examples
+///
+/// ```rust
+/// use readability_js::{FetchResponse, Fetcher};
+///
+/// /// Serves pages from memory, e.g. in tests.
+/// struct Canned(Vec<(&'static str, &'static str)>);
+///
+/// impl Fetcher for Canned {
+///     fn fetch(&self, url: &str) -> Result<FetchResponse, Box<dyn std::error::Error + Send + Sync>> {
+///         let (_, html) = self.0.iter().find(|(u, _)| *u == url).ok_or("not found")?;
+///         Ok(FetchResponse {
+///             body: html.as_bytes().to_vec(),
+///             content_type: Some("text/html; charset=utf-8".into()),
+///             url: url.to_string(),
+///         })
+///     }
+/// }
+/// ```
+pub trait Fetcher {
+    /// Download `url`, following redirects. Error statuses should be
+    /// returned as errors rather than as a response.
+    ///
+    /// Return a [`FetchError`] to have it passed on as it is; any other
+    /// error becomes [`FetchError::Other`].
+    fn fetch(
+        &self,
+        url: &str,
+    ) -> std::result::Result<FetchResponse, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// Fetch `url` with `fetcher`, mapping failures to [`ReadabilityError::Fetch`].
+fn fetch(fetcher: &dyn Fetcher, url: &str) -> Result<FetchResponse> {
+    fetcher.fetch(url).map_err(|e| {
+        let error = match e.downcast::<FetchError>() {
+            Ok(error) => *error,
+            Err(source) => FetchError::Other {
+                url: url.to_string(),
+                source,
+            },
+        };
+        ReadabilityError::Fetch(error)
+    })
+}
+
+impl Readability {
+    /// Download a page with `fetcher` and extract its article.
+    ///
+    /// The body is decoded like [`parse_with_content_type`](Self::parse_with_content_type),
+    /// and links are resolved against the URL the page was served from.
+    ///
+    /// # Errors
+    ///
+    /// * The fetcher fails ([`ReadabilityError::Fetch`])
+    /// * Extraction fails, as for [`parse_with_options`](Self::parse_with_options)
+    pub fn fetch_with(
+        &self,
+        url: &str,
+        fetcher: &dyn Fetcher,
+        options: Option<ReadabilityOptions>,
+    ) -> Result<Article> {
+        let response = fetch(fetcher, url)?;
+        let html = crate::content_type::decode(&response.body, response.content_type.as_deref());
+        self.extract(&html, Some(&response.url), options)
+    }
+
+    /// Like [`fetch_with`](Self::fetch_with), but extract the page's AMP
+    /// version instead when it advertises one.
+    ///
+    /// Falls back to the page itself when the AMP version can't be fetched
+    /// or extracted, so this never does worse than `fetch_with`.
+    pub fn fetch_amp_with(
+        &self,
+        url: &str,
+        fetcher: &dyn Fetcher,
+        options: Option<ReadabilityOptions>,
+    ) -> Result<Article> {
+        let response = fetch(fetcher, url)?;
+        let html = crate::content_type::decode(&response.body, response.content_type.as_deref());
+        if let Some(amp_url) = self.find_amp_url(&html, Some(&response.url))?
+            && amp_url != response.url
+            && let Ok(article) = self.fetch_with(&amp_url, fetcher, options.clone())
+        {
+            return Ok(article);
+        }
+        self.extract(&html, Some(&response.url), options)
+    }
+
+    /// Download an article split over several pages and stitch it together.
+    ///
+    /// Follows `rel="next"` links from page to page, up to `max_pages` pages
+    /// (default: 10), and stops early at a page that was already seen or
+    /// that fails. The first page's metadata is kept; the content, text and
+    /// length cover all pages.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use readability_js::{FetchResponse, Fetcher};
+    /// # struct Client;
+    /// # impl Fetcher for Client {
+    /// #     fn fetch(&self, _: &str) -> Result<FetchResponse, Box<dyn std::error::Error + Send + Sync>> {
+    /// #         Err("offline".into())
+    /// #     }
+    /// # }
+    /// use readability_js::Readability;
+    ///
+    /// let reader = Readability::new()?;
+    /// let article = reader.fetch_pages_with("https://example.com/long-read", &Client, Some(5), None)?;
+    /// # Ok::<(), readability_js::ReadabilityError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails only if the first page can't be fetched or extracted.
+    pub fn fetch_pages_with(
+        &self,
+        url: &str,
+        fetcher: &dyn Fetcher,
+        max_pages: Option<usize>,
+        options: Option<ReadabilityOptions>,
+    ) -> Result<Article> {
+        let max_pages = max_pages.unwrap_or(DEFAULT_MAX_PAGES).max(1);
+        let mut seen = HashSet::from([url.to_string()]);
+
+        let response = fetch(fetcher, url)?;
+        seen.insert(response.url.clone());
+        let mut html =
+            crate::content_type::decode(&response.body, response.content_type.as_deref());
+        let mut page_url = response.url;
+        let mut article = self.extract(&html, Some(&page_url), options.clone())?;
+
+        for _ in 1..max_pages {
+            let next = self.find_link(
+                &html,
+                Some(&page_url),
+                r#"link[rel~="next"][href], a[rel~="next"][href]"#,
+            );
+            let Ok(Some(next)) = next else {
+                break;
+            };
+            if !seen.insert(next.clone()) {
+                break;
+            }
+            let Ok(response) = fetch(fetcher, &next) else {
+                break;
+            };
+            if !seen.insert(response.url.clone()) && response.url != next {
+                break;
+            }
+            html = crate::content_type::decode(&response.body, response.content_type.as_deref());
+            page_url = response.url;
+            let Ok(page) = self.extract(&html, Some(&page_url), options.clone()) else {
+                break;
+            };
+            article.content.push('\n');
+            article.content.push_str(&page.content);
+            article.text_content.push_str("\n\n");
+            article.text_content.push_str(&page.text_content);
+            article.length = article.length.saturating_add(page.length);
+        }
+        Ok(article)
+    }
+}
+
+impl Article {
+    /// Download the content's images with `fetcher` and inline them as
+    /// `data:` URIs, making the article self-contained for offline reading.
+    ///
+    /// Relative sources are resolved against `base_url`. Images that fail to
+    /// download keep their original source; `srcset` is dropped from those
+    /// that were inlined so browsers don't fetch them anyway. Returns the
+    /// number of images inlined.
+    pub fn embed_images(&mut self, base_url: Option<&str>, fetcher: &dyn Fetcher) -> usize {
+        let base = base_url.and_then(|url| url::Url::parse(url).ok());
+        let mut embedded = 0;
+        let mut out = String::with_capacity(self.content.len());
+        let mut rest = self.content.as_str();
+
+        while let Some(start) = find_img(rest) {
+            out.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let end = rest.find('>').map_or(rest.len(), |end| end + 1);
+            let tag = &rest[..end];
+            rest = &rest[end..];
+
+            let inlined = attribute(tag, "src").and_then(|(src, range)| {
+                if src.starts_with("data:") {
+                    return None;
+                }
+                let url = match &base {
+                    Some(base) => base.join(src.trim()).ok()?,
+                    None => url::Url::parse(src.trim()).ok()?,
+                };
+                let response = fetcher.fetch(url.as_str()).ok()?;
+                let media_type = response
+                    .content_type
+                    .as_deref()
+                    .and_then(|t| t.split(';').next())
+                    .map(str::trim)
+                    .filter(|t| t.starts_with("image/"))?;
+                let uri = format!("data:{};base64,{}", media_type, base64(&response.body));
+                Some(format!(
+                    "{}{}{}",
+                    &tag[..range.start],
+                    uri,
+                    &tag[range.end..]
+                ))
+            });
+            match inlined {
+                Some(tag) => {
+                    out.push_str(&without_attribute(&tag, "srcset"));
+                    embedded += 1;
+                }
+                None => out.push_str(tag),
+            }
+        }
+        out.push_str(rest);
+        self.content = out;
+        embedded
+    }
+}
+
+/// Offset of the next `<img` start tag in `html`.
+fn find_img(html: &str) -> Option<usize> {
+    let mut from = 0;
+    while let Some(i) = html[from..].find("<img") {
+        let at = from + i;
+        let next = html[at + 4..].chars().next();
+        if next.is_some_and(|c| c.is_whitespace() || c == '>' || c == '/') {
+            return Some(at);
+        }
+        from = at + 4;
+    }
+    None
+}
+
+/// Value of the quoted attribute `name` in the start tag `tag`, with the
+/// byte range of the value.
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<(&'a str, std::ops::Range<usize>)> {
+    let (start, quote) = attribute_start(tag, name)?;
+    let len = tag[start..].find(quote)?;
+    Some((&tag[start..start + len], start..start + len))
+}
+
+/// Offset just past the opening quote of attribute `name`, and the quote.
+fn attribute_start(tag: &str, name: &str) -> Option<(usize, char)> {
+    let mut from = 0;
+    while let Some(i) = tag[from..].find(name) {
+        let at = from + i;
+        from = at + name.len();
+        if !tag[..at].ends_with(char::is_whitespace) {
+            continue;
+        }
+        // Must be the whole name, followed by =
+        let Some(value) = tag[from..].trim_start().strip_prefix('=') else {
+            continue;
+        };
+        let value = value.trim_start();
+        let quote = value.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+        return Some((tag.len() - value.len() + 1, quote));
+    }
+    None
+}
+
+/// `tag` without the quoted attribute `name`.
+fn without_attribute(tag: &str, name: &str) -> String {
+    let Some((start, quote)) = attribute_start(tag, name) else {
+        return tag.to_string();
+    };
+    let Some(len) = tag[start..].find(quote) else {
+        return tag.to_string();
+    };
+    let name_start = tag[..start].rfind(name).unwrap_or(start);
+    let before = tag[..name_start].trim_end();
+    format!("{}{}", before, &tag[start + len + 1..])
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i)) as usize & 63] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serves a tiny PNG for every URL except those ending in `missing.png`.
+    struct Images;
+
+    impl Fetcher for Images {
+        fn fetch(
+            &self,
+            url: &str,
+        ) -> std::result::Result<FetchResponse, Box<dyn std::error::Error + Send + Sync>> {
+            if url.ends_with("missing.png") {
+                return Err("404".into());
+            }
+            Ok(FetchResponse {
+                body: b"Man".to_vec(),
+                content_type: Some("image/png".into()),
+                url: url.to_string(),
+            })
+        }
+    }
+
+    #[test]
+    fn test_embed_images() {
+        let mut article = Article {
+            content: r#"<p><img srcset="a.png 2x" src="/a.png" alt="a"><img src="missing.png"><img src="data:image/gif;base64,R0"></p>"#.into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            article.embed_images(Some("https://example.com/post"), &Images),
+            1
+        );
+        assert_eq!(
+            article.content,
+            r#"<p><img src="data:image/png;base64,TWFu" alt="a"><img src="missing.png"><img src="data:image/gif;base64,R0"></p>"#
+        );
+    }
+
+    #[test]
+    fn test_fetch_errors() {
+        struct Gone;
+
+        impl Fetcher for Gone {
+            fn fetch(
+                &self,
+                url: &str,
+            ) -> std::result::Result<FetchResponse, Box<dyn std::error::Error + Send + Sync>>
+            {
+                Err(Box::new(FetchError::Status {
+                    url: url.to_string(),
+                    status: 410,
+                }))
+            }
+        }
+
+        let error = fetch(&Gone, "https://example.com/a").unwrap_err();
+        assert!(matches!(
+            error,
+            ReadabilityError::Fetch(FetchError::Status { status: 410, .. })
+        ));
+        assert_eq!(
+            error.to_string(),
+            "Failed to fetch https://example.com/a: server answered with status 410"
+        );
+
+        let error = fetch(&Images, "https://example.com/missing.png").unwrap_err();
+        let ReadabilityError::Fetch(error) = error else {
+            panic!("expected a fetch error, got {:?}", error);
+        };
+        assert!(matches!(error, FetchError::Other { .. }));
+        assert_eq!(error.url(), "https://example.com/missing.png");
+        // The cause is left to the source chain, so reports show it once
+        assert_eq!(error.to_string(), "https://example.com/missing.png");
+        let source = std::error::Error::source(&error).unwrap();
+        assert_eq!(source.to_string(), "404");
+    }
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"M"), "TQ==");
+        assert_eq!(base64(b"Ma"), "TWE=");
+        assert_eq!(base64(b"Man"), "TWFu");
+    }
+}
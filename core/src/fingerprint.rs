@@ -0,0 +1,78 @@
+use crate::readability::Article;
+
+/// Number of consecutive words hashed together.
+const SHINGLE_WORDS: usize = 3;
+
+impl Article {
+    /// 64-bit SimHash of the normalized text content.
+    ///
+    /// Near-duplicate texts, such as a syndicated article with a different
+    /// footer, produce fingerprints that differ in only a few bits. Compare
+    /// them with `(a ^ b).count_ones()`: unrelated texts differ in about 32
+    /// bits, while near-duplicates of full-length articles usually differ in
+    /// only a handful. Fingerprints are stable across releases and platforms,
+    /// so they can be stored.
+    pub fn fingerprint(&self) -> u64 {
+        simhash(&self.text_content)
+    }
+}
+
+fn simhash(text: &str) -> u64 {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+
+    let mut weights = [0i64; 64];
+    let mut add = |shingle: &[String]| {
+        let hash = fnv1a(shingle.join(" ").as_bytes());
+        for (bit, weight) in weights.iter_mut().enumerate() {
+            if hash & (1 << bit) != 0 {
+                *weight += 1;
+            } else {
+                *weight -= 1;
+            }
+        }
+    };
+    if words.len() < SHINGLE_WORDS {
+        if !words.is_empty() {
+            add(&words);
+        }
+    } else {
+        words.windows(SHINGLE_WORDS).for_each(&mut add);
+    }
+
+    weights
+        .iter()
+        .enumerate()
+        .filter(|(_, weight)| **weight > 0)
+        .fold(0, |hash, (bit, _)| hash | (1 << bit))
+}
+
+/// 64-bit FNV-1a, chosen over `DefaultHasher` because its output is stable.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_near_duplicates_have_close_fingerprints() {
+        let text = "The city council voted on Tuesday to approve a new budget that \
+            expands funding for public transit, parks and libraries across all districts, \
+            while postponing a decision on the proposed stadium until next spring.";
+        let syndicated = format!("{} Reporting by the wire service.", text.to_uppercase());
+        let unrelated = "Researchers announced a new battery chemistry that charges in minutes \
+            and survives thousands of cycles without losing capacity in cold weather.";
+
+        let distance = |a: &str, b: &str| (simhash(a) ^ simhash(b)).count_ones();
+        assert_eq!(simhash(text), simhash(&format!("  {}\n", text)));
+        assert!(distance(text, &syndicated) < distance(text, unrelated));
+        assert!(distance(text, &syndicated) <= 10);
+    }
+}
@@ -0,0 +1,232 @@
+//! Regression-testing extraction against a corpus of fixtures.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::readability::{
+    Article, Direction, Readability, ReadabilityError, ReadabilityOptions, Result,
+};
+use crate::text;
+
+/// Page URL Mozilla's fixtures were generated with.
+const FIXTURE_URL: &str = "http://fakehost/test/page.html";
+
+/// Outcome of [`Readability::run_fixtures`].
+#[derive(Debug, Clone, Default)]
+pub struct FixtureReport {
+    /// Names of the fixtures that matched
+    pub passed: Vec<String>,
+    /// Every mismatch, in fixture order
+    pub failures: Vec<FixtureFailure>,
+}
+
+/// A field of one fixture that did not match.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixtureFailure {
+    /// Name of the fixture directory
+    pub fixture: String,
+    /// Field as named in the expected JSON, or `content`
+    pub field: String,
+    /// Expected value, as JSON
+    pub expected: String,
+    /// Extracted value, as JSON
+    pub actual: String,
+}
+
+impl fmt::Display for FixtureFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}\n  expected: {}\n  actual:   {}",
+            self.fixture, self.field, self.expected, self.actual
+        )
+    }
+}
+
+impl FixtureReport {
+    /// Panic listing every mismatch, for use in `#[test]` functions.
+    pub fn assert_passed(&self) {
+        if !self.failures.is_empty() {
+            let failures: Vec<_> = self.failures.iter().map(|f| f.to_string()).collect();
+            panic!(
+                "{} fixture mismatch(es), {} passed:\n{}",
+                self.failures.len(),
+                self.passed.len(),
+                failures.join("\n")
+            );
+        }
+    }
+}
+
+impl Readability {
+    /// Extract every fixture in `dir` and compare it with its expectations.
+    ///
+    /// Each subdirectory is a fixture holding `input.html` and
+    /// `expected.json`. Mozilla's test pages (`source.html`,
+    /// `expected-metadata.json` and `expected.html`) work as they are.
+    /// Only the fields present in the expected JSON are compared: `title`,
+    /// `byline`, `dir`, `lang`, `excerpt`, `siteName`, `publishedTime`,
+    /// `readerable` and `content`. Content is compared ignoring whitespace
+    /// between tags. Subdirectories without an input page are skipped.
+    ///
+    /// Requires the `test-utils` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use readability_js::Readability;
+    ///
+    /// let reader = Readability::new()?;
+    /// reader.run_fixtures("tests/fixtures", None)?.assert_passed();
+    /// # Ok::<(), readability_js::ReadabilityError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * A fixture can't be read ([`ReadabilityError::Io`])
+    /// * An expected JSON file is malformed ([`ReadabilityError::Serialization`])
+    ///
+    /// Extraction errors are reported as mismatches of the `content` field.
+    pub fn run_fixtures(
+        &self,
+        dir: impl AsRef<Path>,
+        options: Option<ReadabilityOptions>,
+    ) -> Result<FixtureReport> {
+        let mut dirs = fs::read_dir(dir)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        dirs.retain(|path| path.is_dir());
+        dirs.sort();
+
+        let mut report = FixtureReport::default();
+        for path in dirs {
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            let Some(input) = read_first(&path, &["input.html", "source.html"])? else {
+                continue;
+            };
+            let mut expected =
+                match read_first(&path, &["expected.json", "expected-metadata.json"])? {
+                    Some(json) => serde_json::from_str(&json)
+                        .map_err(|e| ReadabilityError::Serialization(format!("{}: {}", name, e)))?,
+                    None => Value::Object(Default::default()),
+                };
+            if let Some(html) = read_first(&path, &["expected.html"])? {
+                expected["content"] = Value::String(html);
+            }
+
+            let failures = self.check_fixture(&name, &input, &expected, options.clone())?;
+            if failures.is_empty() {
+                report.passed.push(name);
+            } else {
+                report.failures.extend(failures);
+            }
+        }
+        Ok(report)
+    }
+
+    fn check_fixture(
+        &self,
+        name: &str,
+        input: &str,
+        expected: &Value,
+        options: Option<ReadabilityOptions>,
+    ) -> Result<Vec<FixtureFailure>> {
+        let mut failures = Vec::new();
+        let mut compare = |field: &str, actual: Value| {
+            if let Some(want) = expected.get(field)
+                && !matches(field, want, &actual)
+            {
+                failures.push(FixtureFailure {
+                    fixture: name.to_string(),
+                    field: field.to_string(),
+                    expected: want.to_string(),
+                    actual: actual.to_string(),
+                });
+            }
+        };
+
+        if expected.get("readerable").is_some() {
+            compare("readerable", self.is_probably_readerable(input)?.into());
+        }
+        match self.parse_with_options(input, Some(FIXTURE_URL), options) {
+            Ok(article) => {
+                for (field, actual) in fields(article) {
+                    compare(field, actual);
+                }
+            }
+            Err(e) => compare("content", Value::String(format!("error: {}", e))),
+        }
+        Ok(failures)
+    }
+}
+
+/// The comparable fields of `article`, keyed as in Mozilla's fixtures.
+fn fields(article: Article) -> [(&'static str, Value); 8] {
+    let dir = article.direction.map(|d| match d {
+        Direction::Ltr => "ltr",
+        Direction::Rtl => "rtl",
+    });
+    [
+        ("title", article.title.into()),
+        ("byline", article.byline.into()),
+        ("dir", dir.into()),
+        ("lang", article.language.into()),
+        ("excerpt", article.excerpt.into()),
+        ("siteName", article.site_name.into()),
+        ("publishedTime", article.published_time.into()),
+        ("content", article.content.into()),
+    ]
+}
+
+fn matches(field: &str, expected: &Value, actual: &Value) -> bool {
+    match (field, expected, actual) {
+        ("content", Value::String(expected), Value::String(actual)) => {
+            normalize_html(expected) == normalize_html(actual)
+        }
+        _ => expected == actual,
+    }
+}
+
+/// Drop whitespace that only separates tags, which pretty-printing changes.
+fn normalize_html(html: &str) -> String {
+    text::collapse_whitespace(html)
+        .replace("> ", ">")
+        .replace(" <", "<")
+}
+
+/// Contents of the first of `names` that exists in `dir`.
+fn read_first(dir: &Path, names: &[&str]) -> Result<Option<String>> {
+    for name in names {
+        match fs::read_to_string(dir.join(name)) {
+            Ok(contents) => return Ok(Some(contents)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_ignores_whitespace_between_tags() {
+        let expected = Value::from("<div>\n  <p>\n    Hello <b>world</b>\n  </p>\n</div>");
+        assert!(matches(
+            "content",
+            &expected,
+            &Value::from("<div><p>Hello <b>world</b></p></div>")
+        ));
+        assert!(!matches(
+            "content",
+            &expected,
+            &Value::from("<div><p>Hello world</p></div>")
+        ));
+        assert!(matches("byline", &Value::Null, &Value::Null));
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+
+use crate::readability::Article;
+use crate::text::STOPWORDS;
+
+/// Longer candidates are usually sentence fragments rather than keyphrases.
+const MAX_PHRASE_WORDS: usize = 3;
+
+impl Article {
+    /// Rank the `n` most characteristic keyphrases of the text content.
+    ///
+    /// Uses RAKE: candidate phrases are runs of words between stopwords and
+    /// punctuation, scored by how often their words co-occur with others.
+    /// Works on a single document, so no corpus is needed. Phrases are
+    /// returned lower-cased, best first.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use readability_js::Readability;
+    ///
+    /// # let html = "<html><body><p>Some article text.</p></body></html>";
+    /// let reader = Readability::new()?;
+    /// let article = reader.parse(html)?;
+    /// let tags = article.keywords(5);
+    /// # Ok::<(), readability_js::ReadabilityError>(())
+    /// ```
+    pub fn keywords(&self, n: usize) -> Vec<String> {
+        let phrases = candidate_phrases(&self.text_content);
+
+        let mut frequency: HashMap<&str, f64> = HashMap::new();
+        let mut degree: HashMap<&str, f64> = HashMap::new();
+        for phrase in &phrases {
+            for word in phrase {
+                *frequency.entry(word).or_default() += 1.0;
+                *degree.entry(word).or_default() += phrase.len() as f64;
+            }
+        }
+
+        let mut scores: HashMap<String, f64> = HashMap::new();
+        for phrase in &phrases {
+            let score = phrase
+                .iter()
+                .map(|word| degree[word.as_str()] / frequency[word.as_str()])
+                .sum();
+            scores.insert(phrase.join(" "), score);
+        }
+
+        let mut ranked: Vec<(String, f64)> = scores.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ranked
+            .into_iter()
+            .take(n)
+            .map(|(phrase, _)| phrase)
+            .collect()
+    }
+}
+
+/// Runs of lower-cased content words, split at stopwords and punctuation.
+fn candidate_phrases(text: &str) -> Vec<Vec<String>> {
+    let mut phrases = Vec::new();
+    let mut current: Vec<String> = Vec::new();
+    let mut flush = |current: &mut Vec<String>| {
+        if !current.is_empty() && current.len() <= MAX_PHRASE_WORDS {
+            phrases.push(std::mem::take(current));
+        }
+        current.clear();
+    };
+
+    for token in text.split_inclusive(|c: char| !c.is_alphanumeric() && c != '\'' && c != '-') {
+        let boundary = token
+            .chars()
+            .last()
+            .is_some_and(|c| !c.is_alphanumeric() && !c.is_whitespace() && c != '\'' && c != '-');
+        let word = token
+            .trim_matches(|c: char| !c.is_alphanumeric())
+            .to_lowercase();
+
+        if word.is_empty() || STOPWORDS.contains(&word.as_str()) || word.parse::<f64>().is_ok() {
+            flush(&mut current);
+        } else if word.chars().count() > 1 {
+            current.push(word);
+        }
+        if boundary {
+            flush(&mut current);
+        }
+    }
+    flush(&mut current);
+    phrases
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ranks_multi_word_phrases_first() {
+        let article = Article {
+            text_content: "Memory safety matters. The borrow checker enforces memory safety, \
+                and the borrow checker runs at compile time."
+                .into(),
+            ..Default::default()
+        };
+        let keywords = article.keywords(3);
+        assert_eq!(keywords.len(), 3);
+        assert!(keywords.iter().all(|k| k.contains(' ')));
+        assert!(keywords.contains(&"compile time".to_string()));
+    }
+}
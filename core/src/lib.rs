@@ -181,5 +181,57 @@
 //! # Ok::<(), readability_js::ReadabilityError>(())
 //! ```
 
+mod builder;
+mod cache;
+#[cfg(any(feature = "json", feature = "toml"))]
+mod config;
+mod content_type;
+mod data_uri;
+mod engine;
+mod explain;
+#[cfg(any(feature = "fetch", feature = "async-fetch"))]
+mod fetch;
+mod fetcher;
+mod fingerprint;
+#[cfg(feature = "test-utils")]
+mod fixtures;
+#[cfg(feature = "keywords")]
+mod keywords;
+mod limits;
+mod quality;
 mod readability;
-pub use readability::{Article, Direction, Readability, ReadabilityError, ReadabilityOptions};
+mod render;
+mod sections;
+#[cfg(any(feature = "json", feature = "msgpack", feature = "cbor"))]
+mod serialize;
+#[cfg(feature = "summarize")]
+mod summarize;
+mod text;
+mod title;
+#[cfg(feature = "warc")]
+mod warc;
+pub use builder::ReadabilityBuilder;
+pub use cache::CacheStats;
+pub use engine::EngineInfo;
+pub use explain::{CandidateScore, Explanation};
+#[cfg(any(feature = "fetch", feature = "async-fetch"))]
+pub use fetch::FetchOptions;
+#[cfg(feature = "async-fetch")]
+pub use fetch::fetch_and_parse_async;
+pub use fetcher::{FetchError, FetchResponse, Fetcher};
+#[cfg(feature = "test-utils")]
+pub use fixtures::{FixtureFailure, FixtureReport};
+pub use limits::HardenedLimits;
+pub use readability::{
+    Article, Direction, ExtractionStats, HtmlFormat, LinkRel, Readability, ReadabilityCheckOptions,
+    ReadabilityError, ReadabilityOptions, ReaderableCheck, RemovedNode,
+};
+pub use sections::Section;
+#[cfg(feature = "json")]
+pub use serialize::ARTICLE_SCHEMA_VERSION;
+#[cfg(feature = "warc")]
+pub use warc::WarcArticles;
+
+/// Release of Readability.js embedded in this build: 0.6, or 0.5 with the
+/// `readability-0.5` feature.
+pub const READABILITY_JS_VERSION: &str = env!("READABILITY_JS_VERSION");
@@ -0,0 +1,87 @@
+use std::time::Duration;
+
+/// Resource limits for [`Readability::parse_hardened`](crate::Readability::parse_hardened).
+///
+/// The defaults suit user-submitted HTML: generous for real articles, tight
+/// enough that a hostile document fails fast instead of exhausting the
+/// process. Each exceeded limit is reported as its own error variant.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::time::Duration;
+/// use readability_js::HardenedLimits;
+///
+/// let limits = HardenedLimits::new()
+///     .max_input_bytes(512 * 1024)
+///     .timeout(Duration::from_secs(2));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HardenedLimits {
+    pub max_input_bytes: usize,
+    pub max_memory_bytes: usize,
+    pub max_elements: usize,
+    pub timeout: Duration,
+}
+
+impl Default for HardenedLimits {
+    fn default() -> Self {
+        Self {
+            max_input_bytes: 5 * 1024 * 1024,
+            max_memory_bytes: 256 * 1024 * 1024,
+            max_elements: 50_000,
+            timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+impl HardenedLimits {
+    /// Creates limits with the default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reject larger input before it reaches the parser.
+    ///
+    /// Exceeding it returns [`ReadabilityError::InputTooLarge`](crate::ReadabilityError::InputTooLarge).
+    ///
+    /// # Arguments
+    /// * `bytes` - Maximum HTML size in bytes (default: 5 MiB)
+    pub fn max_input_bytes(mut self, bytes: usize) -> Self {
+        self.max_input_bytes = bytes;
+        self
+    }
+
+    /// Cap how much the JavaScript heap may grow during the call.
+    ///
+    /// Exceeding it returns [`ReadabilityError::MemoryLimitExceeded`](crate::ReadabilityError::MemoryLimitExceeded).
+    ///
+    /// # Arguments
+    /// * `bytes` - Maximum heap growth in bytes (default: 256 MiB)
+    pub fn max_memory_bytes(mut self, bytes: usize) -> Self {
+        self.max_memory_bytes = bytes;
+        self
+    }
+
+    /// Refuse documents with more elements than this.
+    ///
+    /// Exceeding it returns [`ReadabilityError::ElementLimitExceeded`](crate::ReadabilityError::ElementLimitExceeded).
+    ///
+    /// # Arguments
+    /// * `count` - Maximum number of DOM elements (default: 50,000)
+    pub fn max_elements(mut self, count: usize) -> Self {
+        self.max_elements = count;
+        self
+    }
+
+    /// Interrupt the extraction after this long.
+    ///
+    /// Exceeding it returns [`ReadabilityError::Timeout`](crate::ReadabilityError::Timeout).
+    ///
+    /// # Arguments
+    /// * `timeout` - Maximum time for the call (default: 5 seconds)
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
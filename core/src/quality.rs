@@ -0,0 +1,31 @@
+/// Top candidate score at which the score component reaches about 63%.
+const SCORE_SCALE: f64 = 50.0;
+
+/// Text length in characters at which the length component reaches about 63%.
+const LENGTH_SCALE: f64 = 1500.0;
+
+/// Combine extraction signals into a confidence between 0 and 1.
+///
+/// A confident extraction has a clearly winning top candidate, a substantial
+/// amount of text, and few links. Each signal saturates, so very long or very
+/// high-scoring articles do not drown out the others.
+pub(crate) fn quality_score(top_candidate_score: f64, length: u32, link_density: f64) -> f64 {
+    let score = 1.0 - (-top_candidate_score.max(0.0) / SCORE_SCALE).exp();
+    let length = 1.0 - (-(length as f64) / LENGTH_SCALE).exp();
+    let links = 1.0 - (link_density * 2.0).clamp(0.0, 1.0);
+    (0.4 * score + 0.4 * length + 0.2 * links).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quality_orders_typical_pages() {
+        let article = quality_score(120.0, 6000, 0.05);
+        let listing = quality_score(15.0, 400, 0.6);
+        assert!(article > 0.8, "{article}");
+        assert!(listing < 0.3, "{listing}");
+        assert_eq!(quality_score(0.0, 0, 1.0), 0.0);
+    }
+}
@@ -1,8 +1,26 @@
-use rquickjs::{Context as QuickContext, Ctx, Function, Object, Runtime, Value};
+use rquickjs::{Array, Ctx, Function, Object, Value};
+use std::any::Any;
+use std::borrow::Cow;
+use std::cell::{Cell, OnceCell, RefCell};
+use std::io::Read;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
+use crate::builder::ReadabilityBuilder;
+use crate::cache::{self, CacheStats, ResultCache};
+use crate::content_type;
+use crate::data_uri;
+use crate::engine::{Engine, EngineInfo};
+use crate::explain::{self, CandidateScore};
+use crate::fetcher::FetchError;
+use crate::limits::HardenedLimits;
+use crate::{quality, text, title};
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum Direction {
     /// Left-to-Right
     Ltr,
@@ -10,12 +28,35 @@ pub enum Direction {
     Rtl,
 }
 
+/// Whitespace layout of [`Article::content`], see [`ReadabilityOptions::html_format`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum HtmlFormat {
+    /// One block element per line, indented by nesting depth
+    Pretty,
+    /// Insignificant whitespace and comments removed
+    Minified,
+}
+
+/// Treatment of `rel` attributes on links, see [`ReadabilityOptions::link_rel`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum LinkRel {
+    /// Lowercased and deduplicated, without `nofollow`, `sponsored` and `ugc`
+    Normalize,
+    /// Removed entirely
+    Strip,
+}
+
 /// Parsed article content and metadata extracted by Readability.
 ///
 /// All fields except `title`, `content`, `text_content`, and `length` are optional
 /// and depend on the input HTML having appropriate metadata.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Article {
     /// Extracted or inferred article title
     pub title: String,
@@ -30,9 +71,15 @@ pub struct Article {
     pub length: u32,
 
     /// Author byline metadata
+    ///
+    /// Falls back to JSON-LD and microdata authors, author meta tags and
+    /// `rel="author"` links when the page has no visible byline.
     pub byline: Option<String>,
 
     /// Content direction
+    ///
+    /// Taken from the `dir` attribute around the content. Without one,
+    /// `Rtl` when most letters are in a right-to-left script.
     pub direction: Option<Direction>,
 
     /// Article description or short excerpt
@@ -46,6 +93,123 @@ pub struct Article {
 
     /// Published time in ISO 8601 or site format, if detectable
     pub published_time: Option<String>,
+
+    /// Last modification time in ISO 8601 or site format, if detectable
+    pub modified_time: Option<String>,
+
+    /// Confidence in the extraction, from 0 (likely wrong) to 1
+    ///
+    /// Derived from the top candidate's Readability score, the text length
+    /// and the link density of the content. Low values are worth routing to
+    /// manual review; listing and hub pages typically score below 0.3.
+    pub quality: Option<f64>,
+
+    /// Readability score of the winning candidate, the element the content
+    /// was taken from
+    ///
+    /// Unbounded, from the length, commas and class names of the paragraphs
+    /// inside, scaled down by link density. Readability itself only rejects
+    /// pages on text length; pipelines can set their own cutoff on this, with
+    /// real articles usually scoring above 30.
+    pub score: Option<f64>,
+
+    /// Fraction of the document's text that survived extraction, from 0 to 1
+    ///
+    /// A very low ratio usually means a listing or hub page, a very high one
+    /// that the page was already clean.
+    pub retained_ratio: Option<f64>,
+
+    /// Word count declared by the page's schema.org article metadata
+    pub word_count: Option<u32>,
+
+    /// Section or category the article was filed under, e.g. "Sports"
+    ///
+    /// Taken from `article:section` or JSON-LD `articleSection`.
+    pub section: Option<String>,
+
+    /// Comments section as cleaned HTML, kept separate from `content`
+    ///
+    /// Only populated when [`ReadabilityOptions::extract_comments`] is enabled
+    /// and the page has a comments section with server-rendered comments.
+    pub comments_html: Option<String>,
+
+    /// Absolute URL of the lead image, suitable for article cards and covers
+    ///
+    /// Taken from `og:image`, `twitter:image` or JSON-LD metadata, falling
+    /// back to the first large image in the content.
+    pub lead_image_url: Option<String>,
+
+    /// Width of the lead image in pixels, if declared by the page
+    pub lead_image_width: Option<u32>,
+
+    /// Height of the lead image in pixels, if declared by the page
+    pub lead_image_height: Option<u32>,
+
+    /// Absolute URL of the site icon, preferring the largest declared size
+    ///
+    /// Taken from `<link rel="icon">` and `<link rel="apple-touch-icon">`,
+    /// falling back to `/favicon.ico` when a base URL is known.
+    pub site_icon_url: Option<String>,
+
+    /// Whether `content` was cut short by [`ReadabilityOptions::max_output_bytes`]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub truncated: bool,
+
+    /// Where the extraction spent its time
+    ///
+    /// Only populated when [`ReadabilityOptions::collect_stats`] is enabled.
+    pub stats: Option<ExtractionStats>,
+
+    /// Every node Readability removed on the way to the article, in order
+    ///
+    /// Only populated when [`ReadabilityOptions::record_removed`] is enabled.
+    pub removed: Option<Vec<RemovedNode>>,
+}
+
+/// Per-phase timings of one extraction, see [`Article::stats`].
+///
+/// Phases are measured with millisecond resolution inside the JavaScript
+/// engine; `js_eval_ms` covers all of them plus metadata extraction.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ExtractionStats {
+    /// Total time spent in the JavaScript engine
+    pub js_eval_ms: f64,
+    /// Time spent parsing the input into a DOM
+    pub dom_parse_ms: f64,
+    /// Time Readability spent finding and cleaning the article
+    pub grab_article_ms: f64,
+    /// Time spent converting the result into an [`Article`]
+    pub serialize_ms: f64,
+    /// Size of the input HTML
+    pub input_bytes: usize,
+    /// Size of [`Article::content`]
+    pub output_bytes: usize,
+}
+
+/// A node Readability removed while extracting, see [`Article::removed`].
+///
+/// Readability may search the page several times, relaxing its rules each
+/// time it finds too little text; nodes removed in a search that was
+/// abandoned are back in the page for the next one.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct RemovedNode {
+    /// Lowercase tag name, such as `div`
+    pub tag: String,
+    /// Value of the `class` attribute
+    pub class: Option<String>,
+    /// Value of the `id` attribute
+    pub id: Option<String>,
+    /// The rule that removed it, such as `hidden`, `unlikely candidate` or
+    /// `clean conditionally: div`
+    pub reason: String,
+    /// Start of its text, with whitespace collapsed
+    pub text_preview: String,
+    /// Which search removed it, starting at 1
+    pub pass: u32,
 }
 
 impl<'js> TryFrom<Value<'js>> for Article {
@@ -65,24 +229,7 @@ impl<'js> TryFrom<Value<'js>> for Article {
                 source: e,
             })?;
 
-        let byline = obj
-            .get::<_, Value>("byline")
-            .map_err(|e| ReadabilityError::JsEvaluation {
-                context: "failed to get byline".into(),
-                source: e,
-            })?;
-        let byline = if byline.is_null() || byline.is_undefined() {
-            None
-        } else {
-            Some(
-                byline
-                    .get::<String>()
-                    .map_err(|e| ReadabilityError::JsEvaluation {
-                        context: "failed to get byline as string".into(),
-                        source: e,
-                    })?,
-            )
-        };
+        let byline = optional_string(obj, "byline", "byline")?;
 
         let dir = obj
             .get::<_, Value>("dir")
@@ -118,6 +265,9 @@ impl<'js> TryFrom<Value<'js>> for Article {
                     context: "failed to get text_content".into(),
                     source: e,
                 })?;
+        // Many right-to-left pages don't declare `dir`
+        let direction =
+            direction.or_else(|| text::is_mostly_rtl(&text_content).then_some(Direction::Rtl));
         let length = obj
             .get::<_, u32>("length")
             .map_err(|e| ReadabilityError::JsEvaluation {
@@ -125,81 +275,69 @@ impl<'js> TryFrom<Value<'js>> for Article {
                 source: e,
             })?;
 
-        let excerpt =
-            obj.get::<_, Value>("excerpt")
-                .map_err(|e| ReadabilityError::JsEvaluation {
-                    context: "failed to get excerpt".into(),
-                    source: e,
-                })?;
-        let excerpt = if excerpt.is_null() || excerpt.is_undefined() {
-            None
-        } else {
-            Some(
-                excerpt
-                    .get::<String>()
-                    .map_err(|e| ReadabilityError::JsEvaluation {
-                        context: "failed to get excerpt as string".into(),
-                        source: e,
-                    })?,
-            )
-        };
-
-        let site_name =
-            obj.get::<_, Value>("siteName")
-                .map_err(|e| ReadabilityError::JsEvaluation {
-                    context: "failed to get site_name".into(),
-                    source: e,
-                })?;
-        let site_name = if site_name.is_null() || site_name.is_undefined() {
-            None
-        } else {
-            Some(
-                site_name
-                    .get::<String>()
-                    .map_err(|e| ReadabilityError::JsEvaluation {
-                        context: "failed to get site_name as string".into(),
-                        source: e,
-                    })?,
-            )
+        let excerpt = optional_string(obj, "excerpt", "excerpt")?;
+        let site_name = optional_string(obj, "siteName", "site_name")?;
+        let language = optional_string(obj, "lang", "lang")?;
+        let published_time = optional_string(obj, "publishedTime", "published_time")?;
+        let modified_time = optional_string(obj, "modifiedTime", "modified_time")?;
+        let word_count = optional_u32(obj, "wordCount", "word_count")?;
+        let section = optional_string(obj, "section", "section")?;
+        let retained_ratio = optional_f64(obj, "retainedRatio", "retained_ratio")?;
+        let score = optional_f64(obj, "topCandidateScore", "score")?;
+        let link_density = optional_f64(obj, "linkDensity", "link_density")?;
+        let quality = score
+            .map(|score| quality::quality_score(score, length, link_density.unwrap_or_default()));
+        let comments_html = optional_string(obj, "commentsHtml", "comments_html")?;
+        let lead_image_url = optional_string(obj, "leadImageUrl", "lead_image_url")?;
+        let lead_image_width = optional_u32(obj, "leadImageWidth", "lead_image_width")?;
+        let lead_image_height = optional_u32(obj, "leadImageHeight", "lead_image_height")?;
+        let site_icon_url = optional_string(obj, "siteIconUrl", "site_icon_url")?;
+        let truncated = obj
+            .get::<_, Option<bool>>("truncated")
+            .js_context("failed to get truncated")?
+            .unwrap_or(false);
+        let stats = match obj
+            .get::<_, Option<Object>>("stats")
+            .js_context("failed to get stats")?
+        {
+            Some(stats) => Some(ExtractionStats {
+                dom_parse_ms: optional_f64(&stats, "domParseMs", "dom_parse_ms")?
+                    .unwrap_or_default(),
+                grab_article_ms: optional_f64(&stats, "grabArticleMs", "grab_article_ms")?
+                    .unwrap_or_default(),
+                ..Default::default()
+            }),
+            None => None,
         };
-
-        let language = obj
-            .get::<_, Value>("lang")
-            .map_err(|e| ReadabilityError::JsEvaluation {
-                context: "failed to get lang".into(),
-                source: e,
-            })?;
-        let language = if language.is_null() || language.is_undefined() {
-            None
-        } else {
-            Some(
-                language
-                    .get::<String>()
-                    .map_err(|e| ReadabilityError::JsEvaluation {
-                        context: "failed to get lang as string".into(),
-                        source: e,
-                    })?,
-            )
+        let removed = match obj
+            .get::<_, Option<Array>>("removed")
+            .js_context("failed to get removed")?
+        {
+            Some(nodes) => Some(
+                nodes
+                    .iter::<Object>()
+                    .map(|node| {
+                        let node = node.js_context("failed to get removed node")?;
+                        Ok(RemovedNode {
+                            tag: optional_string(&node, "tag", "removed tag")?.unwrap_or_default(),
+                            class: optional_string(&node, "className", "removed class")?,
+                            id: optional_string(&node, "id", "removed id")?,
+                            reason: optional_string(&node, "reason", "removed reason")?
+                                .unwrap_or_default(),
+                            text_preview: optional_string(
+                                &node,
+                                "textPreview",
+                                "removed text_preview",
+                            )?
+                            .unwrap_or_default(),
+                            pass: optional_u32(&node, "pass", "removed pass")?.unwrap_or(1),
+                        })
+                    })
+                    .collect::<Result<_>>()?,
+            ),
+            None => None,
         };
 
-        let published_time =
-            obj.get::<_, Value>("publishedTime")
-                .map_err(|e| ReadabilityError::JsEvaluation {
-                    context: "failed to get published_time".into(),
-                    source: e,
-                })?;
-        let published_time =
-            if published_time.is_null() || published_time.is_undefined() {
-                None
-            } else {
-                Some(published_time.get::<String>().map_err(|e| {
-                    ReadabilityError::JsEvaluation {
-                        context: "failed to get published_time as string".into(),
-                        source: e,
-                    }
-                })?)
-            };
-
         Ok(Article {
             title,
             byline,
@@ -211,10 +349,78 @@ impl<'js> TryFrom<Value<'js>> for Article {
             site_name,
             language,
             published_time,
+            modified_time,
+            quality,
+            score,
+            retained_ratio,
+            word_count,
+            section,
+            comments_html,
+            lead_image_url,
+            lead_image_width,
+            lead_image_height,
+            site_icon_url,
+            truncated,
+            stats,
+            removed,
         })
     }
 }
 
+/// Read an optional string property, treating `null` and `undefined` as absent.
+pub(crate) fn optional_string(obj: &Object, key: &str, name: &str) -> Result<Option<String>> {
+    let value = obj
+        .get::<_, Value>(key)
+        .js_context(&format!("failed to get {}", name))?;
+    if value.is_null() || value.is_undefined() {
+        return Ok(None);
+    }
+    value
+        .get::<String>()
+        .map(Some)
+        .js_context(&format!("failed to get {} as string", name))
+}
+
+/// Read an optional number property, treating `null` and `undefined` as absent.
+pub(crate) fn optional_f64(obj: &Object, key: &str, name: &str) -> Result<Option<f64>> {
+    let value = obj
+        .get::<_, Value>(key)
+        .js_context(&format!("failed to get {}", name))?;
+    if value.is_null() || value.is_undefined() {
+        return Ok(None);
+    }
+    value
+        .get::<f64>()
+        .map(Some)
+        .js_context(&format!("failed to get {} as number", name))
+}
+
+/// Read an optional integer property, treating `null`, `undefined` and numbers
+/// outside the `u32` range as absent.
+pub(crate) fn optional_u32(obj: &Object, key: &str, name: &str) -> Result<Option<u32>> {
+    let value = obj
+        .get::<_, Value>(key)
+        .js_context(&format!("failed to get {}", name))?;
+    if value.is_null() || value.is_undefined() {
+        return Ok(None);
+    }
+    // Counts and sizes come from page markup; an absurd one is unknown, not a
+    // reason to fail the extraction.
+    if value
+        .as_number()
+        .is_some_and(|n| !(0.0..=u32::MAX as f64).contains(&n))
+    {
+        return Ok(None);
+    }
+    value
+        .get::<u32>()
+        .map(Some)
+        .js_context(&format!("failed to get {} as integer", name))
+}
+
+/// Largest `nb_top_candidates` accepted by [`ReadabilityOptions::validate`].
+const MAX_TOP_CANDIDATES: usize = 1000;
+
 /// Configuration options for content extraction.
 ///
 /// Created with [`ReadabilityOptions::new`] and used with
@@ -235,6 +441,7 @@ impl<'js> TryFrom<Value<'js>> for Article {
 /// ```
 #[derive(Default, Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ReadabilityOptions {
     pub max_elems_to_parse: Option<usize>,
     pub nb_top_candidates: Option<usize>,
@@ -243,6 +450,24 @@ pub struct ReadabilityOptions {
     pub keep_classes: Option<bool>,
     pub disable_jsonld: Option<bool>,
     pub link_density_modifier: Option<f32>,
+    pub extract_comments: Option<bool>,
+    pub clean_title: Option<bool>,
+    pub prefer_h1_title: Option<bool>,
+    pub generate_excerpt: Option<usize>,
+    pub strip_data_uris_above: Option<usize>,
+    pub max_data_uri_bytes: Option<usize>,
+    pub max_output_bytes: Option<usize>,
+    pub html_format: Option<HtmlFormat>,
+    pub keep_inline_styles: Option<bool>,
+    pub keep_asides_above: Option<usize>,
+    pub convert_divs_to_paragraphs: Option<bool>,
+    pub collect_stats: Option<bool>,
+    pub record_removed: Option<bool>,
+    pub link_rel: Option<LinkRel>,
+    pub unlink_sponsored: Option<bool>,
+    pub collapse_whitespace: Option<bool>,
+    pub trim_lines: Option<bool>,
+    pub normalize_spaces: Option<bool>,
     // TODO: serializer and allowed_video_regex
 }
 
@@ -252,6 +477,57 @@ impl ReadabilityOptions {
         Self::default()
     }
 
+    /// Options for news sites and blogs.
+    ///
+    /// Requires a substantial body of text, so teasers and link lists are
+    /// rejected, is strict about link-heavy blocks such as "related stories",
+    /// moves reader comments out of the article and takes the headline from
+    /// the visible `<h1>` without the site name.
+    ///
+    /// Like any options, a preset can be adjusted with the other setters.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use readability_js::ReadabilityOptions;
+    ///
+    /// let options = ReadabilityOptions::preset_news().char_threshold(300);
+    /// ```
+    pub fn preset_news() -> Self {
+        Self::new()
+            .char_threshold(500)
+            .link_density_modifier(-0.1)
+            .extract_comments(true)
+            .prefer_h1_title(true)
+            .clean_title(true)
+    }
+
+    /// Options for documentation, references and wikis.
+    ///
+    /// Accepts short pages, compares more candidates since the content is
+    /// often split across containers, tolerates the dense cross-references of
+    /// API docs, and keeps notes, callouts and structured `<div>` layouts.
+    pub fn preset_docs() -> Self {
+        Self::new()
+            .char_threshold(100)
+            .nb_top_candidates(10)
+            .link_density_modifier(0.2)
+            .keep_asides_above(100)
+            .convert_divs_to_paragraphs(false)
+    }
+
+    /// Options for forum threads, Q&A sites and mailing list archives.
+    ///
+    /// A thread is many short posts, often each in its own container, so
+    /// short text is accepted, more candidates are compared and signatures
+    /// full of links weigh less against a post.
+    pub fn preset_forum() -> Self {
+        Self::new()
+            .char_threshold(100)
+            .nb_top_candidates(15)
+            .link_density_modifier(0.1)
+    }
+
     /// Set maximum number of DOM elements to parse.
     ///
     /// Limits processing to avoid performance issues on very large documents.
@@ -330,16 +606,351 @@ impl ReadabilityOptions {
     /// Modify the link density calculation.
     ///
     /// Content with high link density is often navigation rather than article
-    /// content. This modifier adjusts how strictly link density is evaluated.
-    /// Values > 1.0 are more permissive, < 1.0 are stricter.
+    /// content. This modifier is added to the link density a block may have
+    /// before it is removed, 0.2 for most blocks. Positive values are more
+    /// permissive, negative values stricter.
     ///
     /// # Arguments
-    /// * `val` - Link density modifier (recommended: 0.5-2.0, default: 1.0)
+    /// * `val` - Link density modifier (recommended: -0.1 to 0.3, default: 0.0)
     pub fn link_density_modifier(mut self, val: f32) -> Self {
         self.link_density_modifier = Some(val);
         self
     }
 
+    /// Extract the comments section separately into [`Article::comments_html`].
+    ///
+    /// Comments are located through common containers (`#comments`, Disqus
+    /// placeholders, schema.org `Comment` items) and removed from the page
+    /// before the main extraction, so they never leak into the article content.
+    ///
+    /// # Arguments
+    /// * `val` - true to extract comments, false to leave them to the algorithm
+    pub fn extract_comments(mut self, val: bool) -> Self {
+        self.extract_comments = Some(val);
+        self
+    }
+
+    /// Strip site-name and section suffixes from the title.
+    ///
+    /// Turns titles like "Great Story – Example News | Politics" into
+    /// "Great Story", using the detected site name and common separators.
+    ///
+    /// # Arguments
+    /// * `val` - true to clean up the title, false to keep it as detected
+    pub fn clean_title(mut self, val: bool) -> Self {
+        self.clean_title = Some(val);
+        self
+    }
+
+    /// Use the page's visible `<h1>` headline as the title.
+    ///
+    /// Some sites use a marketing `<title>` that differs from the headline
+    /// readers see. When enabled, the first visible `<h1>` (preferring one
+    /// inside `<article>` or `<main>`) replaces the detected title.
+    ///
+    /// # Arguments
+    /// * `val` - true to prefer the `<h1>`, false to use the detected title
+    pub fn prefer_h1_title(mut self, val: bool) -> Self {
+        self.prefer_h1_title = Some(val);
+        self
+    }
+
+    /// Synthesize an excerpt from the text when the page provides none.
+    ///
+    /// The excerpt is taken from the start of [`Article::text_content`] and
+    /// cut after the last full sentence that fits, so list views always have
+    /// a teaser.
+    ///
+    /// # Arguments
+    /// * `max_chars` - Maximum excerpt length in characters (recommended: 200-300)
+    pub fn generate_excerpt(mut self, max_chars: usize) -> Self {
+        self.generate_excerpt = Some(max_chars);
+        self
+    }
+
+    /// Collapse runs of whitespace in [`Article::text_content`].
+    ///
+    /// Runs of spaces and tabs become one space, trailing spaces are
+    /// dropped, and runs of blank lines become one blank line, so the text
+    /// no longer depends on how the page's source was indented.
+    ///
+    /// # Arguments
+    /// * `val` - `true` to collapse whitespace
+    pub fn collapse_whitespace(mut self, val: bool) -> Self {
+        self.collapse_whitespace = Some(val);
+        self
+    }
+
+    /// Remove the indentation at the start of each line of
+    /// [`Article::text_content`].
+    ///
+    /// # Arguments
+    /// * `val` - `true` to trim line-leading spaces and tabs
+    pub fn trim_lines(mut self, val: bool) -> Self {
+        self.trim_lines = Some(val);
+        self
+    }
+
+    /// Turn non-breaking and other Unicode spaces in [`Article::text_content`]
+    /// into plain spaces.
+    ///
+    /// Pages use `&nbsp;` for layout, which breaks matching the text against
+    /// plain strings. Applied before the other whitespace options, so they
+    /// see these spaces too.
+    ///
+    /// # Arguments
+    /// * `val` - `true` to normalize spaces
+    pub fn normalize_spaces(mut self, val: bool) -> Self {
+        self.normalize_spaces = Some(val);
+        self
+    }
+
+    /// Remove inlined `data:` URIs larger than `bytes` before parsing.
+    ///
+    /// Pages saved by SingleFile and similar tools embed every image, font
+    /// and stylesheet as a `data:` URI, which can make a short article tens
+    /// of megabytes. Stripping the large ones first makes parsing much
+    /// faster and keeps them out of the content; the emptied `src` or
+    /// `url()` remains.
+    ///
+    /// # Arguments
+    /// * `bytes` - Longest `data:` URI to keep (e.g. 16 * 1024)
+    pub fn strip_data_uris_above(mut self, bytes: usize) -> Self {
+        self.strip_data_uris_above = Some(bytes);
+        self
+    }
+
+    /// Drop `data:` URIs larger than `bytes` from the extracted article.
+    ///
+    /// Applies to [`Article::content`] and [`Article::lead_image_url`], so a
+    /// single inlined hero image can't bloat the stored article. Unlike
+    /// [`ReadabilityOptions::strip_data_uris_above`], extraction still sees
+    /// the full page.
+    ///
+    /// # Arguments
+    /// * `bytes` - Longest `data:` URI to keep in the output
+    pub fn max_data_uri_bytes(mut self, bytes: usize) -> Self {
+        self.max_data_uri_bytes = Some(bytes);
+        self
+    }
+
+    /// Truncate the article content to at most `bytes` of HTML.
+    ///
+    /// Trailing elements are dropped whole, so the content stays
+    /// well-formed, and [`Article::truncated`] is set. Protects downstream
+    /// systems with message-size limits.
+    ///
+    /// # Arguments
+    /// * `bytes` - Maximum size of [`Article::content`] in bytes
+    pub fn max_output_bytes(mut self, bytes: usize) -> Self {
+        self.max_output_bytes = Some(bytes);
+        self
+    }
+
+    /// Reformat the whitespace of the article content.
+    ///
+    /// By default the content keeps whatever whitespace the source page had.
+    /// `Pretty` puts each block element on its own indented line for
+    /// readable diffs and storage; `Minified` drops insignificant whitespace
+    /// and comments. Preformatted elements are kept as they are.
+    ///
+    /// # Arguments
+    /// * `format` - Layout of [`Article::content`]
+    pub fn html_format(mut self, format: HtmlFormat) -> Self {
+        self.html_format = Some(format);
+        self
+    }
+
+    /// Rewrite the `rel` attributes of links in the content.
+    ///
+    /// `nofollow`, `sponsored` and `ugc` tell search engines how the
+    /// original site vouches for a link, which rarely holds once the content
+    /// is republished elsewhere. `Normalize` drops those and tidies the rest,
+    /// such as `noopener`; `Strip` removes `rel` altogether.
+    ///
+    /// # Arguments
+    /// * `mode` - How to rewrite `rel` (default: kept as on the page)
+    pub fn link_rel(mut self, mode: LinkRel) -> Self {
+        self.link_rel = Some(mode);
+        self
+    }
+
+    /// Replace links marked `rel="sponsored"` with their text.
+    ///
+    /// Drops paid placements from republished content while keeping the
+    /// sentence they appear in intact.
+    ///
+    /// # Arguments
+    /// * `val` - `true` to unlink sponsored links
+    pub fn unlink_sponsored(mut self, val: bool) -> Self {
+        self.unlink_sponsored = Some(val);
+        self
+    }
+
+    /// Keep inline `style` attributes in the extracted content.
+    ///
+    /// Readability strips them unconditionally, but scientific and
+    /// documentation pages sometimes carry meaning in them, such as colored
+    /// spans or alignment. Other presentational attributes are still removed.
+    ///
+    /// # Arguments
+    /// * `val` - `true` to keep `style` attributes
+    pub fn keep_inline_styles(mut self, val: bool) -> Self {
+        self.keep_inline_styles = Some(val);
+        self
+    }
+
+    /// Keep asides and blockquotes with at least `chars` characters of text.
+    ///
+    /// Readability treats every `<aside>`, and anything classed like a
+    /// sidebar, as clutter, which loses pull quotes and notes that are part
+    /// of the piece. Shorter ones are still removed.
+    ///
+    /// # Arguments
+    /// * `chars` - Minimum text length of an aside or blockquote to keep (e.g. 100)
+    pub fn keep_asides_above(mut self, chars: usize) -> Self {
+        self.keep_asides_above = Some(chars);
+        self
+    }
+
+    /// Whether to rewrite `<div>`s without block children into `<p>`s.
+    ///
+    /// Readability does this by default, and also unwraps `<div>`s that hold a
+    /// single paragraph. Disabling it keeps definition lists, widgets and
+    /// other structured layouts intact.
+    ///
+    /// # Arguments
+    /// * `val` - `false` to leave `<div>`s as they are (default: `true`)
+    pub fn convert_divs_to_paragraphs(mut self, val: bool) -> Self {
+        self.convert_divs_to_paragraphs = Some(val);
+        self
+    }
+
+    /// Record per-phase timings in [`Article::stats`].
+    ///
+    /// Useful for monitoring where latency comes from in production. Cached
+    /// results keep the timings of the extraction that produced them.
+    ///
+    /// # Arguments
+    /// * `val` - `true` to collect [`ExtractionStats`]
+    pub fn collect_stats(mut self, val: bool) -> Self {
+        self.collect_stats = Some(val);
+        self
+    }
+
+    /// Record every node Readability removes in [`Article::removed`].
+    ///
+    /// Meant for debugging why a paragraph goes missing on a particular
+    /// site: each entry names the rule that dropped the node.
+    ///
+    /// # Arguments
+    /// * `val` - `true` to collect [`RemovedNode`]s
+    pub fn record_removed(mut self, val: bool) -> Self {
+        self.record_removed = Some(val);
+        self
+    }
+
+    /// Check that the options make sense before they reach Readability.js.
+    ///
+    /// Extraction calls this itself, so it is only needed to reject bad
+    /// options early, such as when loading them from configuration.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use readability_js::ReadabilityOptions;
+    ///
+    /// assert!(ReadabilityOptions::new().char_threshold(500).validate().is_ok());
+    /// assert!(ReadabilityOptions::new().nb_top_candidates(0).validate().is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReadabilityError::InvalidOptions`] naming the first option
+    /// with a value that can't work, such as a zero `nb_top_candidates` or an
+    /// empty entry in `classes_to_preserve`.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |message: String| Err(ReadabilityError::InvalidOptions(message));
+        if let Some(val) = self
+            .nb_top_candidates
+            .filter(|val| !(1..=MAX_TOP_CANDIDATES).contains(val))
+        {
+            return invalid(format!(
+                "nb_top_candidates must be between 1 and {}, got {}",
+                MAX_TOP_CANDIDATES, val
+            ));
+        }
+        if let Some(val) = self
+            .link_density_modifier
+            .filter(|val| !(-1.0..=1.0).contains(val))
+        {
+            // Link density is a share from 0 to 1, so anything beyond keeps
+            // or removes every block
+            return invalid(format!(
+                "link_density_modifier must be between -1 and 1, got {}",
+                val
+            ));
+        }
+        for (i, class) in self.classes_to_preserve.iter().flatten().enumerate() {
+            if class.trim().is_empty() {
+                return invalid(format!("classes_to_preserve[{}] is empty", i));
+            }
+            if class.contains(char::is_whitespace) {
+                return invalid(format!(
+                    "classes_to_preserve[{}] must be a single class name, got {:?}",
+                    i, class
+                ));
+            }
+        }
+        for (name, val) in [
+            ("generate_excerpt", self.generate_excerpt),
+            ("max_output_bytes", self.max_output_bytes),
+        ] {
+            if val == Some(0) {
+                return invalid(format!("{} must be at least 1", name));
+            }
+        }
+        Ok(())
+    }
+
+    /// Fill every unset option from `defaults`.
+    fn or(self, defaults: &ReadabilityOptions) -> Self {
+        let defaults = defaults.clone();
+        Self {
+            max_elems_to_parse: self.max_elems_to_parse.or(defaults.max_elems_to_parse),
+            nb_top_candidates: self.nb_top_candidates.or(defaults.nb_top_candidates),
+            char_threshold: self.char_threshold.or(defaults.char_threshold),
+            classes_to_preserve: self.classes_to_preserve.or(defaults.classes_to_preserve),
+            keep_classes: self.keep_classes.or(defaults.keep_classes),
+            disable_jsonld: self.disable_jsonld.or(defaults.disable_jsonld),
+            link_density_modifier: self
+                .link_density_modifier
+                .or(defaults.link_density_modifier),
+            extract_comments: self.extract_comments.or(defaults.extract_comments),
+            clean_title: self.clean_title.or(defaults.clean_title),
+            prefer_h1_title: self.prefer_h1_title.or(defaults.prefer_h1_title),
+            generate_excerpt: self.generate_excerpt.or(defaults.generate_excerpt),
+            strip_data_uris_above: self
+                .strip_data_uris_above
+                .or(defaults.strip_data_uris_above),
+            max_data_uri_bytes: self.max_data_uri_bytes.or(defaults.max_data_uri_bytes),
+            max_output_bytes: self.max_output_bytes.or(defaults.max_output_bytes),
+            html_format: self.html_format.or(defaults.html_format),
+            keep_inline_styles: self.keep_inline_styles.or(defaults.keep_inline_styles),
+            keep_asides_above: self.keep_asides_above.or(defaults.keep_asides_above),
+            convert_divs_to_paragraphs: self
+                .convert_divs_to_paragraphs
+                .or(defaults.convert_divs_to_paragraphs),
+            collect_stats: self.collect_stats.or(defaults.collect_stats),
+            record_removed: self.record_removed.or(defaults.record_removed),
+            link_rel: self.link_rel.or(defaults.link_rel),
+            unlink_sponsored: self.unlink_sponsored.or(defaults.unlink_sponsored),
+            collapse_whitespace: self.collapse_whitespace.or(defaults.collapse_whitespace),
+            trim_lines: self.trim_lines.or(defaults.trim_lines),
+            normalize_spaces: self.normalize_spaces.or(defaults.normalize_spaces),
+        }
+    }
+
     fn build<'js>(self, ctx: Ctx<'js>) -> Result<Object<'js>> {
         let obj = Object::new(ctx).map_err(|e| ReadabilityError::JsEvaluation {
             context: "failed to create options object".into(),
@@ -396,59 +1007,146 @@ impl ReadabilityOptions {
                     source: e,
                 })?;
         }
+        if let Some(val) = self.extract_comments {
+            obj.set("extractComments", val)
+                .js_context("failed to set extractComments option")?;
+        }
+        if let Some(val) = self.prefer_h1_title {
+            obj.set("preferH1Title", val)
+                .js_context("failed to set preferH1Title option")?;
+        }
+        if let Some(val) = self.max_output_bytes {
+            obj.set("maxOutputBytes", val)
+                .js_context("failed to set maxOutputBytes option")?;
+        }
+        if let Some(val) = self.html_format {
+            let format = match val {
+                HtmlFormat::Pretty => "pretty",
+                HtmlFormat::Minified => "minified",
+            };
+            obj.set("htmlFormat", format)
+                .js_context("failed to set htmlFormat option")?;
+        }
+        if let Some(val) = self.link_rel {
+            let mode = match val {
+                LinkRel::Normalize => "normalize",
+                LinkRel::Strip => "strip",
+            };
+            obj.set("linkRel", mode)
+                .js_context("failed to set linkRel option")?;
+        }
+        if let Some(val) = self.unlink_sponsored {
+            obj.set("unlinkSponsored", val)
+                .js_context("failed to set unlinkSponsored option")?;
+        }
+        if let Some(val) = self.keep_inline_styles {
+            obj.set("keepInlineStyles", val)
+                .js_context("failed to set keepInlineStyles option")?;
+        }
+        if let Some(val) = self.keep_asides_above {
+            obj.set("keepAsidesAbove", val)
+                .js_context("failed to set keepAsidesAbove option")?;
+        }
+        if let Some(val) = self.convert_divs_to_paragraphs {
+            obj.set("convertDivsToParagraphs", val)
+                .js_context("failed to set convertDivsToParagraphs option")?;
+        }
+        if let Some(val) = self.collect_stats {
+            obj.set("collectStats", val)
+                .js_context("failed to set collectStats option")?;
+        }
+        if let Some(val) = self.record_removed {
+            obj.set("recordRemoved", val)
+                .js_context("failed to set recordRemoved option")?;
+        }
         Ok(obj)
     }
 }
 
-// #[derive(Default, Debug, Clone)]
-// struct ReadabilityCheckOptions {
-//     pub min_content_length: Option<usize>, // default 140
-//     pub min_score: Option<usize>,          // default 20
-//                                            // TODO visibility checker
-// }
-
-// impl ReadabilityCheckOptions {
-//     pub fn new() -> Self {
-//         Self::default()
-//     }
-//     pub fn min_content_length(mut self, val: usize) -> Self {
-//         self.min_content_length = Some(val);
-//         self
-//     }
-//     pub fn min_score(mut self, val: usize) -> Self {
-//         self.min_score = Some(val);
-//         self
-//     }
-
-//     fn build<'js>(self, ctx: Ctx<'js>) -> Result<Object<'js>> {
-//         let obj = Object::new(ctx).map_err(|e| ReadabilityError::JsEvaluation {
-//             context: "failed to create check options object".to_string(),
-//             source: e,
-//         })?;
-
-//         if let Some(val) = self.min_content_length {
-//             obj.set("minContentLength", val)
-//                 .map_err(|e| ReadabilityError::JsEvaluation {
-//                     context: "failed to set minContentLength option".to_string(),
-//                     source: e,
-//                 })?
-//         }
-//         if let Some(val) = self.min_score {
-//             obj.set("minScore", val)
-//                 .map_err(|e| ReadabilityError::JsEvaluation {
-//                     context: "failed to set minScore option".to_string(),
-//                     source: e,
-//                 })?;
-//         }
-//         Ok(obj)
-//     }
-// }
-//
-/// Errors that can occur during content extraction.
-#[derive(Error, Debug)]
-pub enum ReadabilityError {
-    /// HTML could not be parsed (malformed, empty, etc.)
-    ///
+/// Configuration for the quick "is this page readerable?" pre-check.
+///
+/// Created with [`ReadabilityCheckOptions::new`] and used with
+/// [`Readability::check_readerable`].
+///
+/// # Examples
+///
+/// ```rust
+/// use readability_js::ReadabilityCheckOptions;
+///
+/// let opts = ReadabilityCheckOptions::new()
+///     .min_content_length(100) // Count shorter paragraphs
+///     .min_score(30.0);        // But require more of them
+/// ```
+#[derive(Default, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ReadabilityCheckOptions {
+    pub min_content_length: Option<usize>,
+    pub min_score: Option<f64>,
+    // TODO visibility checker
+}
+
+impl ReadabilityCheckOptions {
+    /// Creates a new options builder with default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the minimum length of a paragraph for it to count towards the score.
+    ///
+    /// Default is 140 characters.
+    ///
+    /// # Arguments
+    /// * `val` - Minimum paragraph length in characters
+    pub fn min_content_length(mut self, val: usize) -> Self {
+        self.min_content_length = Some(val);
+        self
+    }
+
+    /// Set the score a page needs to exceed to be considered readerable.
+    ///
+    /// Each paragraph longer than `min_content_length` adds the square root
+    /// of its excess length to the score. Default is 20.
+    ///
+    /// # Arguments
+    /// * `val` - Minimum cumulated score
+    pub fn min_score(mut self, val: f64) -> Self {
+        self.min_score = Some(val);
+        self
+    }
+
+    fn build<'js>(self, ctx: Ctx<'js>) -> Result<Object<'js>> {
+        let obj = Object::new(ctx).js_context("failed to create check options object")?;
+
+        if let Some(val) = self.min_content_length {
+            obj.set("minContentLength", val)
+                .js_context("failed to set minContentLength option")?;
+        }
+        if let Some(val) = self.min_score {
+            obj.set("minScore", val)
+                .js_context("failed to set minScore option")?;
+        }
+        Ok(obj)
+    }
+}
+
+/// Outcome of [`Readability::check_readerable`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ReaderableCheck {
+    /// Whether the page likely contains an article worth extracting
+    pub readerable: bool,
+
+    /// Cumulated paragraph score; the page is readerable above `min_score`
+    pub score: f64,
+}
+
+/// Errors that can occur during content extraction.
+#[derive(Error, Debug)]
+pub enum ReadabilityError {
+    /// HTML could not be parsed (malformed, empty, etc.)
+    ///
     /// This typically occurs when:
     /// - HTML is severely malformed or incomplete
     /// - Empty or whitespace-only input
@@ -562,9 +1260,83 @@ pub enum ReadabilityError {
     /// ```
     #[error("Invalid options: {0}")]
     InvalidOptions(String),
+
+    /// Extraction took longer than the configured timeout
+    ///
+    /// Only returned by instances configured with [`Readability::with_timeout`].
+    /// The JavaScript engine is interrupted, so the instance stays usable for
+    /// further documents.
+    #[error("Extraction timed out")]
+    Timeout,
+
+    /// Input exceeded the size limit of [`Readability::parse_hardened`]
+    #[error("Input is {size} bytes, over the {limit} byte limit")]
+    InputTooLarge { size: usize, limit: usize },
+
+    /// The JavaScript heap hit its memory limit
+    ///
+    /// Returned by [`Readability::parse_hardened`], or by instances built with
+    /// a memory limit. The instance replaces its engine afterwards, so it
+    /// stays usable.
+    #[error("Extraction exceeded the memory limit")]
+    MemoryLimitExceeded,
+
+    /// The document has more elements than allowed
+    ///
+    /// Returned when `max_elems_to_parse` or the element limit of
+    /// [`Readability::parse_hardened`] is exceeded.
+    #[error("Document exceeds the element limit: {0}")]
+    ElementLimitExceeded(String),
+
+    /// Reading the input failed
+    ///
+    /// Returned by [`Readability::parse_reader`].
+    #[error("Failed to read input: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// A bug in the engine glue panicked during the call
+    ///
+    /// The panic is caught at the JavaScript boundary so a single document
+    /// can't take the process down; the instance replaces its engine and
+    /// stays usable. Only caught when built with `panic = "unwind"`.
+    #[error("Internal error: {0}")]
+    Internal(String),
+
+    /// An [`Article`] could not be encoded or decoded
+    ///
+    /// Returned by the serialization helpers such as `Article::to_msgpack`,
+    /// typically when decoding bytes written by an incompatible version.
+    #[error("Failed to (de)serialize article: {0}")]
+    Serialization(String),
+
+    /// Downloading the page failed
+    ///
+    /// Returned by `Readability::fetch_and_parse`, `fetch_and_parse_async`
+    /// and the [`Fetcher`](crate::Fetcher) based methods when the request
+    /// fails, the server answers with an error status, or the body exceeds
+    /// `FetchOptions::max_body_bytes`. The [`FetchError`] tells which.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use readability_js::{FetchError, Readability, ReadabilityError};
+    /// # fn fetch(_url: &str) -> Result<readability_js::Article, ReadabilityError> { todo!() }
+    /// match fetch("https://example.com/post") {
+    ///     Err(ReadabilityError::Fetch(FetchError::Status { status: 404, .. })) => {
+    ///         eprintln!("gone, not retrying");
+    ///     }
+    ///     Err(ReadabilityError::Fetch(FetchError::Timeout { url })) => {
+    ///         eprintln!("{} timed out, retrying later", url);
+    ///     }
+    ///     Ok(article) => println!("{}", article.title),
+    ///     Err(e) => eprintln!("{}", e),
+    /// }
+    /// ```
+    #[error("Failed to fetch {0}")]
+    Fetch(#[from] FetchError),
 }
 
-trait JsResultExt<T> {
+pub(crate) trait JsResultExt<T> {
     fn js_context(self, context: &str) -> Result<T>;
 }
 
@@ -577,7 +1349,7 @@ impl<T> JsResultExt<T> for std::result::Result<T, rquickjs::Error> {
     }
 }
 
-type Result<T> = std::result::Result<T, ReadabilityError>;
+pub(crate) type Result<T> = std::result::Result<T, ReadabilityError>;
 
 /// The main readability parser that extracts clean content from HTML.
 ///
@@ -609,7 +1381,10 @@ type Result<T> = std::result::Result<T, ReadabilityError>;
 /// contains an embedded JavaScript engine that cannot be moved between threads or
 /// shared between threads.
 pub struct Readability {
-    context: QuickContext,
+    engine: RefCell<Engine>,
+    timeout: Cell<Option<Duration>>,
+    cache: Option<RefCell<ResultCache>>,
+    default_options: Option<ReadabilityOptions>,
 }
 impl Readability {
     /// Creates a new readability parser.
@@ -625,23 +1400,150 @@ impl Readability {
     /// This method initializes an embedded QuickJS runtime. The JavaScript code
     /// executed is Mozilla's Readability.js library and is considered safe for
     /// processing untrusted HTML input.
+    ///
+    /// Use [`Readability::builder`] to configure the runtime.
     pub fn new() -> Result<Self> {
-        let runtime = Runtime::new().js_context("Failed to create runtime")?;
-        let context = QuickContext::full(&runtime).js_context("Failed to create context")?;
+        Self::builder().build()
+    }
 
-        context.with(|ctx| {
-            let readability_code = include_str!("../vendor/readability/Readability.js");
-            ctx.eval::<(), _>(readability_code)
-                .js_context("Failed to load Readability")?;
+    /// Configure a parser's runtime, limits and defaults before creating it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use readability_js::{Readability, ReadabilityOptions};
+    ///
+    /// let reader = Readability::builder()
+    ///     .memory_limit(256 * 1024 * 1024)
+    ///     .timeout(Duration::from_secs(5))
+    ///     .default_options(ReadabilityOptions::new().char_threshold(300))
+    ///     .build()?;
+    /// # Ok::<(), readability_js::ReadabilityError>(())
+    /// ```
+    pub fn builder() -> ReadabilityBuilder {
+        ReadabilityBuilder::new()
+    }
 
-            let bundle = include_str!("../js/bundled.js");
-            ctx.eval::<(), _>(bundle)
-                .js_context("Failed to load bundle")?;
+    /// Versions of the algorithm and engine this build extracts with.
+    ///
+    /// Store it alongside extractions to trace them back to the
+    /// Readability.js release and scripts that produced them.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use readability_js::{READABILITY_JS_VERSION, Readability};
+    ///
+    /// let info = Readability::engine_info();
+    /// assert_eq!(info.readability_js_version, READABILITY_JS_VERSION);
+    /// println!("bundle {} on QuickJS {}", info.bundle_hash, info.quickjs_version);
+    /// ```
+    pub fn engine_info() -> EngineInfo {
+        EngineInfo::current()
+    }
 
-            Ok(())
-        })?;
+    pub(crate) fn from_parts(
+        engine: Engine,
+        timeout: Option<Duration>,
+        default_options: Option<ReadabilityOptions>,
+    ) -> Self {
+        Self {
+            engine: RefCell::new(engine),
+            timeout: Cell::new(timeout),
+            cache: None,
+            default_options,
+        }
+    }
+
+    /// Returns this thread's shared parser, creating it on first use.
+    ///
+    /// Because `Readability` is `!Send`, servers that handle each request on
+    /// a worker thread cannot hand one instance around. This keeps one
+    /// instance per thread instead, so every request on that thread reuses
+    /// it. The instance uses default settings; build your own with
+    /// [`Readability::new`] to configure a timeout or cache.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use readability_js::Readability;
+    ///
+    /// # let html = "<html><body><p>Content</p></body></html>";
+    /// let article = Readability::thread_local()?.parse(html);
+    /// # Ok::<(), readability_js::ReadabilityError>(())
+    /// ```
+    pub fn thread_local() -> Result<Rc<Self>> {
+        thread_local! {
+            static SHARED: OnceCell<Rc<Readability>> = const { OnceCell::new() };
+        }
+
+        SHARED.with(|shared| {
+            if let Some(reader) = shared.get() {
+                return Ok(reader.clone());
+            }
+            let reader = Rc::new(Self::new()?);
+            Ok(shared.get_or_init(|| reader).clone())
+        })
+    }
+
+    /// Limit how long a single extraction may run.
+    ///
+    /// Extractions exceeding the limit are interrupted and return
+    /// [`ReadabilityError::Timeout`]. Useful when processing untrusted or
+    /// very large documents, where one page could otherwise stall the caller.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use readability_js::Readability;
+    ///
+    /// let reader = Readability::new()?.with_timeout(Duration::from_secs(5));
+    /// # Ok::<(), readability_js::ReadabilityError>(())
+    /// ```
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        self.timeout.set(Some(timeout));
+        self
+    }
+
+    /// Cache up to `capacity` extracted articles.
+    ///
+    /// Results are keyed by the HTML, base URL and options, so repeated
+    /// submissions of the same document skip the extraction entirely. The
+    /// cache keeps a copy of each cached document's HTML to compare against.
+    /// The least recently used article is evicted when the cache is full.
+    /// Failed extractions are never cached.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use readability_js::Readability;
+    ///
+    /// let reader = Readability::new()?.with_cache(1024);
+    /// # let html = "<html><body><p>Content</p></body></html>";
+    /// let first = reader.parse(html);
+    /// let second = reader.parse(html); // served from the cache
+    ///
+    /// let stats = reader.cache_stats().unwrap();
+    /// println!("{} hits, {} misses", stats.hits, stats.misses);
+    /// # Ok::<(), readability_js::ReadabilityError>(())
+    /// ```
+    pub fn with_cache(mut self, capacity: usize) -> Self {
+        self.cache = Some(RefCell::new(ResultCache::new(capacity)));
+        self
+    }
+
+    /// Hit/miss counters of the result cache, if enabled with [`Readability::with_cache`].
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.cache.as_ref().map(|cache| cache.borrow().stats())
+    }
 
-        Ok(Self { context })
+    /// Drop all cached articles, keeping the hit/miss counters.
+    pub fn clear_cache(&self) {
+        if let Some(cache) = &self.cache {
+            cache.borrow_mut().clear();
+        }
     }
 
     fn validate_base_url(url: &str) -> Result<String> {
@@ -778,58 +1680,732 @@ impl Readability {
         self.extract(html, base_url, options)
     }
 
-    fn extract(
+    /// Extract readable content from HTML read from `reader`.
+    ///
+    /// This is not streaming: the whole input is read into memory before
+    /// extraction, but never more than `max_bytes` of it, so a huge or
+    /// endless input fails with [`ReadabilityError::InputTooLarge`] instead
+    /// of exhausting memory. The bytes are decoded like
+    /// [`Readability::parse_with_content_type`] without a header: from a
+    /// byte order mark or `<meta>` charset declaration, defaulting to UTF-8.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - Source of the HTML, such as a file or response body
+    /// * `base_url` - Optional URL for link resolution
+    /// * `max_bytes` - Largest input accepted
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use std::fs::File;
+    /// use readability_js::Readability;
+    ///
+    /// let reader = Readability::new()?;
+    /// let file = File::open("page.html")?;
+    /// let article = reader.parse_reader(file, None, 10 * 1024 * 1024)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Besides the errors of [`Readability::parse_with_options`]:
+    /// * Reading fails ([`ReadabilityError::Io`])
+    /// * The input is larger than `max_bytes` ([`ReadabilityError::InputTooLarge`])
+    pub fn parse_reader(
+        &self,
+        reader: impl Read,
+        base_url: Option<&str>,
+        max_bytes: usize,
+    ) -> Result<Article> {
+        let mut buf = Vec::new();
+        reader
+            .take((max_bytes as u64).saturating_add(1))
+            .read_to_end(&mut buf)?;
+        if buf.len() > max_bytes {
+            return Err(ReadabilityError::InputTooLarge {
+                size: buf.len(),
+                limit: max_bytes,
+            });
+        }
+        self.extract(&content_type::decode(&buf, None), base_url, None)
+    }
+
+    /// Extract readable content from a raw HTTP response body.
+    ///
+    /// Pass the response's `Content-Type` header to decode the body the way a
+    /// browser would: the charset comes from a byte order mark, the header,
+    /// or a `<meta>` declaration, in that order, defaulting to UTF-8, and
+    /// `application/xhtml+xml` bodies get XML syntax like `<div/>` read
+    /// correctly. Every charset label browsers accept is supported, from
+    /// Shift_JIS and GBK to KOI8-R and the ISO-8859 family; unknown labels
+    /// are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `body` - The undecoded response body
+    /// * `content_type` - The `Content-Type` header value, if any
+    /// * `base_url` - Optional URL for link resolution
+    ///
+    /// # Examples
+    /// ```rust
+    /// use readability_js::Readability;
+    ///
+    /// let reader = Readability::new()?;
+    /// # let body: &[u8] = b"<html><body><p>Content</p></body></html>";
+    /// let article = reader.parse_with_content_type(
+    ///     body,
+    ///     Some("text/html; charset=ISO-8859-1"),
+    ///     Some("https://example.com"),
+    /// );
+    /// # Ok::<(), readability_js::ReadabilityError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Readability::parse_with_options`].
+    pub fn parse_with_content_type(
+        &self,
+        body: &[u8],
+        content_type: Option<&str>,
+        base_url: Option<&str>,
+    ) -> Result<Article> {
+        let html = content_type::decode(body, content_type);
+        self.extract(&html, base_url, None)
+    }
+
+    /// Extract readable content from an HTML fragment.
+    ///
+    /// For markup without a surrounding document, such as an email body or a
+    /// CMS rich-text field. The fragment is wrapped in a minimal document
+    /// first, and since it is all meant to be content, the character
+    /// threshold defaults to 0 so short fragments are not rejected. Input
+    /// that already has `<html>` or `<body>` is passed through unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `html` - The HTML fragment to extract from
+    /// * `base_url` - Optional URL for link resolution
+    /// * `options` - Custom parsing options
+    ///
+    /// # Examples
+    /// ```rust
+    /// use readability_js::Readability;
+    ///
+    /// let reader = Readability::new()?;
+    /// let article = reader.parse_fragment("<p>Thanks for the update!</p>", None, None)?;
+    /// assert_eq!(article.text_content.trim(), "Thanks for the update!");
+    /// # Ok::<(), readability_js::ReadabilityError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Readability::parse_with_options`].
+    pub fn parse_fragment(
+        &self,
+        html: &str,
+        base_url: Option<&str>,
+        options: Option<ReadabilityOptions>,
+    ) -> Result<Article> {
+        let options = options
+            .unwrap_or_default()
+            .or(&ReadabilityOptions::new().char_threshold(0));
+        self.extract(&wrap_fragment(html), base_url, Some(options))
+    }
+
+    /// Extract readable content from untrusted HTML under resource limits.
+    ///
+    /// Input size, JavaScript heap growth, element count and run time are
+    /// all capped for this call, whatever the instance is configured with.
+    /// Meant for user-submitted HTML, where one hostile document must not
+    /// take the whole process down.
+    ///
+    /// # Arguments
+    ///
+    /// * `html` - The HTML content to extract from
+    /// * `base_url` - Optional URL for link resolution
+    /// * `limits` - Resource limits for this call
+    ///
+    /// # Examples
+    /// ```rust
+    /// use readability_js::{HardenedLimits, Readability, ReadabilityError};
+    ///
+    /// # let html = "<html><body><p>Content</p></body></html>";
+    /// let reader = Readability::new()?;
+    /// match reader.parse_hardened(html, None, HardenedLimits::default()) {
+    ///     Ok(article) => println!("{}", article.title),
+    ///     Err(ReadabilityError::InputTooLarge { size, .. }) => eprintln!("{} bytes is too much", size),
+    ///     Err(e) => eprintln!("Rejected: {}", e),
+    /// }
+    /// # Ok::<(), readability_js::ReadabilityError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Besides the errors of [`Readability::parse_with_options`]:
+    /// * The input is too large ([`ReadabilityError::InputTooLarge`])
+    /// * The heap grew past the limit ([`ReadabilityError::MemoryLimitExceeded`])
+    /// * The document has too many elements ([`ReadabilityError::ElementLimitExceeded`])
+    /// * The extraction ran too long ([`ReadabilityError::Timeout`])
+    pub fn parse_hardened(
+        &self,
+        html: &str,
+        base_url: Option<&str>,
+        limits: HardenedLimits,
+    ) -> Result<Article> {
+        if html.len() > limits.max_input_bytes {
+            return Err(ReadabilityError::InputTooLarge {
+                size: html.len(),
+                limit: limits.max_input_bytes,
+            });
+        }
+
+        let options = ReadabilityOptions::new().max_elems_to_parse(limits.max_elements);
+        let timeout = self.timeout.replace(Some(limits.timeout));
+        {
+            let engine = self.engine.borrow();
+            let heap_limit = engine
+                .memory_usage()
+                .saturating_add(limits.max_memory_bytes);
+            engine.override_memory_limit(Some(heap_limit));
+        }
+
+        let result = self.extract(html, base_url, Some(options));
+
+        self.engine.borrow().override_memory_limit(None);
+        self.timeout.set(timeout);
+        result
+    }
+
+    /// Quickly check whether a page is likely to contain an article.
+    ///
+    /// This is the same heuristic Firefox uses to decide whether to show the
+    /// Reader Mode button. It is much cheaper than a full extraction, so it is
+    /// useful for filtering large sets of pages before parsing them.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use readability_js::Readability;
+    ///
+    /// let reader = Readability::new()?;
+    /// if reader.is_probably_readerable("<html><body><p>Short</p></body></html>")? {
+    ///     println!("worth extracting");
+    /// }
+    /// # Ok::<(), readability_js::ReadabilityError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReadabilityError`] if the HTML cannot be parsed or
+    /// JavaScript evaluation fails.
+    pub fn is_probably_readerable(&self, html: &str) -> Result<bool> {
+        Ok(self.check_readerable(html, None)?.readerable)
+    }
+
+    /// Run the readerable pre-check with custom options and return its score.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use readability_js::{Readability, ReadabilityCheckOptions};
+    ///
+    /// let reader = Readability::new()?;
+    /// let options = ReadabilityCheckOptions::new().min_score(10.0);
+    /// let check = reader.check_readerable("<html><body><p>Short</p></body></html>", Some(options))?;
+    /// println!("readerable: {} (score {:.1})", check.readerable, check.score);
+    /// # Ok::<(), readability_js::ReadabilityError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReadabilityError`] if the HTML cannot be parsed or
+    /// JavaScript evaluation fails.
+    pub fn check_readerable(
+        &self,
+        html: &str,
+        options: Option<ReadabilityCheckOptions>,
+    ) -> Result<ReaderableCheck> {
+        self.run(|ctx| {
+            let check_fn: Function = ctx
+                .globals()
+                .get("check")
+                .js_context("check function not found")?;
+            let options_obj = match options {
+                None => None,
+                Some(options) => Some(options.build(ctx.clone())?),
+            };
+
+            let result: Value = check_fn
+                .call((html, options_obj))
+                .js_context("Failed to call check")?;
+            if let Some(error) = js_error(&result) {
+                return Err(error);
+            }
+
+            let obj = result.as_object().ok_or_else(|| {
+                ReadabilityError::ExtractionError(
+                    "Expected JavaScript object, got a different type".into(),
+                )
+            })?;
+            Ok(ReaderableCheck {
+                readerable: obj
+                    .get("readerable")
+                    .js_context("failed to get readerable")?,
+                score: obj.get("score").js_context("failed to get score")?,
+            })
+        })
+    }
+
+    /// Find the AMP version of a page, advertised with `<link rel="amphtml">`.
+    ///
+    /// AMP pages are often lighter and cleaner than the canonical page, which
+    /// can make them a better extraction target. A relative link is resolved
+    /// against `base_url` when one is given.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use readability_js::Readability;
+    ///
+    /// let html = r#"<html><head><link rel="amphtml" href="/amp/story"></head></html>"#;
+    /// let reader = Readability::new()?;
+    /// let amp = reader.find_amp_url(html, Some("https://example.com/story"))?;
+    /// assert_eq!(amp.as_deref(), Some("https://example.com/amp/story"));
+    /// # Ok::<(), readability_js::ReadabilityError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReadabilityError::InvalidOptions`] for an invalid base URL, or
+    /// another [`ReadabilityError`] if the HTML cannot be processed.
+    pub fn find_amp_url(&self, html: &str, base_url: Option<&str>) -> Result<Option<String>> {
+        self.find_link(html, base_url, r#"link[rel~="amphtml"][href]"#)
+    }
+
+    /// Find a printer-friendly version of a page.
+    ///
+    /// Looks for `<link rel="alternate" media="print">` first, then for links
+    /// following common print URL patterns such as `?print=1` or `/print/`.
+    /// Print versions usually drop navigation and ads, which makes them a
+    /// cleaner extraction target. A relative link is resolved against
+    /// `base_url` when one is given; only HTTP(S) links are returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use readability_js::Readability;
+    ///
+    /// let html = r#"<html><body><a href="?print=1">Print</a></body></html>"#;
+    /// let reader = Readability::new()?;
+    /// let print = reader.find_print_url(html, Some("https://example.com/story"))?;
+    /// assert_eq!(print.as_deref(), Some("https://example.com/story?print=1"));
+    /// # Ok::<(), readability_js::ReadabilityError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReadabilityError::InvalidOptions`] for an invalid base URL, or
+    /// another [`ReadabilityError`] if the HTML cannot be processed.
+    pub fn find_print_url(&self, html: &str, base_url: Option<&str>) -> Result<Option<String>> {
+        const PRINT_SELECTORS: [&str; 2] = [
+            r#"link[rel~="alternate"][media="print"][href]"#,
+            r#"a[href*="print=1"], a[href*="print=true"], a[href*="print=yes"],
+               a[href*="view=print"], a[href*="output=print"], a[href*="/print/"]"#,
+        ];
+
+        for selector in PRINT_SELECTORS {
+            if let Some(href) = self.find_link(html, base_url, selector)? {
+                let is_http = url::Url::parse(&href)
+                    .map(|url| matches!(url.scheme(), "http" | "https"))
+                    // Unresolved relative link without a base URL
+                    .unwrap_or(base_url.is_none());
+                if is_http {
+                    return Ok(Some(href));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Render an article as reStructuredText.
+    ///
+    /// The title becomes the document title, followed by the content with
+    /// headings, emphasis, links, lists, literal blocks, images and tables
+    /// converted to their RST equivalents, ready to be archived alongside
+    /// Sphinx documentation.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use readability_js::{Article, Readability};
+    ///
+    /// let article = Article {
+    ///     title: "Hello".into(),
+    ///     content: "<p>Some <b>bold</b> text</p>".into(),
+    ///     ..Default::default()
+    /// };
+    /// let reader = Readability::new()?;
+    /// let rst = reader.to_rst(&article)?;
+    /// assert_eq!(rst, "=====\nHello\n=====\n\nSome **bold** text\n");
+    /// # Ok::<(), readability_js::ReadabilityError>(())
+    /// ```
+    pub fn to_rst(&self, article: &Article) -> Result<String> {
+        self.run(|ctx| {
+            let to_rst_fn: Function = ctx
+                .globals()
+                .get("toRst")
+                .js_context("toRst function not found")?;
+            let result: Value = to_rst_fn
+                .call((article.title.as_str(), article.content.as_str()))
+                .js_context("Failed to call toRst")?;
+            if let Some(error) = js_error(&result) {
+                return Err(error);
+            }
+            result
+                .get::<String>()
+                .js_context("failed to get reStructuredText as string")
+        })
+    }
+
+    /// The visible text of an HTML document or fragment, one entry per block.
+    ///
+    /// Paragraphs, headings, list items, table cells and the like each become
+    /// one entry with whitespace collapsed; scripts, styles and empty blocks
+    /// are skipped. Running it over both the input and [`Article::content`]
+    /// shows which parts of a page made it into the article.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use readability_js::Readability;
+    ///
+    /// let reader = Readability::new()?;
+    /// let blocks = reader.text_blocks("<h1>Title</h1><p>Some <b>bold</b>\n text</p>")?;
+    /// assert_eq!(blocks, ["Title", "Some bold text"]);
+    /// # Ok::<(), readability_js::ReadabilityError>(())
+    /// ```
+    pub fn text_blocks(&self, html: &str) -> Result<Vec<String>> {
+        let html = wrap_fragment(html);
+        self.run(|ctx| {
+            let text_blocks_fn: Function = ctx
+                .globals()
+                .get("textBlocks")
+                .js_context("textBlocks function not found")?;
+            let result: Value = text_blocks_fn
+                .call((&*html,))
+                .js_context("Failed to call textBlocks")?;
+            if let Some(error) = js_error(&result) {
+                return Err(error);
+            }
+            result
+                .get::<Vec<String>>()
+                .js_context("failed to get text blocks")
+        })
+    }
+
+    /// Return the `href` of the first element matching `selector`, resolved against `base_url`.
+    pub(crate) fn find_link(
+        &self,
+        html: &str,
+        base_url: Option<&str>,
+        selector: &str,
+    ) -> Result<Option<String>> {
+        let base_url =
+            match base_url {
+                None => None,
+                Some(url) => Some(url::Url::parse(&Self::validate_base_url(url)?).map_err(
+                    |_| ReadabilityError::InvalidOptions("Base URL must be HTTP(S)".into()),
+                )?),
+            };
+
+        let href = self.run(|ctx| {
+            let find_link_fn: Function = ctx
+                .globals()
+                .get("findLink")
+                .js_context("findLink function not found")?;
+            let result: Value = find_link_fn
+                .call((html, selector))
+                .js_context("Failed to call findLink")?;
+            if let Some(error) = js_error(&result) {
+                return Err(error);
+            }
+            if result.is_null() || result.is_undefined() {
+                return Ok(None);
+            }
+            Ok(Some(
+                result
+                    .get::<String>()
+                    .js_context("failed to get link href as string")?,
+            ))
+        })?;
+
+        Ok(href.map(|href| {
+            match base_url
+                .as_ref()
+                .and_then(|base| base.join(href.trim()).ok())
+            {
+                Some(url) => url.to_string(),
+                None => href,
+            }
+        }))
+    }
+
+    /// Run the JavaScript garbage collector now.
+    ///
+    /// Useful between batches on long-lived instances, to release memory
+    /// from earlier documents at a convenient moment instead of mid-parse.
+    pub fn run_gc(&self) {
+        self.engine.borrow().run_gc();
+    }
+
+    /// Bytes currently allocated by the JavaScript heap.
+    pub fn memory_usage(&self) -> usize {
+        self.engine.borrow().memory_usage()
+    }
+
+    /// Run `f` inside the JavaScript context, enforcing the configured timeout.
+    fn run<T>(&self, f: impl for<'js> FnOnce(Ctx<'js>) -> Result<T>) -> Result<T> {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            self.engine.borrow().run(self.timeout.get(), f)
+        }))
+        .unwrap_or_else(|payload| Err(ReadabilityError::Internal(panic_message(&*payload))));
+
+        if matches!(
+            result,
+            Err(ReadabilityError::MemoryLimitExceeded | ReadabilityError::Internal(_))
+        ) {
+            // The engine may be left in a poor state, so start over
+            self.recycle();
+        } else if self.engine.borrow().needs_recycle() {
+            self.recycle();
+        }
+        result
+    }
+
+    /// Swap in a fresh engine.
+    fn recycle(&self) {
+        // On failure keep the current engine; the next call tries again
+        let fresh = self.engine.borrow().recycled();
+        if let Ok(engine) = fresh {
+            *self.engine.borrow_mut() = engine;
+        }
+    }
+
+    pub(crate) fn extract(
         &self,
         html: &str,
         base_url: Option<&str>,
         options: Option<ReadabilityOptions>,
     ) -> Result<Article> {
+        let options = self.with_default_options(options);
+        let Some(cache) = &self.cache else {
+            return self.extract_uncached(html, base_url, options, None);
+        };
+
+        let key = cache::Key::new(html, base_url, options.as_ref());
+        if let Some(article) = cache.borrow_mut().get(&key) {
+            return Ok(article);
+        }
+        let article = self.extract_uncached(html, base_url, options, None)?;
+        cache.borrow_mut().insert(key, article.clone());
+        Ok(article)
+    }
+
+    /// Fill unset options from the ones given to the builder.
+    pub(crate) fn with_default_options(
+        &self,
+        options: Option<ReadabilityOptions>,
+    ) -> Option<ReadabilityOptions> {
+        match (options, &self.default_options) {
+            (Some(options), Some(defaults)) => Some(options.or(defaults)),
+            (options, defaults) => options.or_else(|| defaults.clone()),
+        }
+    }
+
+    /// Extract without the cache, tracing candidate scores into `candidates`
+    /// when given.
+    pub(crate) fn extract_uncached(
+        &self,
+        html: &str,
+        base_url: Option<&str>,
+        options: Option<ReadabilityOptions>,
+        candidates: Option<&mut Vec<CandidateScore>>,
+    ) -> Result<Article> {
+        if let Some(options) = &options {
+            options.validate()?;
+        }
         let clean_base_url = match base_url {
-            None => None,
+            // SingleFile records where the page came from
+            None => {
+                data_uri::singlefile_url(html).and_then(|url| Self::validate_base_url(url).ok())
+            }
             Some(url) => Some(Self::validate_base_url(url)?),
         };
-        self.context.with(|ctx| {
+        let input_bytes = html.len();
+        let html = match options.as_ref().and_then(|o| o.strip_data_uris_above) {
+            Some(max_bytes) => data_uri::strip_large(html, max_bytes),
+            None => Cow::Borrowed(html),
+        };
+        let clean_title = options
+            .as_ref()
+            .and_then(|o| o.clean_title)
+            .unwrap_or(false);
+        let generate_excerpt = options.as_ref().and_then(|o| o.generate_excerpt);
+        let whitespace = options
+            .as_ref()
+            .map_or_else(Default::default, |o| text::Whitespace {
+                collapse: o.collapse_whitespace.unwrap_or(false),
+                trim_lines: o.trim_lines.unwrap_or(false),
+                normalize_spaces: o.normalize_spaces.unwrap_or(false),
+            });
+        let max_data_uri_bytes = options.as_ref().and_then(|o| o.max_data_uri_bytes);
+        self.run(|ctx| {
             let extract_fn: Function = ctx
                 .globals()
                 .get("extract")
                 .js_context("extract function not found")?;
-            let options_obj = match options {
-                None => None,
-                Some(options) => Some(options.build(ctx.clone())?),
+            let options_obj = match (options, &candidates) {
+                (Some(options), _) => Some(options.build(ctx.clone())?),
+                (None, Some(_)) => Some(ReadabilityOptions::new().build(ctx.clone())?),
+                (None, None) => None,
             };
+            if let (Some(obj), Some(_)) = (&options_obj, &candidates) {
+                obj.set("explain", true)
+                    .js_context("failed to set explain option")?;
+            }
 
+            let started = Instant::now();
             let result: Value = extract_fn
-                .call((html, clean_base_url, options_obj))
+                .call((&*html, clean_base_url.as_deref(), options_obj))
                 .js_context("Failed to call extract")?;
+            let js_eval_ms = started.elapsed().as_secs_f64() * 1000.0;
 
             // Check if result is an error object
-            if let Some(obj) = result.as_object()
-                && let Ok(error_type) = obj.get::<_, String>("errorType")
-            {
-                let error_msg = obj
-                    .get::<_, String>("error")
-                    .unwrap_or_else(|_| "Unknown error".to_string());
-
-                return Err(match error_type.as_str() {
-                    "HtmlParseError" => ReadabilityError::HtmlParseError(error_msg),
-                    "ExtractionError" => ReadabilityError::ExtractionError(error_msg),
-                    "RuntimeError" => ReadabilityError::JsEvaluation {
-                        context: format!("JavaScript runtime error: {}", error_msg),
-                        source: rquickjs::Error::Unknown,
-                    },
-                    _ => ReadabilityError::ExtractionError(format!(
-                        "Unknown error type '{}': {}",
-                        error_type, error_msg
-                    )),
-                });
+            if let Some(error) = js_error(&result) {
+                return Err(error);
+            }
+
+            if let Some(candidates) = candidates {
+                *candidates = explain::candidates(&result)?;
             }
 
             // If not an error object, try to parse as Article
-            Article::try_from(result)
+            let started = Instant::now();
+            let mut article = Article::try_from(result)?;
+            if let Some(stats) = &mut article.stats {
+                stats.js_eval_ms = js_eval_ms;
+                stats.serialize_ms = started.elapsed().as_secs_f64() * 1000.0;
+            }
+            Ok(article)
+        })
+        .map(|mut article| {
+            if let Some(base) = clean_base_url
+                .as_deref()
+                .and_then(|u| url::Url::parse(u).ok())
+            {
+                resolve_urls(&mut article, &base);
+            }
+            if clean_title {
+                article.title = title::clean_title(&article.title, article.site_name.as_deref());
+            }
+            if whitespace.is_enabled() {
+                article.text_content = whitespace.apply(&article.text_content);
+            }
+            if let Some(max_chars) = generate_excerpt
+                && article
+                    .excerpt
+                    .as_deref()
+                    .is_none_or(|e| e.trim().is_empty())
+            {
+                article.excerpt = text::excerpt(&article.text_content, max_chars);
+            }
+            if let Some(max_bytes) = max_data_uri_bytes {
+                if let Cow::Owned(content) = data_uri::strip_large(&article.content, max_bytes) {
+                    article.content = content;
+                }
+                if article
+                    .lead_image_url
+                    .as_ref()
+                    .is_some_and(|url| url.starts_with("data:") && url.len() > max_bytes)
+                {
+                    article.lead_image_url = None;
+                    article.lead_image_width = None;
+                    article.lead_image_height = None;
+                }
+            }
+            if let Some(stats) = &mut article.stats {
+                stats.input_bytes = input_bytes;
+                stats.output_bytes = article.content.len();
+            }
+            article
         })
     }
 }
 
+/// Wrap a fragment in a minimal document, unless it already is one.
+fn wrap_fragment(html: &str) -> Cow<'_, str> {
+    let lower = html.to_ascii_lowercase();
+    if lower.contains("<html") || lower.contains("<body") {
+        Cow::Borrowed(html)
+    } else {
+        Cow::Owned(format!(
+            "<!DOCTYPE html><html><head></head><body>{}</body></html>",
+            html
+        ))
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic");
+    format!("panicked in the JavaScript engine: {}", message)
+}
+
+/// Resolve metadata URLs, which pages often give relative to themselves.
+fn resolve_urls(article: &mut Article, base: &url::Url) {
+    let resolve = |url: &mut Option<String>| {
+        if let Some(src) = url
+            && let Ok(resolved) = base.join(src)
+        {
+            *src = resolved.to_string();
+        }
+    };
+    resolve(&mut article.lead_image_url);
+    resolve(&mut article.site_icon_url);
+
+    // Browsers look for /favicon.ico when a page declares no icon
+    if article.site_icon_url.is_none() {
+        article.site_icon_url = base.join("/favicon.ico").ok().map(String::from);
+    }
+}
+
+/// Convert the `{ errorType, error }` objects returned by the JS entry points.
+fn js_error(result: &Value) -> Option<ReadabilityError> {
+    let obj = result.as_object()?;
+    let error_type = obj.get::<_, String>("errorType").ok()?;
+    let error_msg = obj
+        .get::<_, String>("error")
+        .unwrap_or_else(|_| "Unknown error".to_string());
+
+    Some(match error_type.as_str() {
+        "HtmlParseError" => ReadabilityError::HtmlParseError(error_msg),
+        "ExtractionError" => ReadabilityError::ExtractionError(error_msg),
+        "ElementLimitExceeded" => ReadabilityError::ElementLimitExceeded(error_msg),
+        "MemoryLimitExceeded" => ReadabilityError::MemoryLimitExceeded,
+        "RuntimeError" => ReadabilityError::JsEvaluation {
+            context: format!("JavaScript runtime error: {}", error_msg),
+            source: rquickjs::Error::Unknown,
+        },
+        _ => ReadabilityError::ExtractionError(format!(
+            "Unknown error type '{}': {}",
+            error_type, error_msg
+        )),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -862,4 +2438,104 @@ mod tests {
         assert!(!article.text_content.contains("<"));
         assert!(article.length > 0);
     }
+
+    #[test]
+    fn test_options_fall_back_to_defaults() {
+        let defaults = ReadabilityOptions::new()
+            .char_threshold(300)
+            .keep_classes(true);
+        let options = ReadabilityOptions::new().char_threshold(100).or(&defaults);
+
+        assert_eq!(options.char_threshold, Some(100));
+        assert_eq!(options.keep_classes, Some(true));
+        assert_eq!(options.nb_top_candidates, None);
+    }
+
+    #[test]
+    fn test_validate_options() {
+        assert!(ReadabilityOptions::new().validate().is_ok());
+        assert!(ReadabilityOptions::preset_news().validate().is_ok());
+        assert!(ReadabilityOptions::preset_forum().validate().is_ok());
+
+        let message = |options: ReadabilityOptions| match options.validate() {
+            Err(ReadabilityError::InvalidOptions(message)) => message,
+            other => panic!("expected InvalidOptions, got {:?}", other),
+        };
+        assert_eq!(
+            message(ReadabilityOptions::new().nb_top_candidates(100_000)),
+            "nb_top_candidates must be between 1 and 1000, got 100000"
+        );
+        assert!(
+            message(ReadabilityOptions::new().link_density_modifier(f32::NAN))
+                .starts_with("link_density_modifier")
+        );
+        assert_eq!(
+            message(ReadabilityOptions::new().classes_to_preserve(vec!["a".into(), " ".into()])),
+            "classes_to_preserve[1] is empty"
+        );
+        assert!(
+            message(ReadabilityOptions::new().generate_excerpt(0)).starts_with("generate_excerpt")
+        );
+    }
+
+    #[test]
+    fn test_wrap_fragment() {
+        assert_eq!(
+            wrap_fragment("<p>Hi</p>"),
+            "<!DOCTYPE html><html><head></head><body><p>Hi</p></body></html>"
+        );
+        let document = "<HTML><body><p>Hi</p></body></HTML>";
+        assert_eq!(wrap_fragment(document), document);
+    }
+
+    #[test]
+    fn test_text_blocks() {
+        let readability = Readability::new().unwrap();
+        let blocks = readability
+            .text_blocks(
+                "<html><head><title>Skipped</title></head><body>\
+                 <script>var skipped;</script>\
+                 <div>Intro <a href=\"#\">link</a><p>First\n  paragraph</p>tail</div>\
+                 <ul><li>One</li><li></li><li>Two<br>lines</li></ul></body></html>",
+            )
+            .unwrap();
+
+        assert_eq!(
+            blocks,
+            [
+                "Intro link",
+                "First paragraph",
+                "tail",
+                "One",
+                "Two",
+                "lines"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_panic_in_engine_becomes_error() {
+        let readability = Readability::new().unwrap();
+        let result = readability.run(|_| -> Result<()> { panic!("boom") });
+
+        assert!(matches!(result, Err(ReadabilityError::Internal(msg)) if msg.contains("boom")));
+        assert!(
+            readability
+                .run(|ctx| Ok(ctx.eval::<i32, _>("1 + 1").unwrap()))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_hardened_memory_limit_can_be_unbounded() {
+        let readability = Readability::new().unwrap();
+        let limits = HardenedLimits::new().max_memory_bytes(usize::MAX);
+        let result =
+            readability.parse_hardened("<html><body><p>Content</p></body></html>", None, limits);
+
+        assert!(!matches!(
+            result,
+            Err(ReadabilityError::MemoryLimitExceeded)
+        ));
+    }
 }
@@ -0,0 +1,119 @@
+use std::fmt;
+
+use crate::readability::Article;
+use crate::text;
+
+/// Average adult silent reading speed, used for the reading time estimate.
+const WORDS_PER_MINUTE: usize = 230;
+
+/// Line width used by the [`Display`](fmt::Display) implementation.
+const DISPLAY_WIDTH: usize = 80;
+
+impl Article {
+    /// Render the article as plain text for a terminal `width` columns wide.
+    ///
+    /// The output starts with a header (title, byline and site, reading time)
+    /// followed by the text content, one wrapped paragraph per block. Words
+    /// longer than `width`, such as URLs, are kept on their own line rather
+    /// than broken.
+    pub fn render_terminal(&self, width: usize) -> String {
+        let width = width.max(20);
+        let mut out = String::new();
+
+        let title = text::collapse_whitespace(&self.title);
+        if !title.is_empty() {
+            wrap_into(&mut out, &title, width);
+            out.push_str(&"=".repeat(title.chars().count().min(width)));
+            out.push('\n');
+        }
+
+        let source: Vec<&str> = [self.byline.as_deref(), self.site_name.as_deref()]
+            .into_iter()
+            .flatten()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .collect();
+        let words = self.text_content.split_whitespace().count();
+        let minutes = words.div_ceil(WORDS_PER_MINUTE).max(1);
+        let mut header = source.join(" · ");
+        if !header.is_empty() {
+            header.push_str(" · ");
+        }
+        header.push_str(&format!("{} min read", minutes));
+        wrap_into(&mut out, &header, width);
+
+        for paragraph in self.paragraphs() {
+            out.push('\n');
+            wrap_into(&mut out, &paragraph, width);
+        }
+        out
+    }
+
+    /// The text content as plain paragraphs separated by blank lines.
+    ///
+    /// Whitespace within a paragraph is collapsed and lines are not wrapped,
+    /// which suits text-to-speech engines and LLM ingestion better than
+    /// Markdown.
+    pub fn plain_text(&self) -> String {
+        let mut out = self.paragraphs().collect::<Vec<_>>().join("\n\n");
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out
+    }
+
+    fn paragraphs(&self) -> impl Iterator<Item = String> + '_ {
+        self.text_content
+            .split('\n')
+            .map(text::collapse_whitespace)
+            .filter(|p| !p.is_empty())
+    }
+}
+
+impl fmt::Display for Article {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render_terminal(DISPLAY_WIDTH))
+    }
+}
+
+/// Greedy word wrap of `text` into lines of at most `width` characters.
+fn wrap_into(out: &mut String, text: &str, width: usize) {
+    let mut line_len = 0;
+    for word in text.split_whitespace() {
+        let len = word.chars().count();
+        if line_len > 0 && line_len + 1 + len > width {
+            out.push('\n');
+            line_len = 0;
+        }
+        if line_len > 0 {
+            out.push(' ');
+            line_len += 1;
+        }
+        out.push_str(word);
+        line_len += len;
+    }
+    out.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_terminal_wraps_paragraphs() {
+        let article = Article {
+            title: "A Title".into(),
+            byline: Some("Jane Doe".into()),
+            text_content: "  one two three four five\n\n   six seven  ".into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            article.render_terminal(22),
+            "A Title\n=======\nJane Doe · 1 min read\n\none two three four\nfive\n\nsix seven\n"
+        );
+        assert_eq!(
+            article.plain_text(),
+            "one two three four five\n\nsix seven\n"
+        );
+    }
+}
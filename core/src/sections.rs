@@ -0,0 +1,389 @@
+use crate::readability::Article;
+use crate::text;
+
+/// Elements that never have a closing tag.
+const VOID_TAGS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Elements that start a new line in [`Section::text`].
+const BLOCK_TAGS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "br",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "figcaption",
+    "figure",
+    "footer",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "td",
+    "th",
+    "tr",
+    "ul",
+];
+
+/// A part of an article under one heading, see [`Article::sections`].
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Section {
+    /// Text of the heading, or `None` for the content before the first heading
+    pub heading: Option<String>,
+    /// Heading level from 1 for `<h1>` to 6 for `<h6>`, or 0 without a heading
+    pub level: u8,
+    /// HTML of the content under the heading, without the heading itself
+    pub html: String,
+    /// Plain text of the content, one line per block
+    pub text: String,
+}
+
+impl Article {
+    /// Split the content into sections at its headings.
+    ///
+    /// Every `<h1>` to `<h6>` starts a new section, whatever its level, so
+    /// the result is flat; use [`Section::level`] to rebuild the outline.
+    /// Content before the first heading becomes a section without one.
+    /// Wrapper elements that span several sections are left out, so each
+    /// section's HTML stands on its own. Useful for paginating long reads or
+    /// indexing sections separately.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use readability_js::Article;
+    ///
+    /// let article = Article {
+    ///     content: "<div><p>Intro</p><h2>Setup</h2><p>Install it.</p></div>".into(),
+    ///     ..Default::default()
+    /// };
+    /// let sections = article.sections();
+    /// assert_eq!(sections[0].heading, None);
+    /// assert_eq!(sections[1].heading.as_deref(), Some("Setup"));
+    /// assert_eq!(sections[1].html, "<p>Install it.</p>");
+    /// ```
+    pub fn sections(&self) -> Vec<Section> {
+        let tokens = tokenize(&self.content);
+        let mut sections = Vec::new();
+        let mut heading = None;
+        let mut level = 0;
+        let mut body = Vec::new();
+
+        let mut i = 0;
+        while i < tokens.len() {
+            let Some(next_level) = heading_level(&tokens[i]) else {
+                body.push(tokens[i]);
+                i += 1;
+                continue;
+            };
+            let name = tokens[i].name();
+            let end = tokens[i..]
+                .iter()
+                .position(|t| {
+                    matches!(t, Token::Close { .. }) && t.name().eq_ignore_ascii_case(name)
+                })
+                .map_or(tokens.len(), |end| i + end);
+            push_section(&mut sections, heading.take(), level, &body);
+            heading = Some(text::collapse_whitespace(&plain_text(&tokens[i + 1..end])));
+            level = next_level;
+            body.clear();
+            i = end + 1;
+        }
+        push_section(&mut sections, heading, level, &body);
+        sections
+    }
+}
+
+fn push_section(sections: &mut Vec<Section>, heading: Option<String>, level: u8, body: &[Token]) {
+    let body = balanced(body);
+    let html: String = body.iter().map(|t| t.raw()).collect();
+    let html = html.trim();
+    if heading.is_none() && html.is_empty() {
+        return;
+    }
+    sections.push(Section {
+        heading,
+        level,
+        html: html.to_string(),
+        text: plain_text(&body),
+    });
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Token<'a> {
+    Text(&'a str),
+    Open {
+        name: &'a str,
+        raw: &'a str,
+    },
+    Close {
+        name: &'a str,
+        raw: &'a str,
+    },
+    /// Void and self-closing elements, comments and doctypes
+    Single {
+        name: &'a str,
+        raw: &'a str,
+    },
+}
+
+impl<'a> Token<'a> {
+    fn raw(&self) -> &'a str {
+        match *self {
+            Token::Text(raw) => raw,
+            Token::Open { raw, .. } | Token::Close { raw, .. } | Token::Single { raw, .. } => raw,
+        }
+    }
+
+    fn name(&self) -> &'a str {
+        match *self {
+            Token::Text(_) => "",
+            Token::Open { name, .. } | Token::Close { name, .. } | Token::Single { name, .. } => {
+                name
+            }
+        }
+    }
+}
+
+fn heading_level(token: &Token) -> Option<u8> {
+    let Token::Open { name, .. } = token else {
+        return None;
+    };
+    match name.as_bytes() {
+        [b'h' | b'H', level @ b'1'..=b'6'] => Some(level - b'0'),
+        _ => None,
+    }
+}
+
+/// Split serialized HTML into tags and text.
+fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            tokens.push(Token::Text(rest));
+            break;
+        };
+        if start > 0 {
+            tokens.push(Token::Text(&rest[..start]));
+            rest = &rest[start..];
+        }
+        let end = if rest.starts_with("<!--") {
+            rest.find("-->").map_or(rest.len(), |end| end + 3)
+        } else {
+            tag_end(rest)
+        };
+        let raw = &rest[..end];
+        rest = &rest[end..];
+
+        let closing = raw.starts_with("</");
+        let name_start = if closing { 2 } else { 1 };
+        let name_len = raw[name_start..]
+            .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+            .unwrap_or(raw.len() - name_start);
+        let name = &raw[name_start..name_start + name_len];
+        tokens.push(if closing {
+            Token::Close { name, raw }
+        } else if raw.starts_with("<!")
+            || raw.ends_with("/>")
+            || VOID_TAGS.iter().any(|tag| tag.eq_ignore_ascii_case(name))
+        {
+            Token::Single { name, raw }
+        } else {
+            Token::Open { name, raw }
+        });
+    }
+    tokens
+}
+
+/// Length of the tag at the start of `html`, skipping `>` in quoted values.
+fn tag_end(html: &str) -> usize {
+    let mut quote = None;
+    for (i, c) in html.char_indices().skip(1) {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if q == c => quote = None,
+            (None, '>') => return i + 1,
+            _ => {}
+        }
+    }
+    html.len()
+}
+
+/// Drop tags whose partner ended up in another section.
+fn balanced<'a>(tokens: &[Token<'a>]) -> Vec<Token<'a>> {
+    let mut keep = vec![true; tokens.len()];
+    let mut open: Vec<usize> = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Open { .. } => open.push(i),
+            Token::Close { name, .. } => {
+                match open
+                    .iter()
+                    .rposition(|&o| tokens[o].name().eq_ignore_ascii_case(name))
+                {
+                    // Anything opened after the match was closed implicitly
+                    Some(at) => open.truncate(at),
+                    None => keep[i] = false,
+                }
+            }
+            _ => {}
+        }
+    }
+    for i in open {
+        keep[i] = false;
+    }
+    tokens
+        .iter()
+        .zip(keep)
+        .filter_map(|(token, keep)| keep.then_some(*token))
+        .collect()
+}
+
+/// Text of `tokens`, one line per block with whitespace collapsed.
+fn plain_text(tokens: &[Token]) -> String {
+    let mut raw = String::new();
+    for token in tokens {
+        match token {
+            Token::Text(text) => raw.push_str(&decode_entities(text)),
+            token
+                if BLOCK_TAGS
+                    .iter()
+                    .any(|tag| tag.eq_ignore_ascii_case(token.name())) =>
+            {
+                raw.push('\n')
+            }
+            _ => {}
+        }
+    }
+    raw.lines()
+        .map(text::collapse_whitespace)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Decode the character references an HTML serializer emits.
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest.find(';').filter(|&end| end <= 10).and_then(|end| {
+            let c = match &rest[1..end] {
+                "amp" => '&',
+                "lt" => '<',
+                "gt" => '>',
+                "quot" => '"',
+                "apos" => '\'',
+                "nbsp" => '\u{a0}',
+                entity => {
+                    let code = match entity.strip_prefix("#x").or(entity.strip_prefix("#X")) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                        None => entity.strip_prefix('#')?.parse().ok()?,
+                    };
+                    char::from_u32(code)?
+                }
+            };
+            Some((c, end + 1))
+        });
+        match decoded {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn article(content: &str) -> Article {
+        Article {
+            content: content.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_sections_split_at_headings() {
+        let sections = article(
+            "<div id=\"readability-page-1\" class=\"page\"><div>\
+             <p>Lead &amp; intro</p>\
+             <h2 id=\"one\">First <em>part</em></h2><p>A</p><ul><li>B</li><li>C</li></ul>\
+             <section><h3>Detail</h3><p>D<br>E</p></section>\
+             </div></div>",
+        )
+        .sections();
+
+        assert_eq!(
+            sections,
+            [
+                Section {
+                    heading: None,
+                    level: 0,
+                    html: "<p>Lead &amp; intro</p>".into(),
+                    text: "Lead & intro".into(),
+                },
+                Section {
+                    heading: Some("First part".into()),
+                    level: 2,
+                    html: "<p>A</p><ul><li>B</li><li>C</li></ul>".into(),
+                    text: "A\nB\nC".into(),
+                },
+                Section {
+                    heading: Some("Detail".into()),
+                    level: 3,
+                    html: "<p>D<br>E</p>".into(),
+                    text: "D\nE".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sections_without_headings() {
+        let sections = article("<div><p title=\"a > b\">Only text</p></div>").sections();
+        assert_eq!(sections.len(), 1);
+        assert_eq!(
+            sections[0].html,
+            "<div><p title=\"a > b\">Only text</p></div>"
+        );
+        assert!(article("").sections().is_empty());
+    }
+
+    #[test]
+    fn test_decode_entities() {
+        assert_eq!(
+            decode_entities("a &lt;b&gt; &#233;&#x263A; &bogus; & c"),
+            "a <b> é☺ &bogus; & c"
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use crate::readability::{Article, ReadabilityError};
+
+/// Version of the JSON layout written by [`Article::to_json`].
+///
+/// Bumped whenever a field is renamed or changes meaning; adding optional
+/// fields does not change it.
+#[cfg(feature = "json")]
+pub const ARTICLE_SCHEMA_VERSION: u32 = 1;
+
+#[cfg(feature = "json")]
+#[derive(serde::Serialize)]
+struct VersionedRef<'a> {
+    schema_version: u32,
+    #[serde(flatten)]
+    article: &'a Article,
+}
+
+#[cfg(feature = "json")]
+#[derive(serde::Deserialize)]
+struct Versioned {
+    schema_version: Option<u32>,
+    #[serde(flatten)]
+    article: Article,
+}
+
+#[cfg(feature = "json")]
+impl Article {
+    /// Encode the article as JSON, tagged with [`ARTICLE_SCHEMA_VERSION`].
+    pub fn to_json(&self) -> Result<String, ReadabilityError> {
+        serde_json::to_string(&VersionedRef {
+            schema_version: ARTICLE_SCHEMA_VERSION,
+            article: self,
+        })
+        .map_err(|e| ReadabilityError::Serialization(e.to_string()))
+    }
+
+    /// Decode an article from JSON.
+    ///
+    /// Accepts output of [`Article::to_json`] as well as plain serde JSON
+    /// without a `schema_version`. Articles written by a newer schema version
+    /// are rejected rather than silently misread.
+    pub fn from_json(json: &str) -> Result<Self, ReadabilityError> {
+        let versioned: Versioned = serde_json::from_str(json)
+            .map_err(|e| ReadabilityError::Serialization(e.to_string()))?;
+        match versioned.schema_version {
+            Some(version) if version > ARTICLE_SCHEMA_VERSION => {
+                Err(ReadabilityError::Serialization(format!(
+                    "unsupported schema version {} (newest supported is {})",
+                    version, ARTICLE_SCHEMA_VERSION
+                )))
+            }
+            _ => Ok(versioned.article),
+        }
+    }
+}
+
+#[cfg(feature = "msgpack")]
+impl Article {
+    /// Encode the article as MessagePack.
+    ///
+    /// Fields are written by name, so articles stay readable after fields
+    /// are added in later versions.
+    pub fn to_msgpack(&self) -> Result<Vec<u8>, ReadabilityError> {
+        rmp_serde::to_vec_named(self).map_err(|e| ReadabilityError::Serialization(e.to_string()))
+    }
+
+    /// Decode an article written by [`Article::to_msgpack`].
+    pub fn from_msgpack(bytes: &[u8]) -> Result<Self, ReadabilityError> {
+        rmp_serde::from_slice(bytes).map_err(|e| ReadabilityError::Serialization(e.to_string()))
+    }
+}
+
+#[cfg(feature = "cbor")]
+impl Article {
+    /// Encode the article as CBOR (RFC 8949).
+    pub fn to_cbor(&self) -> Result<Vec<u8>, ReadabilityError> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(self, &mut bytes)
+            .map_err(|e| ReadabilityError::Serialization(e.to_string()))?;
+        Ok(bytes)
+    }
+
+    /// Decode an article written by [`Article::to_cbor`].
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self, ReadabilityError> {
+        ciborium::from_reader(bytes).map_err(|e| ReadabilityError::Serialization(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::readability::{Article, Direction, ReadabilityError};
+
+    fn article() -> Article {
+        Article {
+            title: "Title".into(),
+            content: "<p>Body</p>".into(),
+            text_content: "Body".into(),
+            length: 4,
+            direction: Some(Direction::Rtl),
+            quality: Some(0.75),
+            lead_image_width: Some(1200),
+            ..Default::default()
+        }
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn test_msgpack_round_trip() {
+        let article = article();
+        let bytes = article.to_msgpack().unwrap();
+        assert_eq!(Article::from_msgpack(&bytes).unwrap(), article);
+        assert!(Article::from_msgpack(&bytes[..bytes.len() / 2]).is_err());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json_round_trip_checks_version() {
+        let article = article();
+        let json = article.to_json().unwrap();
+        assert!(json.starts_with("{\"schema_version\":1,"));
+        assert_eq!(Article::from_json(&json).unwrap(), article);
+
+        let newer = json.replace("\"schema_version\":1", "\"schema_version\":99");
+        assert!(matches!(
+            Article::from_json(&newer),
+            Err(ReadabilityError::Serialization(_))
+        ));
+    }
+
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn test_cbor_round_trip() {
+        let article = article();
+        let bytes = article.to_cbor().unwrap();
+        assert_eq!(Article::from_cbor(&bytes).unwrap(), article);
+        assert!(Article::from_cbor(&bytes[..bytes.len() / 2]).is_err());
+    }
+}
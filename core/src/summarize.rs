@@ -0,0 +1,111 @@
+use std::collections::HashSet;
+
+use crate::readability::Article;
+use crate::text;
+
+/// Sentences shorter than this are usually headings, captions or bylines.
+const MIN_SENTENCE_WORDS: usize = 4;
+const DAMPING: f64 = 0.85;
+const ITERATIONS: usize = 50;
+const TOLERANCE: f64 = 1e-6;
+
+impl Article {
+    /// Pick the `n_sentences` most central sentences of the text content.
+    ///
+    /// Uses TextRank: sentences are ranked by PageRank over a graph weighted
+    /// by their word overlap. The summary runs fully offline and returns
+    /// sentences in their original order.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use readability_js::Readability;
+    ///
+    /// # let html = "<html><body><p>Some article text.</p></body></html>";
+    /// let reader = Readability::new()?;
+    /// let article = reader.parse(html)?;
+    /// for sentence in article.summarize(3) {
+    ///     println!("- {}", sentence);
+    /// }
+    /// # Ok::<(), readability_js::ReadabilityError>(())
+    /// ```
+    pub fn summarize(&self, n_sentences: usize) -> Vec<String> {
+        let sentences: Vec<String> = text::sentences(&self.text_content)
+            .into_iter()
+            .filter(|s| s.split_whitespace().count() >= MIN_SENTENCE_WORDS)
+            .collect();
+        if sentences.len() <= n_sentences {
+            return sentences;
+        }
+
+        let words: Vec<HashSet<String>> = sentences
+            .iter()
+            .map(|s| text::content_words(s).into_iter().collect())
+            .collect();
+        let scores = text_rank(&words);
+
+        let mut ranked: Vec<usize> = (0..sentences.len()).collect();
+        ranked.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]).then(a.cmp(&b)));
+        ranked.truncate(n_sentences);
+        ranked.sort_unstable();
+        ranked.into_iter().map(|i| sentences[i].clone()).collect()
+    }
+}
+
+/// PageRank scores of sentences, using the similarity measure of the
+/// original TextRank paper.
+fn text_rank(words: &[HashSet<String>]) -> Vec<f64> {
+    let n = words.len();
+    let mut weights = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        for j in i + 1..n {
+            let common = words[i].intersection(&words[j]).count() as f64;
+            let norm = (words[i].len() as f64).ln() + (words[j].len() as f64).ln();
+            if common > 0.0 && norm > 0.0 {
+                weights[i][j] = common / norm;
+                weights[j][i] = common / norm;
+            }
+        }
+    }
+    let out_weight: Vec<f64> = weights.iter().map(|row| row.iter().sum()).collect();
+
+    let mut scores = vec![1.0; n];
+    for _ in 0..ITERATIONS {
+        let next: Vec<f64> = (0..n)
+            .map(|i| {
+                let incoming: f64 = (0..n)
+                    .filter(|&j| out_weight[j] > 0.0)
+                    .map(|j| weights[j][i] / out_weight[j] * scores[j])
+                    .sum();
+                (1.0 - DAMPING) + DAMPING * incoming
+            })
+            .collect();
+        let delta: f64 = next.iter().zip(&scores).map(|(a, b)| (a - b).abs()).sum();
+        scores = next;
+        if delta < TOLERANCE {
+            break;
+        }
+    }
+    scores
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_keeps_central_sentences_in_order() {
+        let article = Article {
+            text_content: "Rust compiles memory safe programs quickly. \
+                The weather was nice on that day. \
+                Memory safe programs in Rust avoid data races. \
+                Rust programs use ownership for memory safety."
+                .into(),
+            ..Default::default()
+        };
+        let summary = article.summarize(2);
+        assert_eq!(summary.len(), 2);
+        assert!(summary.iter().all(|s| s.contains("Rust")));
+        assert!(summary[0].starts_with("Rust compiles") || summary[0].starts_with("Memory"));
+    }
+}
@@ -0,0 +1,229 @@
+/// Collapse runs of whitespace into single spaces.
+pub(crate) fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Whitespace cleanup of `text_content`, from the options of the same names.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Whitespace {
+    pub(crate) collapse: bool,
+    pub(crate) trim_lines: bool,
+    pub(crate) normalize_spaces: bool,
+}
+
+impl Whitespace {
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.collapse || self.trim_lines || self.normalize_spaces
+    }
+
+    pub(crate) fn apply(&self, text: &str) -> String {
+        let text: String = if self.normalize_spaces {
+            text.chars()
+                .map(|c| if is_unicode_space(c) { ' ' } else { c })
+                .collect()
+        } else {
+            text.to_string()
+        };
+        let mut lines = Vec::new();
+        for line in text.split('\n') {
+            let mut line = line.strip_suffix('\r').unwrap_or(line).to_string();
+            if self.trim_lines {
+                line = line.trim_start_matches([' ', '\t']).to_string();
+            }
+            if self.collapse {
+                let indent = line.len() - line.trim_start_matches([' ', '\t']).len();
+                let words: Vec<_> = line[indent..]
+                    .split([' ', '\t'])
+                    .filter(|w| !w.is_empty())
+                    .collect();
+                // Keep a sign of indentation unless trim_lines removed it
+                let indent = if indent > 0 && !words.is_empty() {
+                    " "
+                } else {
+                    ""
+                };
+                line = format!("{}{}", indent, words.join(" "));
+                if line.is_empty() && lines.last().is_none_or(|last: &String| last.is_empty()) {
+                    continue;
+                }
+            }
+            lines.push(line);
+        }
+        if self.collapse {
+            while lines.last().is_some_and(|last| last.is_empty()) {
+                lines.pop();
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+/// Spaces other than the plain one, such as the non-breaking space, but not
+/// tabs or line breaks.
+fn is_unicode_space(c: char) -> bool {
+    c.is_whitespace()
+        && !c.is_ascii_whitespace()
+        && !matches!(c, '\u{85}' | '\u{2028}' | '\u{2029}')
+}
+
+/// Letters looked at by [`is_mostly_rtl`], enough to tell the script.
+const DIRECTION_SAMPLE: usize = 2000;
+
+/// Whether most letters of `text` are written right-to-left, as in Arabic,
+/// Hebrew, Persian or Urdu.
+///
+/// Only the first letters are sampled; digits, punctuation and whitespace
+/// have no direction of their own and are skipped.
+pub(crate) fn is_mostly_rtl(text: &str) -> bool {
+    let (mut rtl, mut ltr) = (0, 0);
+    for c in text
+        .chars()
+        .filter(|c| c.is_alphabetic())
+        .take(DIRECTION_SAMPLE)
+    {
+        if is_rtl(c) {
+            rtl += 1;
+        } else {
+            ltr += 1;
+        }
+    }
+    rtl > ltr
+}
+
+/// Letters of the right-to-left scripts: Hebrew, Arabic, Syriac, Thaana,
+/// N'Ko, Samaritan, Mandaic and their presentation forms.
+fn is_rtl(c: char) -> bool {
+    matches!(c,
+        '\u{0590}'..='\u{08FF}'
+        | '\u{FB1D}'..='\u{FDFF}'
+        | '\u{FE70}'..='\u{FEFF}'
+        | '\u{10800}'..='\u{10FFF}'
+        | '\u{1E800}'..='\u{1EFFF}')
+}
+
+/// Build a teaser of at most `max_chars` characters from the start of `text`.
+///
+/// Prefers cutting after the last complete sentence that fits; when the first
+/// sentence alone is too long, cuts at a word boundary and appends an ellipsis.
+pub(crate) fn excerpt(text: &str, max_chars: usize) -> Option<String> {
+    let text = collapse_whitespace(text);
+    if text.is_empty() || max_chars == 0 {
+        return None;
+    }
+    if text.chars().count() <= max_chars {
+        return Some(text);
+    }
+
+    let end = text
+        .char_indices()
+        .nth(max_chars)
+        .map_or(text.len(), |(i, _)| i);
+    let head = &text[..end];
+
+    let sentence_end = head
+        .char_indices()
+        .rev()
+        .find(|&(i, c)| {
+            matches!(c, '.' | '!' | '?' | '。' | '！' | '？')
+                && text[i + c.len_utf8()..].starts_with(' ')
+        })
+        .map(|(i, c)| i + c.len_utf8());
+    if let Some(sentence_end) = sentence_end {
+        return Some(head[..sentence_end].to_string());
+    }
+
+    let word_end = head.rfind(' ').unwrap_or(head.len());
+    Some(format!("{}…", head[..word_end].trim_end()))
+}
+
+/// Common English words that carry no topic on their own.
+#[cfg(any(feature = "summarize", feature = "keywords"))]
+pub(crate) const STOPWORDS: &[&str] = &[
+    "a", "about", "after", "all", "also", "an", "and", "any", "are", "as", "at", "be", "been",
+    "but", "by", "can", "could", "did", "do", "does", "for", "from", "had", "has", "have", "he",
+    "her", "his", "how", "i", "if", "in", "into", "is", "it", "its", "just", "more", "most", "my",
+    "no", "not", "of", "on", "one", "or", "our", "out", "she", "so", "some", "than", "that", "the",
+    "their", "them", "then", "there", "these", "they", "this", "to", "up", "was", "we", "were",
+    "what", "when", "which", "who", "will", "with", "would", "you", "your",
+];
+
+/// Lower-cased words of `text`, without punctuation or stopwords.
+#[cfg(feature = "summarize")]
+pub(crate) fn content_words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .map(|word| word.trim_matches('\'').to_lowercase())
+        .filter(|word| !word.is_empty() && !STOPWORDS.contains(&word.as_str()))
+        .collect()
+}
+
+/// Split `text` into sentences, treating line breaks as hard boundaries.
+#[cfg(feature = "summarize")]
+pub(crate) fn sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    for line in text.lines() {
+        let mut current = String::new();
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            current.push(c);
+            let at_boundary = chars.peek().is_none_or(|next| next.is_whitespace());
+            if matches!(c, '.' | '!' | '?' | '。' | '！' | '？') && at_boundary {
+                sentences.push(collapse_whitespace(&current));
+                current.clear();
+            }
+        }
+        sentences.push(collapse_whitespace(&current));
+    }
+    sentences.retain(|s| !s.is_empty());
+    sentences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_excerpt_cuts_at_sentence_boundary() {
+        let text = "  First sentence here.\n\n Second one is longer than the limit. Third.";
+        assert_eq!(excerpt(text, 40).unwrap(), "First sentence here.");
+        assert_eq!(excerpt(text, 200).unwrap(), collapse_whitespace(text));
+    }
+
+    #[test]
+    fn test_whitespace() {
+        let text = "\n\n  Title\u{a0}\u{a0}here\n\n\n\t\tBody  text \r\n    more\n\n";
+        let collapse = Whitespace {
+            collapse: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            collapse.apply(text),
+            " Title\u{a0}\u{a0}here\n\n Body text\n more"
+        );
+        let all = Whitespace {
+            collapse: true,
+            trim_lines: true,
+            normalize_spaces: true,
+        };
+        assert_eq!(all.apply(text), "Title here\n\nBody text\nmore");
+        let trim = Whitespace {
+            trim_lines: true,
+            ..Default::default()
+        };
+        assert_eq!(trim.apply("  a  b\n\tc"), "a  b\nc");
+    }
+
+    #[test]
+    fn test_is_mostly_rtl() {
+        assert!(is_mostly_rtl("مرحبا بالعالم، هذا مقال عن Rust 2024."));
+        assert!(is_mostly_rtl("שלום עולם"));
+        assert!(!is_mostly_rtl("Hello world, with a quote: مرحبا"));
+        assert!(!is_mostly_rtl("12, 34 — !"));
+    }
+
+    #[test]
+    fn test_excerpt_falls_back_to_word_boundary() {
+        let text = "one two three four five six seven";
+        assert_eq!(excerpt(text, 12).unwrap(), "one two…");
+        assert_eq!(excerpt("   ", 10), None);
+    }
+}
@@ -0,0 +1,117 @@
+/// Separators sites put between the headline and their name or section.
+const SEPARATORS: [&str; 9] = [
+    " | ", " - ", " – ", " — ", " :: ", " · ", " • ", " » ", " / ",
+];
+
+/// Headlines shorter than this are unlikely to stand on their own, so
+/// segments are only dropped on a guess when the first one is at least this long.
+const MIN_HEADLINE_WORDS: usize = 3;
+
+/// Strip site-name and section segments from a title.
+///
+/// "Great Story – Example News | Politics" becomes "Great Story". With a
+/// known `site_name`, the segment matching it and everything after it are
+/// dropped, or just that segment when it leads the title. Without a match,
+/// the first segment is kept if it looks like a full headline.
+pub(crate) fn clean_title(title: &str, site_name: Option<&str>) -> String {
+    let segments = split_segments(title);
+    if segments.len() < 2 {
+        return title.trim().to_string();
+    }
+
+    let site = site_name.map(normalize).filter(|s| !s.is_empty());
+    let is_site = |segment: &str| {
+        let segment = normalize(segment);
+        site.as_ref().is_some_and(|site| {
+            !segment.is_empty() && (site.contains(&segment) || segment.contains(site))
+        })
+    };
+
+    if let Some(pos) = segments.iter().position(|s| is_site(s)) {
+        let kept = if pos == 0 {
+            &segments[1..]
+        } else {
+            &segments[..pos]
+        };
+        return join_segments(title, kept);
+    }
+
+    if segments[0].split_whitespace().count() >= MIN_HEADLINE_WORDS {
+        return segments[0].trim().to_string();
+    }
+    title.trim().to_string()
+}
+
+/// Split on any of the separators, remembering byte ranges into `title`.
+fn split_segments(title: &str) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    while i < title.len() {
+        if let Some(sep) = SEPARATORS.iter().find(|sep| title[i..].starts_with(*sep)) {
+            segments.push(&title[start..i]);
+            i += sep.len();
+            start = i;
+        } else {
+            i += title[i..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    segments.push(&title[start..]);
+    segments.retain(|s| !s.trim().is_empty());
+    segments
+}
+
+/// Rejoin consecutive segments with the separators they originally had.
+fn join_segments(title: &str, segments: &[&str]) -> String {
+    match (segments.first(), segments.last()) {
+        (Some(first), Some(last)) => {
+            let start = first.as_ptr() as usize - title.as_ptr() as usize;
+            let end = last.as_ptr() as usize - title.as_ptr() as usize + last.len();
+            title[start..end].trim().to_string()
+        }
+        _ => title.trim().to_string(),
+    }
+}
+
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strips_site_name_and_trailing_segments() {
+        assert_eq!(
+            clean_title(
+                "Great Story – Example News | Politics",
+                Some("Example News")
+            ),
+            "Great Story"
+        );
+        assert_eq!(
+            clean_title(
+                "Example News: Home | Great Story Here",
+                Some("Example News: Home")
+            ),
+            "Great Story Here"
+        );
+    }
+
+    #[test]
+    fn test_keeps_short_titles_without_site_match() {
+        assert_eq!(clean_title("Rust - Wikipedia", None), "Rust - Wikipedia");
+        assert_eq!(
+            clean_title("How we shipped it | Blog", None),
+            "How we shipped it"
+        );
+        assert_eq!(
+            clean_title("Plain headline", Some("Example")),
+            "Plain headline"
+        );
+    }
+}
@@ -0,0 +1,249 @@
+//! Reading articles out of WARC web archives.
+
+use std::io::{self, BufRead, Read};
+
+use crate::readability::{Article, Readability, Result};
+
+/// Iterator over the articles in a WARC file, from
+/// [`Readability::warc_articles`].
+///
+/// Yields `(target_uri, article)` for every HTML response record. Records
+/// that fail extraction yield their error and iteration continues; a
+/// malformed or truncated archive yields one I/O error and then ends.
+pub struct WarcArticles<'a, R> {
+    reader: &'a Readability,
+    input: R,
+    done: bool,
+}
+
+/// One WARC record: its header fields and content block.
+struct Record {
+    headers: Vec<(String, String)>,
+    block: Vec<u8>,
+}
+
+impl Readability {
+    /// Extract articles from every HTML response in a WARC archive.
+    ///
+    /// The input must be uncompressed; wrap `.warc.gz` files, such as
+    /// Common Crawl segments, in a multi-member gzip decoder like
+    /// `flate2::read::MultiGzDecoder`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use std::fs::File;
+    /// use std::io::BufReader;
+    /// use readability_js::Readability;
+    ///
+    /// let reader = Readability::new()?;
+    /// let warc = BufReader::new(File::open("crawl.warc")?);
+    /// for item in reader.warc_articles(warc) {
+    ///     match item {
+    ///         Ok((url, article)) => println!("{}: {}", url, article.title),
+    ///         Err(e) => eprintln!("skipped: {}", e),
+    ///     }
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn warc_articles<R: BufRead>(&self, input: R) -> WarcArticles<'_, R> {
+        WarcArticles {
+            reader: self,
+            input,
+            done: false,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for WarcArticles<'_, R> {
+    type Item = Result<(String, Article)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            let record = match read_record(&mut self.input) {
+                Ok(Some(record)) => record,
+                Ok(None) => return None,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e.into()));
+                }
+            };
+            if !record
+                .header("WARC-Type")
+                .is_some_and(|t| t.eq_ignore_ascii_case("response"))
+            {
+                continue;
+            }
+            let Some(url) = record.header("WARC-Target-URI") else {
+                continue;
+            };
+            let Some((content_type, body)) = http_html_body(&record.block) else {
+                continue;
+            };
+
+            let url = url.trim_matches(['<', '>']).to_string();
+            let article =
+                self.reader
+                    .parse_with_content_type(&body, content_type.as_deref(), Some(&url));
+            return Some(article.map(|article| (url, article)));
+        }
+        None
+    }
+}
+
+impl Record {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Read the next record, or `None` at the end of the input.
+fn read_record(input: &mut impl BufRead) -> io::Result<Option<Record>> {
+    // Records are separated by blank lines
+    let mut version = String::new();
+    loop {
+        version.clear();
+        if input.read_line(&mut version)? == 0 {
+            return Ok(None);
+        }
+        if !version.trim().is_empty() {
+            break;
+        }
+    }
+    if !version.starts_with("WARC/") {
+        return Err(invalid(format!(
+            "expected a WARC record, found {:?}",
+            version.trim()
+        )));
+    }
+
+    let mut headers = Vec::new();
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            return Err(invalid("truncated WARC header".into()));
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            headers.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    let record = Record {
+        headers,
+        block: Vec::new(),
+    };
+    let length: u64 = record
+        .header("Content-Length")
+        .and_then(|l| l.parse().ok())
+        .ok_or_else(|| invalid("WARC record without a valid Content-Length".into()))?;
+    let mut block = Vec::new();
+    input.take(length).read_to_end(&mut block)?;
+    if (block.len() as u64) < length {
+        return Err(invalid("truncated WARC record".into()));
+    }
+    Ok(Some(Record { block, ..record }))
+}
+
+/// Split an HTTP response into its `Content-Type` and body, if it is HTML.
+fn http_html_body(block: &[u8]) -> Option<(Option<String>, Vec<u8>)> {
+    let split = block.windows(4).position(|w| w == b"\r\n\r\n")?;
+    let head = String::from_utf8_lossy(&block[..split]);
+    let body = &block[split + 4..];
+
+    let mut lines = head.lines();
+    let status = lines.next()?;
+    if !status.starts_with("HTTP/") || status.split_whitespace().nth(1) != Some("200") {
+        return None;
+    }
+    let mut content_type = None;
+    let mut chunked = false;
+    for (key, value) in lines.filter_map(|l| l.split_once(':')) {
+        let value = value.trim();
+        if key.trim().eq_ignore_ascii_case("Content-Type") {
+            content_type = Some(value.to_string());
+        } else if key.trim().eq_ignore_ascii_case("Transfer-Encoding") {
+            chunked = value.eq_ignore_ascii_case("chunked");
+        }
+    }
+
+    let mime = content_type.as_deref().map(|c| {
+        c.split(';')
+            .next()
+            .unwrap_or("")
+            .trim()
+            .to_ascii_lowercase()
+    });
+    if !matches!(
+        mime.as_deref(),
+        Some("text/html" | "application/xhtml+xml") | None
+    ) {
+        return None;
+    }
+
+    let body = if chunked {
+        dechunk(body)
+    } else {
+        body.to_vec()
+    };
+    Some((content_type, body))
+}
+
+/// Undo HTTP chunked transfer encoding, keeping whatever chunks are intact.
+fn dechunk(mut body: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(body.len());
+    while let Some(end) = body.windows(2).position(|w| w == b"\r\n") {
+        let size = String::from_utf8_lossy(&body[..end]);
+        let size = size.split(';').next().unwrap_or("").trim();
+        let Ok(size) = usize::from_str_radix(size, 16) else {
+            break;
+        };
+        body = &body[end + 2..];
+        if size == 0 || size > body.len() {
+            break;
+        }
+        out.extend_from_slice(&body[..size]);
+        body = body.get(size + 2..).unwrap_or_default();
+    }
+    out
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(warc_type: &str, block: &str) -> String {
+        format!(
+            "WARC/1.0\r\nWARC-Type: {}\r\nWARC-Target-URI: <https://example.com/a>\r\nContent-Length: {}\r\n\r\n{}\r\n\r\n",
+            warc_type,
+            block.len(),
+            block
+        )
+    }
+
+    #[test]
+    fn test_reads_html_response_records() {
+        let response = "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nTransfer-Encoding: chunked\r\n\r\n5\r\n<p>Hi\r\n4\r\n</p>\r\n0\r\n\r\n";
+        let warc = record("request", "GET /a HTTP/1.1\r\n\r\n") + &record("response", response);
+        let mut input = warc.as_bytes();
+
+        let request = read_record(&mut input).unwrap().unwrap();
+        assert_eq!(request.header("warc-type"), Some("request"));
+        let response = read_record(&mut input).unwrap().unwrap();
+        let (content_type, body) = http_html_body(&response.block).unwrap();
+        assert_eq!(content_type.as_deref(), Some("text/html; charset=utf-8"));
+        assert_eq!(body, b"<p>Hi</p>");
+        assert!(read_record(&mut input).unwrap().is_none());
+    }
+}
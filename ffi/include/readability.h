@@ -0,0 +1,38 @@
+/*
+ * C bindings for readability-js.
+ *
+ * Link against libreadability (built with `cargo build -p readability-js-ffi --release`).
+ * All strings are NUL-terminated UTF-8.
+ */
+#ifndef READABILITY_H
+#define READABILITY_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/*
+ * Extract the readable article from `html`.
+ *
+ * `url` is the page URL used to resolve relative links, or NULL.
+ * `options_json` is a JSON object with ReadabilityOptions fields
+ * (e.g. {"char_threshold": 100}), or NULL for defaults.
+ *
+ * Returns a JSON object owned by the caller, to be released with
+ * readability_string_free(). On success it is the serialized Article
+ * (including "schema_version"); on failure it is {"error": "..."}.
+ * Returns NULL only if memory for the result could not be allocated.
+ *
+ * Each thread lazily creates its own extractor, so the first call on a
+ * thread is slower. Calls on different threads may run concurrently.
+ */
+char *readability_extract(const char *html, const char *url, const char *options_json);
+
+/* Release a string returned by this library. Passing NULL is a no-op. */
+void readability_string_free(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* READABILITY_H */
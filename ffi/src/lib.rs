@@ -0,0 +1,73 @@
+//! C ABI for readability-js.
+//!
+//! See `include/readability.h` for the interface documentation.
+
+use std::ffi::{CStr, CString, c_char};
+
+use readability_js::{Readability, ReadabilityError, ReadabilityOptions};
+
+/// Extract the readable article from `html` and return it as JSON.
+///
+/// # Safety
+///
+/// `html` must be a valid NUL-terminated string. `url` and `options_json`
+/// must each be NULL or a valid NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn readability_extract(
+    html: *const c_char,
+    url: *const c_char,
+    options_json: *const c_char,
+) -> *mut c_char {
+    // SAFETY: upheld by the caller per the function contract
+    let result = unsafe { extract(html, url, options_json) };
+    let json = result.unwrap_or_else(|message| serde_json::json!({ "error": message }).to_string());
+    CString::new(json).map_or(std::ptr::null_mut(), CString::into_raw)
+}
+
+/// Release a string returned by [`readability_extract`].
+///
+/// # Safety
+///
+/// `s` must be NULL or a pointer returned by this library that has not
+/// been freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn readability_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        // SAFETY: `s` was created by `CString::into_raw` per the contract
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+unsafe fn extract(
+    html: *const c_char,
+    url: *const c_char,
+    options_json: *const c_char,
+) -> Result<String, String> {
+    // SAFETY: upheld by the caller of `readability_extract`
+    let html = unsafe { optional_str(html, "html")? }.ok_or("html must not be NULL")?;
+    let url = unsafe { optional_str(url, "url")? };
+    let options = match unsafe { optional_str(options_json, "options_json")? } {
+        Some(json) => Some(
+            serde_json::from_str::<ReadabilityOptions>(json)
+                .map_err(|e| format!("Invalid options: {}", e))?,
+        ),
+        None => None,
+    };
+
+    // Readability is !Send, so every calling thread gets its own instance
+    Readability::thread_local()
+        .and_then(|reader| reader.parse_with_options(html, url, options))
+        .and_then(|article| article.to_json())
+        .map_err(|e: ReadabilityError| e.to_string())
+}
+
+unsafe fn optional_str<'a>(ptr: *const c_char, name: &str) -> Result<Option<&'a str>, String> {
+    if ptr.is_null() {
+        return Ok(None);
+    }
+    // SAFETY: non-null pointers are valid C strings per the caller contract
+    unsafe { CStr::from_ptr(ptr) }
+        .to_str()
+        .map(Some)
+        .map_err(|_| format!("{} is not valid UTF-8", name))
+}
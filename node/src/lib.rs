@@ -0,0 +1,252 @@
+//! Node.js bindings for readability-js.
+//!
+//! ```js
+//! const { Readability } = require("readability-js");
+//!
+//! const reader = new Readability();
+//! const article = reader.parse(html, "https://example.com/article");
+//! console.log(article.title);
+//! ```
+
+use napi::{Error, Result, Status};
+use napi_derive::napi;
+
+/// Options for [`Readability::parse`], mirroring `ReadabilityOptions`.
+#[napi(object)]
+#[derive(Default)]
+pub struct ParseOptions {
+    pub max_elems_to_parse: Option<u32>,
+    pub nb_top_candidates: Option<u32>,
+    pub char_threshold: Option<u32>,
+    pub classes_to_preserve: Option<Vec<String>>,
+    pub keep_classes: Option<bool>,
+    pub disable_jsonld: Option<bool>,
+    pub link_density_modifier: Option<f64>,
+    pub extract_comments: Option<bool>,
+    pub clean_title: Option<bool>,
+    pub prefer_h1_title: Option<bool>,
+    pub generate_excerpt: Option<u32>,
+    pub strip_data_uris_above: Option<u32>,
+    pub max_data_uri_bytes: Option<u32>,
+    pub max_output_bytes: Option<u32>,
+    /// "pretty" or "minified"
+    pub html_format: Option<String>,
+    pub keep_inline_styles: Option<bool>,
+    pub keep_asides_above: Option<u32>,
+    pub convert_divs_to_paragraphs: Option<bool>,
+    pub collect_stats: Option<bool>,
+    pub record_removed: Option<bool>,
+    /// "normalize" or "strip"
+    pub link_rel: Option<String>,
+    pub unlink_sponsored: Option<bool>,
+    pub collapse_whitespace: Option<bool>,
+    pub trim_lines: Option<bool>,
+    pub normalize_spaces: Option<bool>,
+}
+
+impl TryFrom<ParseOptions> for readability_js::ReadabilityOptions {
+    type Error = Error;
+
+    fn try_from(o: ParseOptions) -> Result<Self> {
+        let html_format = match o.html_format.as_deref() {
+            None => None,
+            Some("pretty") => Some(readability_js::HtmlFormat::Pretty),
+            Some("minified") => Some(readability_js::HtmlFormat::Minified),
+            Some(other) => {
+                return Err(invalid_choice("htmlFormat", other, &["pretty", "minified"]));
+            }
+        };
+        let link_rel = match o.link_rel.as_deref() {
+            None => None,
+            Some("normalize") => Some(readability_js::LinkRel::Normalize),
+            Some("strip") => Some(readability_js::LinkRel::Strip),
+            Some(other) => return Err(invalid_choice("linkRel", other, &["normalize", "strip"])),
+        };
+        Ok(readability_js::ReadabilityOptions {
+            max_elems_to_parse: o.max_elems_to_parse.map(|v| v as usize),
+            nb_top_candidates: o.nb_top_candidates.map(|v| v as usize),
+            char_threshold: o.char_threshold.map(|v| v as usize),
+            classes_to_preserve: o.classes_to_preserve,
+            keep_classes: o.keep_classes,
+            disable_jsonld: o.disable_jsonld,
+            link_density_modifier: o.link_density_modifier.map(|v| v as f32),
+            extract_comments: o.extract_comments,
+            clean_title: o.clean_title,
+            prefer_h1_title: o.prefer_h1_title,
+            generate_excerpt: o.generate_excerpt.map(|v| v as usize),
+            strip_data_uris_above: o.strip_data_uris_above.map(|v| v as usize),
+            max_data_uri_bytes: o.max_data_uri_bytes.map(|v| v as usize),
+            max_output_bytes: o.max_output_bytes.map(|v| v as usize),
+            html_format,
+            keep_inline_styles: o.keep_inline_styles,
+            keep_asides_above: o.keep_asides_above.map(|v| v as usize),
+            convert_divs_to_paragraphs: o.convert_divs_to_paragraphs,
+            collect_stats: o.collect_stats,
+            record_removed: o.record_removed,
+            link_rel,
+            unlink_sponsored: o.unlink_sponsored,
+            collapse_whitespace: o.collapse_whitespace,
+            trim_lines: o.trim_lines,
+            normalize_spaces: o.normalize_spaces,
+        })
+    }
+}
+
+/// Reject an unknown value of a string option rather than silently ignoring it.
+fn invalid_choice(option: &str, value: &str, expected: &[&str]) -> Error {
+    Error::new(
+        Status::InvalidArg,
+        format!("{} must be one of {:?}, got {:?}", option, expected, value),
+    )
+}
+
+/// The extracted article, with the fields of the Rust `Article` in camelCase.
+#[napi(object)]
+pub struct Article {
+    pub title: String,
+    pub content: String,
+    pub text_content: String,
+    pub length: u32,
+    pub byline: Option<String>,
+    /// "ltr" or "rtl"
+    pub direction: Option<String>,
+    pub excerpt: Option<String>,
+    pub site_name: Option<String>,
+    pub language: Option<String>,
+    pub published_time: Option<String>,
+    pub modified_time: Option<String>,
+    pub quality: Option<f64>,
+    pub score: Option<f64>,
+    pub retained_ratio: Option<f64>,
+    pub word_count: Option<u32>,
+    pub section: Option<String>,
+    pub comments_html: Option<String>,
+    pub lead_image_url: Option<String>,
+    pub lead_image_width: Option<u32>,
+    pub lead_image_height: Option<u32>,
+    pub site_icon_url: Option<String>,
+    pub truncated: bool,
+    pub stats: Option<ExtractionStats>,
+    pub removed: Option<Vec<RemovedNode>>,
+}
+
+/// Per-phase timings, present when `collectStats` is set.
+#[napi(object)]
+pub struct ExtractionStats {
+    pub js_eval_ms: f64,
+    pub dom_parse_ms: f64,
+    pub grab_article_ms: f64,
+    pub serialize_ms: f64,
+    pub input_bytes: u32,
+    pub output_bytes: u32,
+}
+
+/// A node Readability removed, present when `recordRemoved` is set.
+#[napi(object)]
+pub struct RemovedNode {
+    pub tag: String,
+    pub class: Option<String>,
+    pub id: Option<String>,
+    pub reason: String,
+    pub text_preview: String,
+    pub pass: u32,
+}
+
+impl From<readability_js::Article> for Article {
+    fn from(a: readability_js::Article) -> Self {
+        Self {
+            title: a.title,
+            content: a.content,
+            text_content: a.text_content,
+            length: a.length,
+            byline: a.byline,
+            direction: a.direction.map(|d| match d {
+                readability_js::Direction::Ltr => "ltr".into(),
+                readability_js::Direction::Rtl => "rtl".into(),
+            }),
+            excerpt: a.excerpt,
+            site_name: a.site_name,
+            language: a.language,
+            published_time: a.published_time,
+            modified_time: a.modified_time,
+            quality: a.quality,
+            score: a.score,
+            retained_ratio: a.retained_ratio,
+            word_count: a.word_count,
+            section: a.section,
+            comments_html: a.comments_html,
+            lead_image_url: a.lead_image_url,
+            lead_image_width: a.lead_image_width,
+            lead_image_height: a.lead_image_height,
+            site_icon_url: a.site_icon_url,
+            truncated: a.truncated,
+            stats: a.stats.map(|s| ExtractionStats {
+                js_eval_ms: s.js_eval_ms,
+                dom_parse_ms: s.dom_parse_ms,
+                grab_article_ms: s.grab_article_ms,
+                serialize_ms: s.serialize_ms,
+                input_bytes: s.input_bytes as u32,
+                output_bytes: s.output_bytes as u32,
+            }),
+            removed: a.removed.map(|nodes| {
+                nodes
+                    .into_iter()
+                    .map(|n| RemovedNode {
+                        tag: n.tag,
+                        class: n.class,
+                        id: n.id,
+                        reason: n.reason,
+                        text_preview: n.text_preview,
+                        pass: n.pass,
+                    })
+                    .collect()
+            }),
+        }
+    }
+}
+
+/// Extracts articles from HTML. Reuse one instance for many documents.
+#[napi]
+pub struct Readability {
+    inner: readability_js::Readability,
+}
+
+#[napi]
+impl Readability {
+    #[napi(constructor)]
+    pub fn new() -> Result<Self> {
+        let inner = readability_js::Readability::new().map_err(to_napi_error)?;
+        Ok(Self { inner })
+    }
+
+    /// Extract the article from `html`, resolving links against `url` if given.
+    #[napi]
+    pub fn parse(
+        &self,
+        html: String,
+        url: Option<String>,
+        options: Option<ParseOptions>,
+    ) -> Result<Article> {
+        let options = options.map(TryInto::try_into).transpose()?;
+        self.inner
+            .parse_with_options(&html, url.as_deref(), options)
+            .map(Article::from)
+            .map_err(to_napi_error)
+    }
+
+    /// Whether `html` likely contains an article worth extracting.
+    #[napi]
+    pub fn is_probably_readerable(&self, html: String) -> Result<bool> {
+        self.inner
+            .is_probably_readerable(&html)
+            .map_err(to_napi_error)
+    }
+}
+
+fn to_napi_error(e: readability_js::ReadabilityError) -> Error {
+    let status = match e {
+        readability_js::ReadabilityError::InvalidOptions(_) => Status::InvalidArg,
+        _ => Status::GenericFailure,
+    };
+    Error::new(status, e.to_string())
+}
@@ -0,0 +1,208 @@
+//! Python bindings for readability-js.
+//!
+//! ```python
+//! import readability_js
+//!
+//! reader = readability_js.Readability()
+//! article = reader.parse(html, url="https://example.com/article")
+//! print(article.title)
+//! ```
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+
+create_exception!(
+    readability_js,
+    ReadabilityError,
+    PyException,
+    "Raised when a document cannot be parsed or has no readable content."
+);
+
+// Parsing runs with the GIL released on whichever thread calls in, and the
+// engine is !Send, so each thread keeps its own instance.
+fn with_engine<T>(
+    f: impl FnOnce(&readability_core::Readability) -> Result<T, readability_core::ReadabilityError>,
+) -> Result<T, String> {
+    readability_core::Readability::thread_local()
+        .and_then(|engine| f(&engine))
+        .map_err(|e| e.to_string())
+}
+
+/// Extracts articles from HTML. Reuse one instance for many documents.
+#[pyclass(frozen)]
+struct Readability {
+    char_threshold: Option<usize>,
+    keep_classes: Option<bool>,
+}
+
+#[pymethods]
+impl Readability {
+    #[new]
+    #[pyo3(signature = (*, char_threshold=None, keep_classes=None))]
+    fn new(
+        py: Python<'_>,
+        char_threshold: Option<usize>,
+        keep_classes: Option<bool>,
+    ) -> PyResult<Self> {
+        // Fail early if the engine cannot start, rather than on the first parse
+        py.detach(|| with_engine(|_| Ok(())))
+            .map_err(ReadabilityError::new_err)?;
+        Ok(Self {
+            char_threshold,
+            keep_classes,
+        })
+    }
+
+    /// Extract the article from `html`, resolving links against `url` if given.
+    ///
+    /// The GIL is released while parsing, so other Python threads keep running.
+    #[pyo3(signature = (html, url=None))]
+    fn parse(&self, py: Python<'_>, html: String, url: Option<String>) -> PyResult<Article> {
+        let mut options = readability_core::ReadabilityOptions::new();
+        if let Some(val) = self.char_threshold {
+            options = options.char_threshold(val);
+        }
+        if let Some(val) = self.keep_classes {
+            options = options.keep_classes(val);
+        }
+
+        py.detach(|| {
+            with_engine(|engine| engine.parse_with_options(&html, url.as_deref(), Some(options)))
+        })
+        .map(Article::from)
+        .map_err(ReadabilityError::new_err)
+    }
+
+    /// Whether `html` likely contains an article worth extracting.
+    fn is_probably_readerable(&self, py: Python<'_>, html: String) -> PyResult<bool> {
+        py.detach(|| with_engine(|engine| engine.is_probably_readerable(&html)))
+            .map_err(ReadabilityError::new_err)
+    }
+}
+
+/// An extracted article, with the same fields as the Rust `Article`.
+#[pyclass(frozen, get_all)]
+struct Article {
+    title: String,
+    content: String,
+    text_content: String,
+    length: u32,
+    byline: Option<String>,
+    /// "ltr", "rtl" or None
+    direction: Option<&'static str>,
+    excerpt: Option<String>,
+    site_name: Option<String>,
+    language: Option<String>,
+    published_time: Option<String>,
+    modified_time: Option<String>,
+    quality: Option<f64>,
+    score: Option<f64>,
+    retained_ratio: Option<f64>,
+    word_count: Option<u32>,
+    section: Option<String>,
+    comments_html: Option<String>,
+    lead_image_url: Option<String>,
+    lead_image_width: Option<u32>,
+    lead_image_height: Option<u32>,
+    site_icon_url: Option<String>,
+    truncated: bool,
+    stats: Option<ExtractionStats>,
+    removed: Option<Vec<RemovedNode>>,
+}
+
+/// Per-phase timings of one extraction, with the same fields as the Rust
+/// `ExtractionStats`.
+#[pyclass(frozen, get_all)]
+#[derive(Clone)]
+struct ExtractionStats {
+    js_eval_ms: f64,
+    dom_parse_ms: f64,
+    grab_article_ms: f64,
+    serialize_ms: f64,
+    input_bytes: usize,
+    output_bytes: usize,
+}
+
+/// A node Readability removed while extracting, with the same fields as the
+/// Rust `RemovedNode`.
+#[pyclass(frozen, get_all)]
+#[derive(Clone)]
+struct RemovedNode {
+    tag: String,
+    class: Option<String>,
+    id: Option<String>,
+    reason: String,
+    text_preview: String,
+    pass: u32,
+}
+
+#[pymethods]
+impl Article {
+    fn __repr__(&self) -> String {
+        format!("Article(title={:?}, length={})", self.title, self.length)
+    }
+}
+
+impl From<readability_core::Article> for Article {
+    fn from(a: readability_core::Article) -> Self {
+        Self {
+            title: a.title,
+            content: a.content,
+            text_content: a.text_content,
+            length: a.length,
+            byline: a.byline,
+            direction: a.direction.map(|d| match d {
+                readability_core::Direction::Ltr => "ltr",
+                readability_core::Direction::Rtl => "rtl",
+            }),
+            excerpt: a.excerpt,
+            site_name: a.site_name,
+            language: a.language,
+            published_time: a.published_time,
+            modified_time: a.modified_time,
+            quality: a.quality,
+            score: a.score,
+            retained_ratio: a.retained_ratio,
+            word_count: a.word_count,
+            section: a.section,
+            comments_html: a.comments_html,
+            lead_image_url: a.lead_image_url,
+            lead_image_width: a.lead_image_width,
+            lead_image_height: a.lead_image_height,
+            site_icon_url: a.site_icon_url,
+            truncated: a.truncated,
+            stats: a.stats.map(|s| ExtractionStats {
+                js_eval_ms: s.js_eval_ms,
+                dom_parse_ms: s.dom_parse_ms,
+                grab_article_ms: s.grab_article_ms,
+                serialize_ms: s.serialize_ms,
+                input_bytes: s.input_bytes,
+                output_bytes: s.output_bytes,
+            }),
+            removed: a.removed.map(|nodes| {
+                nodes
+                    .into_iter()
+                    .map(|n| RemovedNode {
+                        tag: n.tag,
+                        class: n.class,
+                        id: n.id,
+                        reason: n.reason,
+                        text_preview: n.text_preview,
+                        pass: n.pass,
+                    })
+                    .collect()
+            }),
+        }
+    }
+}
+
+#[pymodule]
+fn readability_js(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Readability>()?;
+    m.add_class::<Article>()?;
+    m.add_class::<ExtractionStats>()?;
+    m.add_class::<RemovedNode>()?;
+    m.add("ReadabilityError", m.py().get_type::<ReadabilityError>())?;
+    Ok(())
+}